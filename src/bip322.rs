@@ -0,0 +1,250 @@
+use {
+  super::*,
+  bitcoin::{
+    blockdata::{opcodes, script},
+    hashes::{sha256, HashEngine},
+    schnorr::SchnorrSig,
+    secp256k1::{self, Secp256k1, XOnlyPublicKey},
+    util::sighash::{Prevouts, SighashCache},
+    EcdsaSig, PackedLockTime, PublicKey, Witness,
+  },
+};
+
+const TAG: &[u8] = b"BIP0322-signed-message";
+
+pub(crate) fn message_hash(message: &[u8]) -> sha256::Hash {
+  let tag = sha256::Hash::hash(TAG);
+
+  let mut engine = sha256::Hash::engine();
+  engine.input(&tag);
+  engine.input(&tag);
+  engine.input(message);
+
+  sha256::Hash::from_engine(engine)
+}
+
+/// The virtual transaction whose output is spent by the signature.
+pub(crate) fn to_spend(script_pubkey: &Script, message: &[u8]) -> Transaction {
+  Transaction {
+    version: 0,
+    lock_time: PackedLockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: OutPoint {
+        txid: Txid::all_zeros(),
+        vout: u32::MAX,
+      },
+      script_sig: script::Builder::new()
+        .push_int(0)
+        .push_slice(&message_hash(message))
+        .into_script(),
+      sequence: Sequence::ZERO,
+      witness: Witness::new(),
+    }],
+    output: vec![TxOut {
+      value: 0,
+      script_pubkey: script_pubkey.clone(),
+    }],
+  }
+}
+
+/// The virtual transaction whose input witness is the signature.
+pub(crate) fn to_sign(to_spend: &Transaction, witness: Witness) -> Transaction {
+  Transaction {
+    version: 0,
+    lock_time: PackedLockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: OutPoint {
+        txid: to_spend.txid(),
+        vout: 0,
+      },
+      script_sig: Script::new(),
+      sequence: Sequence::ZERO,
+      witness,
+    }],
+    output: vec![TxOut {
+      value: 0,
+      script_pubkey: script::Builder::new()
+        .push_opcode(opcodes::all::OP_RETURN)
+        .into_script(),
+    }],
+  }
+}
+
+/// Encode `witness` as a BIP-322 simple signature, which is the
+/// consensus-encoded witness of the `to_sign` transaction's input.
+pub(crate) fn encode_signature(witness: &Witness) -> String {
+  base64::encode(consensus::serialize(witness))
+}
+
+/// Verify a simple signature of `message` by the key controlling
+/// `script_pubkey`. Only P2TR key path and P2WPKH spends are supported.
+pub(crate) fn verify(script_pubkey: &Script, message: &[u8], signature: &str) -> Result {
+  let witness: Witness = consensus::deserialize(
+    &base64::decode(signature.trim()).context("signature is not valid base64")?,
+  )
+  .context("failed to decode signature")?;
+
+  let to_spend = to_spend(script_pubkey, message);
+  let to_sign = to_sign(&to_spend, witness.clone());
+
+  let secp256k1 = Secp256k1::verification_only();
+
+  let elements = witness.to_vec();
+
+  if script_pubkey.is_v1_p2tr() {
+    let [signature] = elements.as_slice() else {
+      bail!("P2TR signature must be a single witness element");
+    };
+
+    let signature = SchnorrSig::from_slice(signature).context("invalid schnorr signature")?;
+
+    let public_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..])?;
+
+    let signature_hash = SighashCache::new(&to_sign).taproot_key_spend_signature_hash(
+      0,
+      &Prevouts::All(&to_spend.output),
+      signature.hash_ty,
+    )?;
+
+    if secp256k1
+      .verify_schnorr(
+        &signature.sig,
+        &secp256k1::Message::from_slice(signature_hash.as_inner())?,
+        &public_key,
+      )
+      .is_err()
+    {
+      bail!("signature verification failed");
+    }
+  } else if script_pubkey.is_v0_p2wpkh() {
+    let [signature, public_key] = elements.as_slice() else {
+      bail!("P2WPKH signature must have two witness elements");
+    };
+
+    let signature = EcdsaSig::from_slice(signature).context("invalid ECDSA signature")?;
+
+    let public_key = PublicKey::from_slice(public_key).context("invalid public key")?;
+
+    if Some(script_pubkey)
+      != public_key
+        .wpubkey_hash()
+        .map(|hash| Script::new_v0_p2wpkh(&hash))
+        .as_ref()
+    {
+      bail!("public key does not match address");
+    }
+
+    let signature_hash = SighashCache::new(&to_sign).segwit_signature_hash(
+      0,
+      &Script::new_p2pkh(&public_key.pubkey_hash()),
+      0,
+      signature.hash_ty,
+    )?;
+
+    if secp256k1
+      .verify_ecdsa(
+        &secp256k1::Message::from_slice(signature_hash.as_inner())?,
+        &signature.sig,
+        &public_key.inner,
+      )
+      .is_err()
+    {
+      bail!("signature verification failed");
+    }
+  } else {
+    bail!("BIP-322 signatures are only supported for P2TR and P2WPKH outputs");
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Test vectors from BIP-322
+
+  fn p2wpkh() -> Script {
+    "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l"
+      .parse::<Address>()
+      .unwrap()
+      .script_pubkey()
+  }
+
+  fn p2tr() -> Script {
+    "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3"
+      .parse::<Address>()
+      .unwrap()
+      .script_pubkey()
+  }
+
+  #[test]
+  fn message_hashes() {
+    assert_eq!(
+      message_hash(b"").to_string(),
+      "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+    );
+    assert_eq!(
+      message_hash(b"Hello World").to_string(),
+      "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+    );
+  }
+
+  #[test]
+  fn transaction_ids() {
+    let empty = to_spend(&p2wpkh(), b"");
+    assert_eq!(
+      empty.txid().to_string(),
+      "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7"
+    );
+    assert_eq!(
+      to_sign(&empty, Witness::new()).txid().to_string(),
+      "1e9654e951a5ba44c8604c4de6c67fd78a27e81dcadcfe1edf638ba3aaebaed6"
+    );
+
+    let hello_world = to_spend(&p2wpkh(), b"Hello World");
+    assert_eq!(
+      hello_world.txid().to_string(),
+      "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b"
+    );
+    assert_eq!(
+      to_sign(&hello_world, Witness::new()).txid().to_string(),
+      "88737ae86f2077145f93cc4b153ae9a1cb8d56afa511988c149c5c8c9d93bddf"
+    );
+  }
+
+  #[test]
+  fn verify_p2wpkh() {
+    let signature = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+
+    verify(&p2wpkh(), b"", signature).unwrap();
+
+    assert_eq!(
+      verify(&p2wpkh(), b"Hello World", signature)
+        .unwrap_err()
+        .to_string(),
+      "signature verification failed"
+    );
+  }
+
+  #[test]
+  fn verify_p2tr() {
+    let signature = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+
+    verify(&p2tr(), b"Hello World", signature).unwrap();
+
+    assert_eq!(
+      verify(&p2tr(), b"Hello World!", signature)
+        .unwrap_err()
+        .to_string(),
+      "signature verification failed"
+    );
+
+    assert_eq!(
+      verify(&p2wpkh(), b"Hello World", signature)
+        .unwrap_err()
+        .to_string(),
+      "P2WPKH signature must have two witness elements"
+    );
+  }
+}
@@ -0,0 +1,82 @@
+use super::*;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) struct FeeRate(f64);
+
+impl FeeRate {
+  const MIN: f64 = 1.0;
+  const MAX: f64 = 1000.0;
+
+  pub(crate) fn fee(&self, vsize: usize) -> Amount {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    Amount::from_sat((self.0 * vsize as f64).ceil() as u64)
+  }
+}
+
+impl Display for FeeRate {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{} sat/vB", self.0)
+  }
+}
+
+impl TryFrom<f64> for FeeRate {
+  type Error = Error;
+
+  fn try_from(rate: f64) -> Result<Self, Self::Error> {
+    if !rate.is_finite() {
+      bail!("fee rate must be a finite number");
+    }
+
+    if rate < Self::MIN {
+      bail!("fee rate must be at least {} sat/vB", Self::MIN);
+    }
+
+    if rate > Self::MAX {
+      bail!("fee rate must be at most {} sat/vB", Self::MAX);
+    }
+
+    Ok(Self(rate))
+  }
+}
+
+impl FromStr for FeeRate {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::try_from(f64::from_str(s)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse() {
+    assert_eq!("1.0".parse::<FeeRate>().unwrap(), FeeRate(1.0));
+    assert_eq!("11.19".parse::<FeeRate>().unwrap(), FeeRate(11.19));
+    assert_eq!("1000".parse::<FeeRate>().unwrap(), FeeRate(1000.0));
+    assert!("foo".parse::<FeeRate>().is_err());
+    assert!("NaN".parse::<FeeRate>().is_err());
+    assert!("inf".parse::<FeeRate>().is_err());
+  }
+
+  #[test]
+  fn limits() {
+    assert_eq!(
+      "0.5".parse::<FeeRate>().unwrap_err().to_string(),
+      "fee rate must be at least 1 sat/vB"
+    );
+    assert_eq!(
+      "1000.5".parse::<FeeRate>().unwrap_err().to_string(),
+      "fee rate must be at most 1000 sat/vB"
+    );
+  }
+
+  #[test]
+  fn fee() {
+    assert_eq!(FeeRate(1.0).fee(100), Amount::from_sat(100));
+    assert_eq!(FeeRate(2.5).fee(100), Amount::from_sat(250));
+    assert_eq!(FeeRate(1.1).fee(101), Amount::from_sat(112));
+  }
+}
@@ -5,7 +5,10 @@ use {
   bitcoincore_rpc::{json::GetBlockHeaderResult, Auth, Client},
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
-  redb::{Database, ReadableTable, Table, TableDefinition, WriteStrategy, WriteTransaction},
+  redb::{
+    Database, MultimapTable, MultimapTableDefinition, ReadableMultimapTable, ReadableTable, Table,
+    TableDefinition, WriteStrategy, WriteTransaction,
+  },
   std::collections::HashMap,
   std::sync::atomic::{AtomicBool, Ordering},
 };
@@ -13,24 +16,35 @@ use {
 mod rtx;
 mod updater;
 
+const SCHEMA_VERSION: u64 = 4;
+
 type BlockHashArray = [u8; 32];
+type ContentHashArray = [u8; 32];
 type InscriptionIdArray = [u8; 32];
 type SatRangeArray = [u8; 11];
 type OutPointArray = [u8; 36];
 type SatPointArray = [u8; 44];
 
+const CONTENT_HASH_TO_SEQUENCE_NUMBERS: MultimapTableDefinition<&ContentHashArray, u64> =
+  MultimapTableDefinition::new("CONTENT_HASH_TO_SEQUENCE_NUMBERS");
 const HEIGHT_TO_BLOCK_HASH: TableDefinition<u64, &BlockHashArray> =
   TableDefinition::new("HEIGHT_TO_BLOCK_HASH");
 const INSCRIPTION_ID_TO_HEIGHT: TableDefinition<&InscriptionIdArray, u64> =
   TableDefinition::new("INSCRIPTION_ID_TO_HEIGHT");
 const INSCRIPTION_ID_TO_SATPOINT: TableDefinition<&InscriptionIdArray, &SatPointArray> =
   TableDefinition::new("INSCRIPTION_ID_TO_SATPOINT");
-const INSCRIPTION_NUMBER_TO_INSCRIPTION_ID: TableDefinition<u64, &InscriptionIdArray> =
+const INSCRIPTION_ID_TO_NUMBER: TableDefinition<&InscriptionIdArray, i64> =
+  TableDefinition::new("INSCRIPTION_ID_TO_NUMBER");
+const INSCRIPTION_NUMBER_TO_INSCRIPTION_ID: TableDefinition<i64, &InscriptionIdArray> =
   TableDefinition::new("INSCRIPTION_NUMBER_TO_INSCRIPTION_ID");
 const OUTPOINT_TO_SAT_RANGES: TableDefinition<&OutPointArray, &[u8]> =
   TableDefinition::new("OUTPOINT_TO_SAT_RANGES");
-const SATPOINT_TO_INSCRIPTION_ID: TableDefinition<&SatPointArray, &InscriptionIdArray> =
-  TableDefinition::new("SATPOINT_TO_INSCRIPTION_ID");
+const OUTPOINT_TO_SCRIPT_PUBKEY: TableDefinition<&OutPointArray, &[u8]> =
+  TableDefinition::new("OUTPOINT_TO_SCRIPT_PUBKEY");
+const SATPOINT_TO_INSCRIPTION_ID: MultimapTableDefinition<&SatPointArray, &InscriptionIdArray> =
+  MultimapTableDefinition::new("SATPOINT_TO_INSCRIPTION_ID");
+const SEQUENCE_NUMBER_TO_INSCRIPTION_ID: TableDefinition<u64, &InscriptionIdArray> =
+  TableDefinition::new("SEQUENCE_NUMBER_TO_INSCRIPTION_ID");
 const SAT_TO_INSCRIPTION_ID: TableDefinition<u64, &InscriptionIdArray> =
   TableDefinition::new("SAT_TO_INSCRIPTION_ID");
 const SAT_TO_SATPOINT: TableDefinition<u64, &SatPointArray> =
@@ -92,6 +106,7 @@ pub(crate) enum Statistic {
   OutputsTraversed = 0,
   Commits = 1,
   SatRanges = 2,
+  Schema = 3,
 }
 
 impl Statistic {
@@ -154,6 +169,17 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
 
 impl Index {
   pub(crate) fn open(options: &Options) -> Result<Self> {
+    Self::open_inner(options, false)
+  }
+
+  /// Open the index without requiring the Bitcoin Core cookie file, which is
+  /// removed when Bitcoin Core shuts down, for commands that only read the
+  /// index and never make RPC calls.
+  pub(crate) fn open_offline(options: &Options) -> Result<Self> {
+    Self::open_inner(options, true)
+  }
+
+  fn open_inner(options: &Options, offline: bool) -> Result<Self> {
     let rpc_url = options.rpc_url();
     let cookie_file = options.cookie_file()?;
 
@@ -169,7 +195,11 @@ impl Index {
       cookie_file.display()
     );
 
-    let auth = Auth::CookieFile(cookie_file);
+    let auth = if offline && !cookie_file.exists() {
+      Auth::None
+    } else {
+      Auth::CookieFile(cookie_file)
+    };
 
     let client = Client::new(&rpc_url, auth.clone()).context("failed to connect to RPC URL")?;
 
@@ -186,7 +216,23 @@ impl Index {
     };
 
     let database = match unsafe { redb::Database::builder().open_mmapped(&database_path) } {
-      Ok(database) => database,
+      Ok(database) => {
+        let schema_version = database
+          .begin_read()?
+          .open_table(STATISTIC_TO_COUNT)?
+          .get(&Statistic::Schema.key())?
+          .map(|x| x.value())
+          .unwrap_or(0);
+
+        if schema_version != SCHEMA_VERSION {
+          bail!(
+            "index at `{}` was built with an incompatible version of ord, consider deleting and rebuilding the index: index schema {schema_version}, ord schema {SCHEMA_VERSION}",
+            database_path.display()
+          );
+        }
+
+        database
+      }
       Err(redb::Error::Io(error)) if error.kind() == io::ErrorKind::NotFound => {
         let database = unsafe {
           Database::builder()
@@ -206,14 +252,19 @@ impl Index {
           tx
         };
 
+        tx.open_multimap_table(CONTENT_HASH_TO_SEQUENCE_NUMBERS)?;
         tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
         tx.open_table(INSCRIPTION_ID_TO_HEIGHT)?;
+        tx.open_table(INSCRIPTION_ID_TO_NUMBER)?;
         tx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
-        tx.open_table(SATPOINT_TO_INSCRIPTION_ID)?;
+        tx.open_table(OUTPOINT_TO_SCRIPT_PUBKEY)?;
+        tx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
+        tx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
         tx.open_table(SAT_TO_INSCRIPTION_ID)?;
         tx.open_table(SAT_TO_SATPOINT)?;
-        tx.open_table(STATISTIC_TO_COUNT)?;
+        tx.open_table(STATISTIC_TO_COUNT)?
+          .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
         tx.open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?;
 
         if options.index_sats {
@@ -381,6 +432,22 @@ impl Index {
     self.begin_read()?.block_count()
   }
 
+  pub(crate) fn block_hash(&self, height: Option<u64>) -> Result<Option<BlockHash>> {
+    let rtx = self.begin_read()?;
+
+    let Some(height) = height.or(rtx.height()?.map(|height| height.n())) else {
+      return Ok(None);
+    };
+
+    let height_to_block_hash = rtx.0.open_table(HEIGHT_TO_BLOCK_HASH)?;
+
+    let Some(hash) = height_to_block_hash.get(&height)? else {
+      return Ok(None);
+    };
+
+    Ok(Some(BlockHash::from_slice(hash.value())?))
+  }
+
   pub(crate) fn blocks(&self, take: usize) -> Result<Vec<(u64, BlockHash)>> {
     let mut blocks = Vec::new();
 
@@ -464,18 +531,24 @@ impl Index {
       return Ok(None);
     };
 
-    Ok(
-      self
-        .get_inscription_by_inscription_id(Txid::from_inner(*txid.value()))?
-        .map(|(inscription, _)| (InscriptionId::from_inner(*txid.value()), inscription)),
-    )
+    let inscription_id = InscriptionId::from_inner(*txid.value());
+
+    let Some((inscription, _satpoint)) = self.get_inscription_by_inscription_id(inscription_id)?
+    else {
+      return Ok(None);
+    };
+
+    Ok(Some((inscription_id, self.resolve_delegate(inscription)?)))
   }
 
   pub(crate) fn get_inscription_by_inscription_id(
     &self,
     txid: Txid,
   ) -> Result<Option<(Inscription, SatPoint)>> {
-    let Some(inscription) = self.get_transaction(txid)?.and_then(|tx| Inscription::from_transaction(&tx)) else {
+    let Some(inscription) = self
+      .get_transaction(txid)?
+      .and_then(|tx| Inscription::from_transaction(&tx))
+    else {
       return Ok(None);
     };
 
@@ -492,6 +565,19 @@ impl Index {
     Ok(Some((inscription, satpoint)))
   }
 
+  pub(crate) fn resolve_delegate(&self, inscription: Inscription) -> Result<Inscription> {
+    let Some(delegate) = inscription.delegate() else {
+      return Ok(inscription);
+    };
+
+    Ok(
+      self
+        .get_inscription_by_inscription_id(delegate)?
+        .map(|(delegate, _satpoint)| delegate)
+        .unwrap_or(inscription),
+    )
+  }
+
   pub(crate) fn get_transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
     if txid == self.genesis_block_coinbase_txid {
       Ok(Some(self.genesis_block_coinbase_transaction.clone()))
@@ -607,6 +693,8 @@ impl Index {
     }
   }
 
+  /// Inscription satpoints, with only the first inscription on satpoints
+  /// holding more than one, which is enough to know which sats are inscribed.
   pub(crate) fn get_inscriptions(
     &self,
     n: Option<usize>,
@@ -615,15 +703,37 @@ impl Index {
       self
         .database
         .begin_read()?
-        .open_table(SATPOINT_TO_INSCRIPTION_ID)?
-        .range([0; 44]..)?
-        .map(|(satpoint, id)| {
+        .open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?
+        .iter()?
+        .filter_map(|(satpoint, mut ids)| {
+          ids.next().map(|id| {
+            (
+              decode_satpoint(*satpoint.value()),
+              decode_inscription_id(*id.value()),
+            )
+          })
+        })
+        .take(n.unwrap_or(usize::MAX))
+        .collect(),
+    )
+  }
+
+  /// All inscriptions on each inscribed satpoint.
+  pub(crate) fn get_inscriptions_by_satpoint(
+    &self,
+  ) -> Result<BTreeMap<SatPoint, Vec<InscriptionId>>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?
+        .iter()?
+        .map(|(satpoint, ids)| {
           (
             decode_satpoint(*satpoint.value()),
-            decode_inscription_id(*id.value()),
+            ids.map(|id| decode_inscription_id(*id.value())).collect(),
           )
         })
-        .take(n.unwrap_or(usize::MAX))
         .collect(),
     )
   }
@@ -647,7 +757,7 @@ impl Index {
         continue;
       };
 
-      inscriptions.push((inscription, id));
+      inscriptions.push((self.resolve_delegate(inscription)?, id));
 
       if inscriptions.len() == n {
         break;
@@ -657,6 +767,49 @@ impl Index {
     Ok(inscriptions)
   }
 
+  pub(crate) fn get_inscriptions_with_content(
+    &self,
+    inscription: &Inscription,
+  ) -> Result<Vec<InscriptionId>> {
+    let Some(content_hash) = inscription.content_hash() else {
+      return Ok(Vec::new());
+    };
+
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_id = rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+
+    let content_hash_to_sequence_numbers =
+      rtx.open_multimap_table(CONTENT_HASH_TO_SEQUENCE_NUMBERS)?;
+
+    let ids = content_hash_to_sequence_numbers
+      .get(&content_hash)?
+      .map(|sequence_number| {
+        sequence_number_to_id
+          .get(&sequence_number.value())?
+          .map(|id| decode_inscription_id(*id.value()))
+          .ok_or_else(|| {
+            anyhow!(
+              "no inscription with sequence number {}",
+              sequence_number.value()
+            )
+          })
+      })
+      .collect();
+
+    ids
+  }
+
+  pub(crate) fn get_inscription_number(&self, inscription_id: InscriptionId) -> Result<i64> {
+    self
+      .database
+      .begin_read()?
+      .open_table(INSCRIPTION_ID_TO_NUMBER)?
+      .get(inscription_id.as_inner())?
+      .map(|x| x.value())
+      .ok_or_else(|| anyhow!("no number for inscription"))
+  }
+
   pub(crate) fn get_genesis_height(&self, inscription_id: InscriptionId) -> Result<u64> {
     self
       .database
@@ -666,11 +819,36 @@ impl Index {
       .map(|x| x.value())
       .ok_or_else(|| anyhow!("no height for inscription"))
   }
+
+  pub(crate) fn get_inscription_satpoint_by_id(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<SatPoint>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(INSCRIPTION_ID_TO_SATPOINT)?
+        .get(inscription_id.as_inner())?
+        .map(|satpoint| decode_satpoint(*satpoint.value())),
+    )
+  }
+
+  pub(crate) fn get_script_pubkey(&self, outpoint: OutPoint) -> Result<Option<Script>> {
+    Ok(
+      self
+        .database
+        .begin_read()?
+        .open_table(OUTPOINT_TO_SCRIPT_PUBKEY)?
+        .get(&encode_outpoint(outpoint))?
+        .map(|script_pubkey| Script::from(script_pubkey.value().to_vec())),
+    )
+  }
 }
 
 #[cfg(test)]
 mod tests {
-  use super::*;
+  use {super::*, bitcoin::Witness};
 
   struct Context {
     rpc_server: test_bitcoincore_rpc::Handle,
@@ -778,6 +956,7 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 2,
       fee: 0,
+      witness: Witness::new(),
     };
     let txid = context.rpc_server.broadcast_tx(split_coinbase_output);
 
@@ -804,6 +983,7 @@ mod tests {
       input_slots: &[(1, 0, 0), (2, 0, 0)],
       output_count: 1,
       fee: 0,
+      witness: Witness::new(),
     };
 
     let txid = context.rpc_server.broadcast_tx(merge_coinbase_outputs);
@@ -828,6 +1008,7 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 2,
       fee: 10,
+      witness: Witness::new(),
     };
     let txid = context.rpc_server.broadcast_tx(fee_paying_tx);
     let coinbase_txid = context.rpc_server.mine_blocks(1)[0].txdata[0].txid();
@@ -862,11 +1043,13 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 1,
       fee: 10,
+      witness: Witness::new(),
     };
     let second_fee_paying_tx = TransactionTemplate {
       input_slots: &[(2, 0, 0)],
       output_count: 1,
       fee: 10,
+      witness: Witness::new(),
     };
     context.rpc_server.broadcast_tx(first_fee_paying_tx);
     context.rpc_server.broadcast_tx(second_fee_paying_tx);
@@ -897,6 +1080,7 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 1,
       fee: 50 * COIN_VALUE,
+      witness: Witness::new(),
     };
     let txid = context.rpc_server.broadcast_tx(no_value_output);
     context.rpc_server.mine_blocks(1);
@@ -917,6 +1101,7 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 1,
       fee: 50 * COIN_VALUE,
+      witness: Witness::new(),
     };
     context.rpc_server.broadcast_tx(no_value_output);
     context.rpc_server.mine_blocks(1);
@@ -925,6 +1110,7 @@ mod tests {
       input_slots: &[(2, 1, 0)],
       output_count: 1,
       fee: 0,
+      witness: Witness::new(),
     };
     let txid = context.rpc_server.broadcast_tx(no_value_input);
     context.rpc_server.mine_blocks(1);
@@ -944,6 +1130,7 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 1,
       fee: 0,
+      witness: Witness::new(),
     });
     context.rpc_server.mine_blocks(1);
     context.index.update().unwrap();
@@ -1029,6 +1216,7 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 1,
       fee: 0,
+      witness: Witness::new(),
     });
     context.rpc_server.mine_blocks(1);
     context.index.update().unwrap();
@@ -1040,4 +1228,249 @@ mod tests {
       }
     )
   }
+
+  #[test]
+  fn reinscription_gets_cursed_number() {
+    let context = Context::with_args("--index-sats");
+
+    context.rpc_server.mine_blocks(1);
+
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "hello").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(2, 1, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "world").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    context.index.update().unwrap();
+
+    assert_eq!(context.index.get_inscription_number(first).unwrap(), 0);
+    assert_eq!(context.index.get_inscription_number(second).unwrap(), -1);
+    assert_eq!(
+      context
+        .index
+        .get_inscription_by_sat(Sat(50 * COIN_VALUE))
+        .unwrap(),
+      Some((first, inscription("text/plain", "hello")))
+    );
+  }
+
+  #[test]
+  fn script_pubkeys_of_outputs_holding_inscriptions_are_indexed() {
+    let context = Context::with_args("");
+
+    context.rpc_server.mine_blocks(1);
+
+    let inscription_id = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "hello").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    context.index.update().unwrap();
+
+    let inscription_output = OutPoint::new(inscription_id, 0);
+
+    assert_eq!(
+      context.index.get_script_pubkey(inscription_output).unwrap(),
+      Some(
+        context.rpc_server.tx_by_id(inscription_id).output[0]
+          .script_pubkey
+          .clone()
+      )
+    );
+
+    let send = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(2, 1, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: Witness::new(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.get_script_pubkey(inscription_output).unwrap(),
+      None
+    );
+    assert_eq!(
+      context
+        .index
+        .get_script_pubkey(OutPoint::new(send, 0))
+        .unwrap(),
+      Some(
+        context.rpc_server.tx_by_id(send).output[0]
+          .script_pubkey
+          .clone()
+      )
+    );
+  }
+
+  #[test]
+  fn reinscribed_inscriptions_move_together() {
+    let context = Context::with_args("");
+
+    context.rpc_server.mine_blocks(1);
+
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "hello").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(2, 1, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "world").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    let send = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(3, 1, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: Witness::new(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    context.index.update().unwrap();
+
+    let satpoint = SatPoint {
+      outpoint: OutPoint::new(send, 0),
+      offset: 0,
+    };
+
+    for inscription_id in [first, second] {
+      assert_eq!(
+        context
+          .index
+          .get_inscription_by_inscription_id(inscription_id)
+          .unwrap()
+          .unwrap()
+          .1,
+        satpoint
+      );
+    }
+
+    let mut inscription_ids = vec![first, second];
+    inscription_ids.sort();
+
+    assert_eq!(
+      context.index.get_inscriptions_by_satpoint().unwrap(),
+      [(satpoint, inscription_ids)].into_iter().collect()
+    );
+  }
+
+  #[test]
+  fn cursed_numbers_do_not_affect_positive_numbers() {
+    let context = Context::with_args("");
+
+    context.rpc_server.mine_blocks(2);
+
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "first").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    let cursed = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(3, 1, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "cursed").to_witness(),
+    });
+
+    let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(2, 0, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "second").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    context.index.update().unwrap();
+
+    assert_eq!(context.index.get_inscription_number(first).unwrap(), 0);
+    assert_eq!(context.index.get_inscription_number(cursed).unwrap(), -1);
+    assert_eq!(context.index.get_inscription_number(second).unwrap(), 1);
+  }
+
+  #[test]
+  fn inscriptions_with_content_are_in_chain_order() {
+    let context = Context::with_args("");
+
+    context.rpc_server.mine_blocks(1);
+
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(1, 0, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "hello").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    let first_cursed = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(2, 1, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "hello").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    let second_cursed = context.rpc_server.broadcast_tx(TransactionTemplate {
+      input_slots: &[(3, 1, 0)],
+      output_count: 1,
+      fee: 0,
+      witness: inscription("text/plain", "hello").to_witness(),
+    });
+
+    context.rpc_server.mine_blocks(1);
+
+    context.index.update().unwrap();
+
+    assert_eq!(
+      context.index.get_inscription_number(first_cursed).unwrap(),
+      -1
+    );
+    assert_eq!(
+      context.index.get_inscription_number(second_cursed).unwrap(),
+      -2
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_inscriptions_with_content(&inscription("text/plain", "hello"))
+        .unwrap(),
+      [first, first_cursed, second_cursed]
+    );
+  }
 }
@@ -274,9 +274,13 @@ impl Updater {
       }
     }
 
+    let mut content_hash_to_sequence_numbers =
+      wtx.open_multimap_table(CONTENT_HASH_TO_SEQUENCE_NUMBERS)?;
     let mut inscription_id_to_height = wtx.open_table(INSCRIPTION_ID_TO_HEIGHT)?;
+    let mut inscription_id_to_number = wtx.open_table(INSCRIPTION_ID_TO_NUMBER)?;
     let mut inscription_id_to_satpoint = wtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
-    let mut satpoint_to_inscription_id = wtx.open_table(SATPOINT_TO_INSCRIPTION_ID)?;
+    let mut satpoint_to_inscription_id = wtx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
+    let mut outpoint_to_script_pubkey = wtx.open_table(OUTPOINT_TO_SCRIPT_PUBKEY)?;
     let mut inscription_number_to_inscription_id =
       wtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
     let mut next_inscription_number = inscription_number_to_inscription_id
@@ -284,15 +288,36 @@ impl Updater {
       .rev()
       .map(|(number, _id)| number.value() + 1)
       .next()
+      .unwrap_or(0)
+      .max(0);
+    let mut next_cursed_inscription_number = inscription_number_to_inscription_id
+      .iter()?
+      .map(|(number, _id)| number.value() - 1)
+      .next()
+      .unwrap_or(-1)
+      .min(-1);
+    let mut sequence_number_to_inscription_id =
+      wtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut next_sequence_number = sequence_number_to_inscription_id
+      .iter()?
+      .rev()
+      .map(|(sequence_number, _id)| sequence_number.value() + 1)
+      .next()
       .unwrap_or(0);
 
     let mut inscription_updater = InscriptionUpdater {
+      content_hash_to_sequence_numbers: &mut content_hash_to_sequence_numbers,
       height: self.height,
       id_to_height: &mut inscription_id_to_height,
+      id_to_number: &mut inscription_id_to_number,
       id_to_satpoint: &mut inscription_id_to_satpoint,
+      next_cursed_number: &mut next_cursed_inscription_number,
       next_number: &mut next_inscription_number,
+      next_sequence_number: &mut next_sequence_number,
       number_to_id: &mut inscription_number_to_inscription_id,
+      outpoint_to_script_pubkey: &mut outpoint_to_script_pubkey,
       satpoint_to_id: &mut satpoint_to_inscription_id,
+      sequence_number_to_id: &mut sequence_number_to_inscription_id,
     };
 
     if self.index_sats {
@@ -395,7 +420,9 @@ impl Updater {
   ) -> Result {
     if inscription_updater.index_transaction_inscriptions(tx, txid)? {
       if let Some((start, _end)) = input_sat_ranges.get(0) {
-        sat_to_inscription_id.insert(&start, txid.as_inner())?;
+        if sat_to_inscription_id.get(start)?.is_none() {
+          sat_to_inscription_id.insert(start, txid.as_inner())?;
+        }
       }
     }
 
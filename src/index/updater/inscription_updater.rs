@@ -1,12 +1,20 @@
-use super::*;
+use {super::*, crate::inscription::Curse};
 
 pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
+  pub(super) content_hash_to_sequence_numbers:
+    &'a mut MultimapTable<'db, 'tx, &'tx ContentHashArray, u64>,
   pub(super) height: u64,
   pub(super) id_to_height: &'a mut Table<'db, 'tx, &'tx InscriptionIdArray, u64>,
+  pub(super) id_to_number: &'a mut Table<'db, 'tx, &'tx InscriptionIdArray, i64>,
   pub(super) id_to_satpoint: &'a mut Table<'db, 'tx, &'tx InscriptionIdArray, &'tx SatPointArray>,
-  pub(super) next_number: &'a mut u64,
-  pub(super) number_to_id: &'a mut Table<'db, 'tx, u64, &'tx InscriptionIdArray>,
-  pub(super) satpoint_to_id: &'a mut Table<'db, 'tx, &'tx SatPointArray, &'tx InscriptionIdArray>,
+  pub(super) next_cursed_number: &'a mut i64,
+  pub(super) next_number: &'a mut i64,
+  pub(super) next_sequence_number: &'a mut u64,
+  pub(super) number_to_id: &'a mut Table<'db, 'tx, i64, &'tx InscriptionIdArray>,
+  pub(super) outpoint_to_script_pubkey: &'a mut Table<'db, 'tx, &'tx OutPointArray, &'tx [u8]>,
+  pub(super) satpoint_to_id:
+    &'a mut MultimapTable<'db, 'tx, &'tx SatPointArray, &'tx InscriptionIdArray>,
+  pub(super) sequence_number_to_id: &'a mut Table<'db, 'tx, u64, &'tx InscriptionIdArray>,
 }
 
 impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
@@ -15,9 +23,27 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
     tx: &Transaction,
     txid: Txid,
   ) -> Result<bool> {
-    let inscribed = Inscription::from_transaction(tx).is_some();
+    let inscription = Inscription::from_transaction_with_curse(tx);
+
+    let inscribed = inscription.is_some();
+
+    let mut inscriptions_received = inscribed;
+
+    if let Some((inscription, curse)) = inscription {
+      let reinscription = match tx.input.first() {
+        Some(tx_in) => self
+          .satpoint_to_id
+          .get(&encode_satpoint(SatPoint {
+            outpoint: tx_in.previous_output,
+            offset: 0,
+          }))?
+          .next()
+          .is_some(),
+        None => false,
+      };
+
+      let curse = curse.or_else(|| reinscription.then_some(Curse::Reinscription));
 
-    if inscribed {
       let satpoint = encode_satpoint(SatPoint {
         outpoint: OutPoint { txid, vout: 0 },
         offset: 0,
@@ -25,11 +51,37 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
 
       let inscription_id = txid.as_inner();
 
+      let number = if curse.is_some() {
+        let number = *self.next_cursed_number;
+        *self.next_cursed_number -= 1;
+        number
+      } else {
+        let number = *self.next_number;
+        *self.next_number += 1;
+        number
+      };
+
       self.id_to_height.insert(inscription_id, &self.height)?;
+      self.id_to_number.insert(inscription_id, &number)?;
       self.id_to_satpoint.insert(inscription_id, &satpoint)?;
       self.satpoint_to_id.insert(&satpoint, inscription_id)?;
-      self.number_to_id.insert(self.next_number, inscription_id)?;
-      *self.next_number += 1;
+      self.number_to_id.insert(&number, inscription_id)?;
+
+      // Unlike inscription numbers, which are negative for cursed
+      // inscriptions, sequence numbers increase in chain order, so the first
+      // inscription with a given content sorts first.
+      let sequence_number = *self.next_sequence_number;
+      *self.next_sequence_number += 1;
+
+      self
+        .sequence_number_to_id
+        .insert(&sequence_number, inscription_id)?;
+
+      if let Some(content_hash) = inscription.content_hash() {
+        self
+          .content_hash_to_sequence_numbers
+          .insert(&content_hash, &sequence_number)?;
+      }
     };
 
     for tx_in in &tx.input {
@@ -44,21 +96,40 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         offset: u64::MAX,
       });
 
-      let inscription_ids: Vec<(SatPointArray, InscriptionIdArray)> = self
+      let inscription_ids: Vec<(SatPointArray, Vec<InscriptionIdArray>)> = self
         .satpoint_to_id
-        .range(start..=end)?
-        .map(|(satpoint, id)| (*satpoint.value(), *id.value()))
+        .range(&start..=&end)?
+        .map(|(satpoint, ids)| (*satpoint.value(), ids.map(|id| *id.value()).collect()))
         .collect();
 
-      for (old_satpoint, inscription_id) in inscription_ids {
+      if !inscription_ids.is_empty() {
+        self
+          .outpoint_to_script_pubkey
+          .remove(&encode_outpoint(outpoint))?;
+        inscriptions_received = true;
+      }
+
+      for (old_satpoint, inscription_ids) in inscription_ids {
         let new_satpoint = encode_satpoint(SatPoint {
           outpoint: OutPoint { txid, vout: 0 },
           offset: 0,
         });
 
-        self.satpoint_to_id.remove(&old_satpoint)?;
-        self.satpoint_to_id.insert(&new_satpoint, &inscription_id)?;
-        self.id_to_satpoint.insert(&inscription_id, &new_satpoint)?;
+        self.satpoint_to_id.remove_all(&old_satpoint)?;
+
+        for inscription_id in inscription_ids {
+          self.satpoint_to_id.insert(&new_satpoint, &inscription_id)?;
+          self.id_to_satpoint.insert(&inscription_id, &new_satpoint)?;
+        }
+      }
+    }
+
+    if inscriptions_received {
+      if let Some(tx_out) = tx.output.first() {
+        self.outpoint_to_script_pubkey.insert(
+          &encode_outpoint(OutPoint { txid, vout: 0 }),
+          tx_out.script_pubkey.as_bytes(),
+        )?;
       }
     }
 
@@ -5,6 +5,7 @@ use {
       opcodes,
       script::{self, Instruction, Instructions},
     },
+    hashes::sha256,
     util::taproot::TAPROOT_ANNEX_PREFIX,
     Script, Witness,
   },
@@ -17,11 +18,30 @@ const PROTOCOL_ID: &[u8] = b"ord";
 
 const CONTENT_TAG: &[u8] = &[];
 const CONTENT_TYPE_TAG: &[u8] = &[1];
+const DELEGATE_TAG: &[u8] = &[11];
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub(crate) enum Curse {
+  DuplicateField,
+  NotInFirstInput,
+  Reinscription,
+}
+
+impl Display for Curse {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::DuplicateField => write!(f, "duplicate field"),
+      Self::NotInFirstInput => write!(f, "not in first input"),
+      Self::Reinscription => write!(f, "reinscription"),
+    }
+  }
+}
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct Inscription {
   content: Option<Vec<u8>>,
   content_type: Option<Vec<u8>>,
+  delegate: Option<Vec<u8>>,
 }
 
 impl Inscription {
@@ -30,11 +50,41 @@ impl Inscription {
     Self {
       content_type,
       content,
+      delegate: None,
+    }
+  }
+
+  pub(crate) fn delegated(delegate: InscriptionId) -> Self {
+    Self {
+      content_type: None,
+      content: None,
+      delegate: Some(delegate.into_inner().to_vec()),
     }
   }
 
   pub(crate) fn from_transaction(tx: &Transaction) -> Option<Inscription> {
-    InscriptionParser::parse(&tx.input.get(0)?.witness).ok()
+    Self::from_transaction_with_curse(tx).map(|(inscription, _curse)| inscription)
+  }
+
+  /// Parse the first envelope in `tx`, along with the curse, if any, that
+  /// can be determined from the transaction alone. Reinscriptions can only
+  /// be detected by the index.
+  pub(crate) fn from_transaction_with_curse(
+    tx: &Transaction,
+  ) -> Option<(Inscription, Option<Curse>)> {
+    tx.input.iter().enumerate().find_map(|(i, input)| {
+      let envelope = InscriptionParser::parse_envelope(&input.witness).ok()?;
+
+      let curse = if i != 0 {
+        Some(Curse::NotInFirstInput)
+      } else if envelope.has_duplicate_field() {
+        Some(Curse::DuplicateField)
+      } else {
+        None
+      };
+
+      Some((envelope.inscription(), curse))
+    })
   }
 
   pub(crate) fn from_file(chain: Chain, path: impl AsRef<Path>) -> Result<Self, Error> {
@@ -60,6 +110,7 @@ impl Inscription {
     Ok(Self {
       content: Some(content),
       content_type: Some(content_type.into()),
+      delegate: None,
     })
   }
 
@@ -75,6 +126,10 @@ impl Inscription {
         .push_slice(content_type);
     }
 
+    if let Some(delegate) = &self.delegate {
+      builder = builder.push_slice(DELEGATE_TAG).push_slice(delegate);
+    }
+
     if let Some(content) = &self.content {
       builder = builder.push_slice(CONTENT_TAG);
       for chunk in content.chunks(520) {
@@ -85,6 +140,16 @@ impl Inscription {
     builder.push_opcode(opcodes::all::OP_ENDIF).into_script()
   }
 
+  #[cfg(test)]
+  pub(crate) fn to_witness(&self) -> Witness {
+    Witness::from_vec(vec![
+      self
+        .append_reveal_script(script::Builder::new())
+        .into_bytes(),
+      Vec::new(),
+    ])
+  }
+
   pub(crate) fn content(&self) -> Option<Content> {
     let content = self.content.as_ref()?;
 
@@ -107,6 +172,10 @@ impl Inscription {
     })
   }
 
+  pub(crate) fn content_hash(&self) -> Option<[u8; 32]> {
+    Some(sha256::Hash::hash(self.content_bytes()?).into_inner())
+  }
+
   pub(crate) fn content_size(&self) -> Option<usize> {
     Some(self.content_bytes()?.len())
   }
@@ -114,10 +183,14 @@ impl Inscription {
   pub(crate) fn content_type(&self) -> Option<&str> {
     str::from_utf8(self.content_type.as_ref()?).ok()
   }
+
+  pub(crate) fn delegate(&self) -> Option<InscriptionId> {
+    InscriptionId::from_slice(self.delegate.as_ref()?).ok()
+  }
 }
 
 #[derive(Debug, PartialEq)]
-enum InscriptionError {
+pub(crate) enum InscriptionError {
   EmptyWitness,
   KeyPathSpend,
   Script(script::Error),
@@ -125,14 +198,99 @@ enum InscriptionError {
   InvalidInscription,
 }
 
+impl Display for InscriptionError {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::EmptyWitness => write!(f, "empty witness"),
+      Self::KeyPathSpend => write!(f, "witness is a key path spend"),
+      Self::Script(err) => write!(f, "failed to parse script: {err}"),
+      Self::NoInscription => write!(f, "no inscription"),
+      Self::InvalidInscription => write!(f, "invalid inscription"),
+    }
+  }
+}
+
 type Result<T, E = InscriptionError> = std::result::Result<T, E>;
 
+#[derive(Debug, PartialEq)]
+pub(crate) struct Envelope {
+  pub(crate) fields: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Envelope {
+  /// Parse every envelope in `witness`, returning those parsed before the
+  /// error that stopped parsing, if any.
+  pub(crate) fn all_from_witness(witness: &Witness) -> (Vec<Self>, Option<InscriptionError>) {
+    InscriptionParser::parse_envelopes(witness)
+  }
+
+  pub(crate) fn inscription(&self) -> Inscription {
+    let mut fields = BTreeMap::new();
+
+    for (tag, value) in &self.fields {
+      fields
+        .entry(tag.as_slice())
+        .or_insert_with(|| value.clone());
+    }
+
+    Inscription {
+      content: fields.remove(CONTENT_TAG),
+      content_type: fields.remove(CONTENT_TYPE_TAG),
+      delegate: fields.remove(DELEGATE_TAG),
+    }
+  }
+
+  pub(crate) fn has_duplicate_field(&self) -> bool {
+    let mut tags = HashSet::new();
+    !self.fields.iter().all(|(tag, _value)| tags.insert(tag))
+  }
+
+  pub(crate) fn is_content_tag(tag: &[u8]) -> bool {
+    tag == CONTENT_TAG
+  }
+}
+
 struct InscriptionParser<'a> {
   instructions: Peekable<Instructions<'a>>,
 }
 
 impl<'a> InscriptionParser<'a> {
+  #[cfg(test)]
   fn parse(witness: &Witness) -> Result<Inscription> {
+    Ok(Self::parse_envelope(witness)?.inscription())
+  }
+
+  fn parse_envelope(witness: &Witness) -> Result<Envelope> {
+    let script = Script::from(Vec::from(Self::tapscript(witness)?));
+
+    InscriptionParser {
+      instructions: script.instructions().peekable(),
+    }
+    .parse_script()
+  }
+
+  fn parse_envelopes(witness: &Witness) -> (Vec<Envelope>, Option<InscriptionError>) {
+    let script = match Self::tapscript(witness) {
+      Ok(script) => Script::from(Vec::from(script)),
+      Err(err) => return (Vec::new(), Some(err)),
+    };
+
+    let mut parser = InscriptionParser {
+      instructions: script.instructions().peekable(),
+    };
+
+    let mut envelopes = Vec::new();
+
+    loop {
+      match parser.parse_script() {
+        Ok(envelope) => envelopes.push(envelope),
+        Err(InscriptionError::NoInscription) if !envelopes.is_empty() => return (envelopes, None),
+        Err(err) => return (envelopes, Some(err)),
+      }
+    }
+  }
+
+  fn tapscript(witness: &Witness) -> Result<&[u8]> {
     if witness.is_empty() {
       return Err(InscriptionError::EmptyWitness);
     }
@@ -150,28 +308,25 @@ impl<'a> InscriptionParser<'a> {
       return Err(InscriptionError::KeyPathSpend);
     }
 
-    let script = witness
-      .iter()
-      .nth(if annex {
-        witness.len() - 1
-      } else {
-        witness.len() - 2
-      })
-      .unwrap();
-
-    InscriptionParser {
-      instructions: Script::from(Vec::from(script)).instructions().peekable(),
-    }
-    .parse_script()
+    Ok(
+      witness
+        .iter()
+        .nth(if annex {
+          witness.len() - 1
+        } else {
+          witness.len() - 2
+        })
+        .unwrap(),
+    )
   }
 
-  fn parse_script(mut self) -> Result<Inscription> {
+  fn parse_script(&mut self) -> Result<Envelope> {
     loop {
       let next = self.advance()?;
 
       if next == Instruction::PushBytes(&[]) {
-        if let Some(inscription) = self.parse_inscription()? {
-          return Ok(inscription);
+        if let Some(envelope) = self.parse_inscription()? {
+          return Ok(envelope);
         }
       }
     }
@@ -185,13 +340,13 @@ impl<'a> InscriptionParser<'a> {
       .map_err(InscriptionError::Script)
   }
 
-  fn parse_inscription(&mut self) -> Result<Option<Inscription>> {
+  fn parse_inscription(&mut self) -> Result<Option<Envelope>> {
     if self.advance()? == Instruction::Op(opcodes::all::OP_IF) {
       if !self.accept(Instruction::PushBytes(PROTOCOL_ID))? {
         return Err(InscriptionError::NoInscription);
       }
 
-      let mut fields = BTreeMap::new();
+      let mut fields = Vec::new();
 
       loop {
         match self.advance()? {
@@ -200,24 +355,18 @@ impl<'a> InscriptionParser<'a> {
             while !self.accept(Instruction::Op(opcodes::all::OP_ENDIF))? {
               content.extend_from_slice(self.expect_push()?);
             }
-            fields.insert(CONTENT_TAG, content);
+            fields.push((CONTENT_TAG.to_vec(), content));
             break;
           }
           Instruction::PushBytes(tag) => {
-            if fields.contains_key(tag) {
-              return Err(InscriptionError::InvalidInscription);
-            }
-            fields.insert(tag, self.expect_push()?.to_vec());
+            fields.push((tag.to_vec(), self.expect_push()?.to_vec()));
           }
           Instruction::Op(opcodes::all::OP_ENDIF) => break,
           _ => return Err(InscriptionError::InvalidInscription),
         }
       }
 
-      return Ok(Some(Inscription {
-        content: fields.remove(CONTENT_TAG),
-        content_type: fields.remove(CONTENT_TYPE_TAG),
-      }));
+      return Ok(Some(Envelope { fields }));
     }
 
     Ok(None)
@@ -312,11 +461,11 @@ mod tests {
         &[1],
         b"text/plain;charset=utf-8",
         &[1],
-        b"text/plain;charset=utf-8",
+        b"text/html;charset=utf-8",
         &[],
         b"ord",
       ])),
-      Err(InscriptionError::InvalidInscription),
+      Ok(inscription("text/plain;charset=utf-8", "ord")),
     );
   }
 
@@ -357,6 +506,7 @@ mod tests {
       Ok(Inscription {
         content_type: Some(b"text/plain;charset=utf-8".to_vec()),
         content: None,
+        delegate: None,
       }),
     );
   }
@@ -368,6 +518,7 @@ mod tests {
       Ok(Inscription {
         content_type: None,
         content: Some(b"foo".to_vec()),
+        delegate: None,
       }),
     );
   }
@@ -552,6 +703,7 @@ mod tests {
       Ok(Inscription {
         content_type: None,
         content: None,
+        delegate: None,
       }),
     );
   }
@@ -577,14 +729,7 @@ mod tests {
   }
 
   #[test]
-  fn do_not_extract_from_second_input() {
-    let script = script::Builder::new()
-      .push_opcode(opcodes::OP_FALSE)
-      .push_opcode(opcodes::all::OP_IF)
-      .push_slice("ord".as_bytes())
-      .push_opcode(opcodes::all::OP_ENDIF)
-      .into_script();
-
+  fn extract_cursed_from_second_input() {
     let tx = Transaction {
       version: 0,
       lock_time: bitcoin::PackedLockTime(0),
@@ -599,13 +744,62 @@ mod tests {
           previous_output: OutPoint::null(),
           script_sig: Script::new(),
           sequence: Sequence(0),
-          witness: Witness::from_vec(vec![script.into_bytes(), vec![]]),
+          witness: container(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"ord"]),
         },
       ],
       output: Vec::new(),
     };
 
-    assert_eq!(Inscription::from_transaction(&tx), None);
+    assert_eq!(
+      Inscription::from_transaction_with_curse(&tx),
+      Some((
+        inscription("text/plain;charset=utf-8", "ord"),
+        Some(Curse::NotInFirstInput)
+      )),
+    );
+  }
+
+  #[test]
+  fn extract_cursed_with_duplicate_field() {
+    let tx = Transaction {
+      version: 0,
+      lock_time: bitcoin::PackedLockTime(0),
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: Script::new(),
+        sequence: Sequence(0),
+        witness: container(&[b"ord", &[1], b"text/plain;charset=utf-8", &[1], b"foo"]),
+      }],
+      output: Vec::new(),
+    };
+
+    assert_eq!(
+      Inscription::from_transaction_with_curse(&tx),
+      Some((
+        Inscription::new(Some(b"text/plain;charset=utf-8".to_vec()), None),
+        Some(Curse::DuplicateField)
+      )),
+    );
+  }
+
+  #[test]
+  fn extract_uncursed_from_first_input() {
+    let tx = Transaction {
+      version: 0,
+      lock_time: bitcoin::PackedLockTime(0),
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: Script::new(),
+        sequence: Sequence(0),
+        witness: container(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"ord"]),
+      }],
+      output: Vec::new(),
+    };
+
+    assert_eq!(
+      Inscription::from_transaction_with_curse(&tx),
+      Some((inscription("text/plain;charset=utf-8", "ord"), None)),
+    );
   }
 
   #[test]
@@ -689,6 +883,7 @@ mod tests {
       &Inscription {
         content_type: None,
         content: None,
+        delegate: None,
       }
       .append_reveal_script(script::Builder::new()),
     );
@@ -700,7 +895,122 @@ mod tests {
       Inscription {
         content_type: None,
         content: None,
+        delegate: None,
       }
     );
   }
+
+  #[test]
+  fn valid_with_delegate() {
+    assert_eq!(
+      InscriptionParser::parse(&container(&[b"ord", &[11], &[1; 32]])),
+      Ok(Inscription {
+        content_type: None,
+        content: None,
+        delegate: Some(vec![1; 32]),
+      }),
+    );
+  }
+
+  #[test]
+  fn delegate_must_be_inscription_id() {
+    assert_eq!(
+      InscriptionParser::parse(&container(&[b"ord", &[11], &[1; 31]]))
+        .unwrap()
+        .delegate(),
+      None,
+    );
+  }
+
+  #[test]
+  fn round_trip_with_delegate() {
+    let mut witness = Witness::new();
+
+    witness.push(Inscription::delegated(txid(1)).append_reveal_script(script::Builder::new()));
+
+    witness.push([]);
+
+    let inscription = InscriptionParser::parse(&witness).unwrap();
+
+    assert_eq!(inscription, Inscription::delegated(txid(1)));
+    assert_eq!(inscription.delegate(), Some(txid(1)));
+    assert_eq!(inscription.content_bytes(), None);
+  }
+
+  #[test]
+  fn envelope_preserves_duplicate_fields() {
+    let envelope = InscriptionParser::parse_envelope(&container(&[
+      b"ord",
+      &[1],
+      b"text/plain;charset=utf-8",
+      &[1],
+      b"text/html;charset=utf-8",
+      &[],
+      b"ord",
+    ]))
+    .unwrap();
+
+    assert_eq!(
+      envelope,
+      Envelope {
+        fields: vec![
+          (vec![1], b"text/plain;charset=utf-8".to_vec()),
+          (vec![1], b"text/html;charset=utf-8".to_vec()),
+          (Vec::new(), b"ord".to_vec()),
+        ],
+      }
+    );
+
+    assert!(envelope.has_duplicate_field());
+
+    assert_eq!(
+      envelope.inscription(),
+      inscription("text/plain;charset=utf-8", "ord")
+    );
+  }
+
+  #[test]
+  fn all_envelopes_are_returned_with_error_that_stopped_parsing() {
+    let script = script::Builder::new()
+      .push_opcode(opcodes::OP_FALSE)
+      .push_opcode(opcodes::all::OP_IF)
+      .push_slice(b"ord")
+      .push_slice(&[])
+      .push_slice(b"foo")
+      .push_opcode(opcodes::all::OP_ENDIF)
+      .push_opcode(opcodes::OP_FALSE)
+      .push_opcode(opcodes::all::OP_IF)
+      .push_slice(b"ord")
+      .push_slice(&[])
+      .push_slice(b"bar")
+      .push_opcode(opcodes::all::OP_ENDIF)
+      .push_opcode(opcodes::OP_FALSE)
+      .push_opcode(opcodes::all::OP_IF)
+      .push_slice(b"ord")
+      .push_opcode(opcodes::all::OP_CHECKSIG)
+      .into_script();
+
+    assert_eq!(
+      Envelope::all_from_witness(&Witness::from_vec(vec![script.into_bytes(), vec![]])),
+      (
+        vec![
+          Envelope {
+            fields: vec![(Vec::new(), b"foo".to_vec())],
+          },
+          Envelope {
+            fields: vec![(Vec::new(), b"bar".to_vec())],
+          },
+        ],
+        Some(InscriptionError::InvalidInscription),
+      ),
+    );
+  }
+
+  #[test]
+  fn all_envelopes_of_key_path_spend_is_error() {
+    assert_eq!(
+      Envelope::all_from_witness(&Witness::from_vec(vec![vec![]])),
+      (Vec::new(), Some(InscriptionError::KeyPathSpend)),
+    );
+  }
 }
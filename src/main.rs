@@ -18,6 +18,7 @@ use {
     decimal::Decimal,
     degree::Degree,
     epoch::Epoch,
+    fee_rate::FeeRate,
     height::Height,
     index::{Index, List},
     inscription::Inscription,
@@ -76,12 +77,14 @@ mod test;
 use self::test::*;
 
 mod arguments;
+mod bip322;
 mod blocktime;
 mod chain;
 mod content;
 mod decimal;
 mod degree;
 mod epoch;
+mod fee_rate;
 mod height;
 mod index;
 mod inscription;
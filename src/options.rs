@@ -1,6 +1,7 @@
 use {
   super::*,
   bitcoincore_rpc::{Auth, Client},
+  percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC},
 };
 
 #[derive(Debug, Parser)]
@@ -32,6 +33,8 @@ pub(crate) struct Options {
   signet: bool,
   #[clap(long, help = "Use testnet.")]
   testnet: bool,
+  #[clap(long, default_value = "ord", help = "Use wallet named <WALLET>.")]
+  pub(crate) wallet: String,
 }
 
 impl Options {
@@ -89,14 +92,27 @@ impl Options {
   }
 
   pub(crate) fn bitcoin_rpc_client(&self) -> Result<Client> {
+    self.bitcoin_rpc_client_at(&self.rpc_url())
+  }
+
+  /// Connect to Bitcoin Core with wallet RPCs routed to the wallet selected
+  /// with `--wallet`.
+  pub(crate) fn bitcoin_rpc_client_for_wallet(&self) -> Result<Client> {
+    self.bitcoin_rpc_client_at(&format!(
+      "{}/wallet/{}",
+      self.rpc_url(),
+      utf8_percent_encode(&self.wallet, NON_ALPHANUMERIC)
+    ))
+  }
+
+  fn bitcoin_rpc_client_at(&self, rpc_url: &str) -> Result<Client> {
     let cookie_file = self.cookie_file()?;
-    let rpc_url = self.rpc_url();
     log::info!(
       "Connecting to Bitcoin Core RPC server at {rpc_url} using credentials from `{}`",
       cookie_file.display()
     );
 
-    let client = Client::new(&rpc_url, Auth::CookieFile(cookie_file))
+    let client = Client::new(rpc_url, Auth::CookieFile(cookie_file))
       .with_context(|| format!("failed to connect to Bitcoin Core RPC at {rpc_url}"))?;
 
     let rpc_chain = match client.get_blockchain_info()?.chain.as_str() {
@@ -117,16 +133,16 @@ impl Options {
   }
 
   pub(crate) fn bitcoin_rpc_client_mainnet_forbidden(&self, command: &str) -> Result<Client> {
-    let client = self.bitcoin_rpc_client()?;
+    let client = self.bitcoin_rpc_client_for_wallet()?;
 
-//    if self.chain() == Chain::Mainnet {
-//      bail!("`{command}` is unstable and not yet supported on mainnet.");
-//    }
+    //    if self.chain() == Chain::Mainnet {
+    //      bail!("`{command}` is unstable and not yet supported on mainnet.");
+    //    }
     Ok(client)
   }
 
   pub(crate) fn bitcoin_rpc_client_for_wallet_command(&self, command: &str) -> Result<Client> {
-    let client = self.bitcoin_rpc_client()?;
+    let client = self.bitcoin_rpc_client_for_wallet()?;
 
     if self.chain() == Chain::Mainnet {
       let wallet_info = client.get_wallet_info()?;
@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
 pub(crate) enum Rarity {
   Common,
   Uncommon,
@@ -27,6 +27,22 @@ impl Display for Rarity {
   }
 }
 
+impl FromStr for Rarity {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "common" => Ok(Self::Common),
+      "uncommon" => Ok(Self::Uncommon),
+      "rare" => Ok(Self::Rare),
+      "epic" => Ok(Self::Epic),
+      "legendary" => Ok(Self::Legendary),
+      "mythic" => Ok(Self::Mythic),
+      _ => bail!("invalid rarity `{s}`"),
+    }
+  }
+}
+
 impl From<Sat> for Rarity {
   fn from(sat: Sat) -> Self {
     let Degree {
@@ -95,4 +111,20 @@ mod tests {
     assert_eq!(Sat(2067187500000000).rarity(), Rarity::Legendary);
     assert_eq!(Sat(2067187500000000 + 1).rarity(), Rarity::Common);
   }
+
+  #[test]
+  fn from_str_and_display_round_trip() {
+    for rarity in [
+      Rarity::Common,
+      Rarity::Uncommon,
+      Rarity::Rare,
+      Rarity::Epic,
+      Rarity::Legendary,
+      Rarity::Mythic,
+    ] {
+      assert_eq!(rarity.to_string().parse::<Rarity>().unwrap(), rarity);
+    }
+
+    assert!("foo".parse::<Rarity>().is_err());
+  }
 }
@@ -45,6 +45,26 @@ impl FromStr for SatPoint {
   }
 }
 
+impl Serialize for SatPoint {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for SatPoint {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    String::deserialize(deserializer)?
+      .parse()
+      .map_err(serde::de::Error::custom)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -78,4 +98,14 @@ mod tests {
       .parse::<SatPoint>()
       .unwrap_err();
   }
+
+  #[test]
+  fn serde() {
+    let satpoint = "1111111111111111111111111111111111111111111111111111111111111111:1:1"
+      .parse::<SatPoint>()
+      .unwrap();
+    let json = "\"1111111111111111111111111111111111111111111111111111111111111111:1:1\"";
+    assert_eq!(serde_json::to_string(&satpoint).unwrap(), json);
+    assert_eq!(serde_json::from_str::<SatPoint>(json).unwrap(), satpoint);
+  }
 }
@@ -1,19 +1,24 @@
 use super::*;
 
+mod decode;
 mod epochs;
 mod find;
 mod index;
 mod info;
+mod inscription;
 mod list;
 mod parse;
 mod server;
 mod subsidy;
 mod supply;
 mod traits;
+mod verify;
 mod wallet;
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
+  #[clap(about = "Decode inscription envelopes in a transaction or PSBT")]
+  Decode(decode::Decode),
   #[clap(about = "List the first satoshis of each reward epoch")]
   Epochs,
   #[clap(about = "Find a satoshi's current location")]
@@ -22,6 +27,8 @@ pub(crate) enum Subcommand {
   Index,
   #[clap(about = "Display index statistics")]
   Info(info::Info),
+  #[clap(subcommand, about = "Inscription commands")]
+  Inscription(inscription::Inscription),
   #[clap(about = "List the satoshis in an output")]
   List(list::List),
   #[clap(about = "Parse a satoshi from ordinal notation")]
@@ -34,6 +41,8 @@ pub(crate) enum Subcommand {
   Supply,
   #[clap(about = "Display satoshi traits")]
   Traits(traits::Traits),
+  #[clap(about = "Verify a BIP-322 signature proving ownership of an inscription")]
+  Verify(verify::Verify),
   #[clap(subcommand, about = "Wallet commands")]
   Wallet(wallet::Wallet),
 }
@@ -41,10 +50,12 @@ pub(crate) enum Subcommand {
 impl Subcommand {
   pub(crate) fn run(self, options: Options) -> Result {
     match self {
+      Self::Decode(decode) => decode.run(options),
       Self::Epochs => epochs::run(),
       Self::Find(find) => find.run(options),
       Self::Index => index::run(options),
       Self::Info(info) => info.run(options),
+      Self::Inscription(inscription) => inscription.run(options),
       Self::List(list) => list.run(options),
       Self::Parse(parse) => parse.run(),
       Self::Subsidy(subsidy) => subsidy.run(),
@@ -56,6 +67,7 @@ impl Subcommand {
       }
       Self::Supply => supply::run(),
       Self::Traits(traits) => traits.run(),
+      Self::Verify(verify) => verify.run(options),
       Self::Wallet(wallet) => wallet.run(options),
     }
   }
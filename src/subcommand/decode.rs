@@ -0,0 +1,283 @@
+use {
+  super::*,
+  crate::inscription::Envelope,
+  bitcoin::{util::psbt::PartiallySignedTransaction, Witness},
+};
+
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+#[derive(Debug, Parser)]
+pub(crate) struct Decode {
+  #[clap(
+    help = "Decode inscriptions in <INPUT>, a txid, hex, or a file containing a transaction or PSBT"
+  )]
+  input: String,
+  #[clap(long, help = "Include base64-encoded inscription content in output")]
+  content: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Output {
+  pub(crate) inputs: Vec<DecodedInput>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DecodedInput {
+  pub(crate) input: usize,
+  pub(crate) envelopes: Vec<DecodedEnvelope>,
+  pub(crate) error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DecodedEnvelope {
+  pub(crate) fields: Vec<DecodedField>,
+  pub(crate) duplicate_field: bool,
+  pub(crate) content_type: Option<String>,
+  pub(crate) content_length: Option<usize>,
+  pub(crate) content: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct DecodedField {
+  pub(crate) tag: String,
+  pub(crate) value: String,
+}
+
+impl Decode {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let witnesses = if let Ok(txid) = self.input.parse::<Txid>() {
+      Self::transaction_witnesses(
+        options
+          .bitcoin_rpc_client()?
+          .get_raw_transaction(&txid, None)?,
+      )
+    } else if Path::new(&self.input).is_file() {
+      let bytes =
+        fs::read(&self.input).with_context(|| format!("io error reading {}", self.input))?;
+
+      if bytes.starts_with(PSBT_MAGIC) {
+        Self::psbt_witnesses(consensus::deserialize(&bytes)?)
+      } else if let Ok(tx) = consensus::deserialize(&bytes) {
+        Self::transaction_witnesses(tx)
+      } else {
+        Self::decode_text(str::from_utf8(&bytes).context("file is not a transaction or PSBT")?)?
+      }
+    } else {
+      Self::decode_text(&self.input)?
+    };
+
+    println!(
+      "{}",
+      serde_json::to_string_pretty(&Self::decode_witnesses(&witnesses, self.content))?
+    );
+
+    Ok(())
+  }
+
+  fn decode_text(text: &str) -> Result<Vec<Witness>> {
+    let text = text.trim();
+
+    let bytes = match hex::decode(text) {
+      Ok(bytes) => bytes,
+      Err(_) => base64::decode(text).context("input is not a txid, hex, or base64")?,
+    };
+
+    if bytes.starts_with(PSBT_MAGIC) {
+      Ok(Self::psbt_witnesses(consensus::deserialize(&bytes)?))
+    } else {
+      Ok(Self::transaction_witnesses(consensus::deserialize(&bytes)?))
+    }
+  }
+
+  fn transaction_witnesses(tx: Transaction) -> Vec<Witness> {
+    tx.input.into_iter().map(|input| input.witness).collect()
+  }
+
+  fn psbt_witnesses(psbt: PartiallySignedTransaction) -> Vec<Witness> {
+    psbt
+      .inputs
+      .into_iter()
+      .zip(psbt.unsigned_tx.input)
+      .map(|(psbt_input, tx_input)| {
+        if let Some(witness) = psbt_input.final_script_witness {
+          return witness;
+        }
+
+        // Unsigned script-path spends carry their leaf scripts in the PSBT,
+        // so build a witness that the parser can read the script from.
+        if let Some((control_block, (script, _leaf_version))) =
+          psbt_input.tap_scripts.into_iter().next()
+        {
+          return Witness::from_vec(vec![script.into_bytes(), control_block.serialize()]);
+        }
+
+        tx_input.witness
+      })
+      .collect()
+  }
+
+  fn decode_witnesses(witnesses: &[Witness], include_content: bool) -> Output {
+    Output {
+      inputs: witnesses
+        .iter()
+        .enumerate()
+        .map(|(input, witness)| {
+          let (envelopes, error) = Envelope::all_from_witness(witness);
+
+          DecodedInput {
+            input,
+            envelopes: envelopes
+              .iter()
+              .map(|envelope| Self::decode_envelope(envelope, include_content))
+              .collect(),
+            error: error.map(|err| err.to_string()),
+          }
+        })
+        .collect(),
+    }
+  }
+
+  fn decode_envelope(envelope: &Envelope, include_content: bool) -> DecodedEnvelope {
+    let inscription = envelope.inscription();
+
+    let fields = envelope
+      .fields
+      .iter()
+      .filter(|(tag, _value)| !Envelope::is_content_tag(tag))
+      .map(|(tag, value)| DecodedField {
+        tag: hex::encode(tag),
+        value: hex::encode(value),
+      })
+      .collect();
+
+    let content = inscription.content_bytes();
+
+    DecodedEnvelope {
+      fields,
+      duplicate_field: envelope.has_duplicate_field(),
+      content_type: inscription.content_type().map(str::to_string),
+      content_length: content.map(<[u8]>::len),
+      content: content.filter(|_| include_content).map(base64::encode),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::{
+      blockdata::{opcodes, script},
+      secp256k1::{rand, KeyPair, Secp256k1, XOnlyPublicKey},
+      util::taproot::{LeafVersion, TaprootBuilder},
+      PackedLockTime,
+    },
+  };
+
+  #[test]
+  fn key_path_spend() {
+    assert_eq!(
+      Decode::decode_witnesses(&[Witness::from_vec(vec![vec![1; 64]])], false),
+      Output {
+        inputs: vec![DecodedInput {
+          input: 0,
+          envelopes: Vec::new(),
+          error: Some("witness is a key path spend".into()),
+        }],
+      }
+    );
+  }
+
+  #[test]
+  fn unsigned_psbt_script_path_spend() {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = KeyPair::new(&secp256k1, &mut rand::thread_rng());
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let reveal_script = inscription("text/plain;charset=utf-8", "foo").append_reveal_script(
+      script::Builder::new()
+        .push_slice(&public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    );
+
+    let control_block = TaprootBuilder::new()
+      .add_leaf(0, reveal_script.clone())
+      .unwrap()
+      .finalize(&secp256k1, public_key)
+      .unwrap()
+      .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+      .unwrap();
+
+    let mut psbt = PartiallySignedTransaction::from_unsigned_tx(Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![tx_in(outpoint(1))],
+      output: Vec::new(),
+    })
+    .unwrap();
+
+    psbt.inputs[0]
+      .tap_scripts
+      .insert(control_block, (reveal_script, LeafVersion::TapScript));
+
+    assert_eq!(
+      Decode::decode_witnesses(&Decode::psbt_witnesses(psbt), true),
+      Output {
+        inputs: vec![DecodedInput {
+          input: 0,
+          envelopes: vec![DecodedEnvelope {
+            fields: vec![DecodedField {
+              tag: "01".into(),
+              value: hex::encode("text/plain;charset=utf-8"),
+            }],
+            duplicate_field: false,
+            content_type: Some("text/plain;charset=utf-8".into()),
+            content_length: Some(3),
+            content: Some("Zm9v".into()),
+          }],
+          error: None,
+        }],
+      }
+    );
+  }
+
+  #[test]
+  fn multiple_envelopes_in_one_input() {
+    let script = script::Builder::new()
+      .push_opcode(opcodes::OP_FALSE)
+      .push_opcode(opcodes::all::OP_IF)
+      .push_slice(b"ord")
+      .push_slice(&[])
+      .push_slice(b"foo")
+      .push_opcode(opcodes::all::OP_ENDIF)
+      .push_opcode(opcodes::OP_FALSE)
+      .push_opcode(opcodes::all::OP_IF)
+      .push_slice(b"ord")
+      .push_slice(&[])
+      .push_slice(b"bar")
+      .push_opcode(opcodes::all::OP_ENDIF)
+      .into_script();
+
+    let envelope = |content_length| DecodedEnvelope {
+      fields: Vec::new(),
+      duplicate_field: false,
+      content_type: None,
+      content_length: Some(content_length),
+      content: None,
+    };
+
+    assert_eq!(
+      Decode::decode_witnesses(
+        &[Witness::from_vec(vec![script.into_bytes(), vec![]])],
+        false
+      ),
+      Output {
+        inputs: vec![DecodedInput {
+          input: 0,
+          envelopes: vec![envelope(3), envelope(3)],
+          error: None,
+        }],
+      }
+    );
+  }
+}
@@ -0,0 +1,17 @@
+use super::*;
+
+mod duplicates;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Inscription {
+  #[clap(about = "List inscriptions with the same content as an inscription")]
+  Duplicates(duplicates::Duplicates),
+}
+
+impl Inscription {
+  pub(crate) fn run(self, options: Options) -> Result {
+    match self {
+      Self::Duplicates(duplicates) => duplicates.run(options),
+    }
+  }
+}
@@ -0,0 +1,28 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Duplicates {
+  #[clap(help = "List inscriptions with the same content as <INSCRIPTION_ID>")]
+  inscription_id: InscriptionId,
+}
+
+impl Duplicates {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let (inscription, _satpoint) = index
+      .get_inscription_by_inscription_id(self.inscription_id)?
+      .ok_or_else(|| anyhow!("inscription {} not found", self.inscription_id))?;
+
+    if inscription.content_bytes().is_none() {
+      bail!("inscription {} has no content", self.inscription_id);
+    }
+
+    for inscription_id in index.get_inscriptions_with_content(&inscription)? {
+      println!("{inscription_id}");
+    }
+
+    Ok(())
+  }
+}
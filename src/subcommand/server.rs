@@ -12,7 +12,7 @@ use {
     http::{header, StatusCode},
     response::{IntoResponse, Redirect, Response},
     routing::get,
-    Router,
+    Json, Router,
   },
   axum_server::Handle,
   rust_embed::RustEmbed,
@@ -29,6 +29,17 @@ use {
 
 mod deserialize_from_str;
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct InscriptionJson {
+  pub(crate) content_length: Option<usize>,
+  pub(crate) content_type: Option<String>,
+  pub(crate) delegate: Option<InscriptionId>,
+  pub(crate) genesis_height: u64,
+  pub(crate) inscription_id: InscriptionId,
+  pub(crate) number: i64,
+  pub(crate) satpoint: SatPoint,
+}
+
 enum BlockQuery {
   Height(u64),
   Hash(BlockHash),
@@ -159,6 +170,13 @@ impl Server {
         .route("/install.sh", get(Self::install_script))
         .route("/ordinal/:sat", get(Self::ordinal))
         .route("/output/:output", get(Self::output))
+        .route("/r/blockhash", get(Self::block_hash_json))
+        .route("/r/blockhash/:height", get(Self::block_hash_at_height_json))
+        .route("/r/blockheight", get(Self::block_height_json))
+        .route(
+          "/r/inscription/:inscription_id",
+          get(Self::inscription_json),
+        )
         .route("/range/:start/:end", get(Self::range))
         .route("/rare.txt", get(Self::rare_txt))
         .route("/sat/:sat", get(Self::sat))
@@ -504,7 +522,13 @@ impl Server {
           "failed to retrieve inscription from txid {txid} from index: {err}"
         ))
       })?
-      .map(|(inscription, _satpoint)| inscription);
+      .map(|(inscription, _satpoint)| index.resolve_delegate(inscription))
+      .transpose()
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "failed to resolve delegate of inscription {txid} from index: {err}"
+        ))
+      })?;
 
     Ok(
       TransactionHtml::new(
@@ -619,6 +643,47 @@ impl Server {
     )
   }
 
+  async fn block_height_json(Extension(index): Extension<Arc<Index>>) -> ServerResult<Json<u64>> {
+    Ok(Json(
+      index
+        .height()
+        .map_err(|err| {
+          ServerError::Internal(anyhow!("failed to retrieve height from index: {err}"))
+        })?
+        .ok_or_else(|| ServerError::NotFound("no blocks indexed".to_string()))?
+        .n(),
+    ))
+  }
+
+  async fn block_hash_json(
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<Json<BlockHash>> {
+    Ok(Json(
+      index
+        .block_hash(None)
+        .map_err(|err| {
+          ServerError::Internal(anyhow!("failed to retrieve block hash from index: {err}"))
+        })?
+        .ok_or_else(|| ServerError::NotFound("no blocks indexed".to_string()))?,
+    ))
+  }
+
+  async fn block_hash_at_height_json(
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u64>,
+  ) -> ServerResult<Json<BlockHash>> {
+    Ok(Json(
+      index
+        .block_hash(Some(height))
+        .map_err(|err| {
+          ServerError::Internal(anyhow!(
+            "failed to retrieve block hash at height {height} from index: {err}"
+          ))
+        })?
+        .ok_or_else(|| ServerError::NotFound(format!("block at height {height} unknown")))?,
+    ))
+  }
+
   async fn input(
     Extension(chain): Extension<Chain>,
     Extension(index): Extension<Arc<Index>>,
@@ -669,22 +734,31 @@ impl Server {
         ServerError::NotFound(format!("transaction {inscription_id} has no inscription"))
       })?;
 
+    let inscription = index.resolve_delegate(inscription).map_err(|err| {
+      ServerError::Internal(anyhow!(
+        "failed to resolve delegate of inscription {inscription_id} from index: {err}"
+      ))
+    })?;
+
     let (content_type, content) = Self::content_response(inscription).ok_or_else(|| {
       ServerError::NotFound(format!("inscription {inscription_id} has no content"))
     })?;
 
+    // Both policies are enforced, so content may only load resources from the
+    // same origin, and only from `/content/` and the recursive `/r/` endpoints.
     Ok(
-      (
-        [
-          (header::CONTENT_TYPE, content_type),
-          (
-            header::CONTENT_SECURITY_POLICY,
-            "default-src 'none' 'unsafe-eval' 'unsafe-inline'".to_string(),
-          ),
-        ],
-        content,
-      )
-        .into_response(),
+      Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+          header::CONTENT_SECURITY_POLICY,
+          "default-src 'self' 'unsafe-eval' 'unsafe-inline' data: blob:",
+        )
+        .header(
+          header::CONTENT_SECURITY_POLICY,
+          "default-src *:*/content/ *:*/r/ 'unsafe-eval' 'unsafe-inline' data: blob:",
+        )
+        .body(body::boxed(body::Full::from(content)))
+        .unwrap(),
     )
   }
 
@@ -719,11 +793,39 @@ impl Server {
         ))
       })?;
 
+    let copies = index
+      .get_inscriptions_with_content(&inscription)
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "failed to retrieve copies of inscription {inscription_id} from index: {err}"
+        ))
+      })?;
+
+    let number = index
+      .get_inscription_number(inscription_id)
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "failed to retrieve number for inscription {inscription_id} from index: {err}"
+        ))
+      })?;
+
+    let delegate = inscription.delegate();
+
+    let inscription = index.resolve_delegate(inscription).map_err(|err| {
+      ServerError::Internal(anyhow!(
+        "failed to resolve delegate of inscription {inscription_id} from index: {err}"
+      ))
+    })?;
+
     Ok(
       InscriptionHtml {
+        copies: copies.len(),
+        delegate,
         genesis_height,
         inscription_id,
         inscription,
+        number,
+        original: copies.first().copied().filter(|id| *id != inscription_id),
         satpoint,
       }
       .page(
@@ -733,6 +835,56 @@ impl Server {
     )
   }
 
+  async fn inscription_json(
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult<Json<InscriptionJson>> {
+    let (inscription, satpoint) = index
+      .get_inscription_by_inscription_id(inscription_id)
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "failed to retrieve inscription with inscription id {inscription_id} from index: {err}"
+        ))
+      })?
+      .ok_or_else(|| {
+        ServerError::NotFound(format!("transaction {inscription_id} has no inscription"))
+      })?;
+
+    let genesis_height = index.get_genesis_height(inscription_id).map_err(|err| {
+      ServerError::Internal(anyhow!(
+        "failed to retrieve height for inscription with inscription id {inscription_id} from index: {err}"
+      ))
+    })?;
+
+    let number = index
+      .get_inscription_number(inscription_id)
+      .map_err(|err| {
+        ServerError::Internal(anyhow!(
+          "failed to retrieve number for inscription {inscription_id} from index: {err}"
+        ))
+      })?;
+
+    let delegate = inscription.delegate();
+
+    let inscription = index.resolve_delegate(inscription).map_err(|err| {
+      ServerError::Internal(anyhow!(
+        "failed to resolve delegate of inscription {inscription_id} from index: {err}"
+      ))
+    })?;
+
+    Ok(Json(InscriptionJson {
+      content_length: inscription.content_bytes().map(|content| content.len()),
+      content_type: inscription
+        .content_type()
+        .map(|content_type| content_type.to_string()),
+      delegate,
+      genesis_height,
+      inscription_id,
+      number,
+      satpoint,
+    }))
+  }
+
   async fn inscriptions(
     Extension(chain): Extension<Chain>,
     Extension(index): Extension<Arc<Index>>,
@@ -753,7 +905,7 @@ impl Server {
 
 #[cfg(test)]
 mod tests {
-  use {super::*, reqwest::Url, std::net::TcpListener, tempfile::TempDir};
+  use {super::*, bitcoin::Witness, reqwest::Url, std::net::TcpListener, tempfile::TempDir};
 
   struct TestServer {
     bitcoin_rpc_server: test_bitcoincore_rpc::Handle,
@@ -1131,6 +1283,34 @@ mod tests {
     assert_eq!(response.text().unwrap(), "2");
   }
 
+  #[test]
+  fn recursive_block_height_endpoint() {
+    let test_server = TestServer::new();
+
+    test_server.assert_response("/r/blockheight", StatusCode::OK, "0");
+
+    test_server.bitcoin_rpc_server.mine_blocks(1);
+
+    test_server.assert_response("/r/blockheight", StatusCode::OK, "1");
+  }
+
+  #[test]
+  fn recursive_block_hash_endpoints() {
+    let test_server = TestServer::new();
+
+    let hash = test_server.bitcoin_rpc_server.mine_blocks(1)[0].block_hash();
+
+    test_server.assert_response("/r/blockhash", StatusCode::OK, &format!("\"{hash}\""));
+
+    test_server.assert_response("/r/blockhash/1", StatusCode::OK, &format!("\"{hash}\""));
+
+    test_server.assert_response(
+      "/r/blockhash/2",
+      StatusCode::NOT_FOUND,
+      "block at height 2 unknown",
+    );
+  }
+
   #[test]
   fn range_end_before_range_start_returns_400() {
     TestServer::new().assert_response(
@@ -1386,6 +1566,7 @@ mod tests {
       input_slots: &[(1, 0, 0)],
       output_count: 1,
       fee: 0,
+      witness: Witness::new(),
     };
     test_server.bitcoin_rpc_server.broadcast_tx(transaction);
     let block_hash = test_server.bitcoin_rpc_server.mine_blocks(1)[0].block_hash();
@@ -1684,6 +1865,7 @@ next.*",
       input_slots: &[(1, 0, 0)],
       output_count: 2,
       fee: 0,
+      witness: Witness::new(),
     });
     server.bitcoin_rpc_server.mine_blocks(1);
     server.index.update().unwrap();
@@ -1714,6 +1896,7 @@ next.*",
       input_slots: &[(1, 0, 0)],
       output_count: 2,
       fee: 2,
+      witness: Witness::new(),
     });
     server.bitcoin_rpc_server.mine_blocks(1);
     server.index.update().unwrap();
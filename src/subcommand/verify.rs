@@ -0,0 +1,32 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Verify {
+  #[clap(long, help = "Verify ownership of <INSCRIPTION>")]
+  inscription: InscriptionId,
+  #[clap(long, help = "Verify signature of <MESSAGE>")]
+  message: String,
+  #[clap(long, help = "Verify base64-encoded BIP-322 <SIGNATURE>")]
+  signature: String,
+}
+
+impl Verify {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open_offline(&options)?;
+
+    let satpoint = index
+      .get_inscription_satpoint_by_id(self.inscription)?
+      .ok_or_else(|| anyhow!("No inscription found for {}", self.inscription))?;
+
+    let script_pubkey = index
+      .get_script_pubkey(satpoint.outpoint)?
+      .ok_or_else(|| anyhow!("output {} not found", satpoint.outpoint))?;
+
+    bip322::verify(&script_pubkey, self.message.as_bytes(), &self.signature).with_context(|| {
+      format!(
+        "signature does not prove ownership of inscription {} in output {}",
+        self.inscription, satpoint.outpoint
+      )
+    })
+  }
+}
@@ -1,12 +1,43 @@
-use {super::*, transaction_builder::TransactionBuilder};
+use {
+  super::*,
+  bitcoin::{
+    psbt::Psbt,
+    secp256k1::Secp256k1,
+    util::{
+      amount::Denomination,
+      bip32::{ChildNumber, DerivationPath, ExtendedPrivKey},
+    },
+    Network, Witness,
+  },
+  bitcoincore_rpc::Client,
+  fee_options::FeeOptions,
+  journal::Journal,
+  preview::Preview,
+  sat_protection::SatProtection,
+  serde_json::json,
+  transaction_builder::TransactionBuilder,
+};
 
 mod balance;
+mod broadcast;
+mod bump;
+mod cpfp;
 mod create;
+mod extract;
+mod fee_options;
 mod inscribe;
 mod inscriptions;
+mod journal;
+mod offer;
+mod preview;
 mod receive;
+mod recover;
+mod restore;
+mod sat_protection;
 mod sats;
 mod send;
+mod send_batch;
+mod sign;
 mod transaction_builder;
 mod transactions;
 mod utxos;
@@ -14,19 +45,37 @@ mod utxos;
 #[derive(Debug, Parser)]
 pub(crate) enum Wallet {
   #[clap(about = "Get wallet balance")]
-  Balance,
+  Balance(balance::Balance),
+  #[clap(about = "Finalize and broadcast a PSBT")]
+  Broadcast(broadcast::Broadcast),
+  #[clap(about = "Bump the fee of an unconfirmed transaction with replace-by-fee")]
+  Bump(bump::Bump),
+  #[clap(about = "Accelerate an unconfirmed transaction with child-pays-for-parent")]
+  Cpfp(cpfp::Cpfp),
   #[clap(about = "Create a new wallet")]
   Create(create::Create),
+  #[clap(about = "Extract a sat into its own output")]
+  Extract(extract::Extract),
   #[clap(about = "Create an inscription")]
   Inscribe(inscribe::Inscribe),
   #[clap(about = "List wallet inscriptions")]
   Inscriptions(inscriptions::Inscriptions),
+  #[clap(subcommand, about = "Create and accept offers to sell inscriptions")]
+  Offer(offer::Offer),
   #[clap(about = "Generate a receive address")]
   Receive(receive::Receive),
+  #[clap(about = "Recover the commit outputs of inscriptions that failed to reveal")]
+  Recover(recover::Recover),
+  #[clap(about = "Restore wallet from mnemonic")]
+  Restore(restore::Restore),
   #[clap(about = "List wallet satoshis")]
   Sats(sats::Sats),
   #[clap(about = "Send a satoshi or inscription")]
   Send(send::Send),
+  #[clap(about = "Send several satoshis or inscriptions in one transaction")]
+  SendBatch(send_batch::SendBatch),
+  #[clap(about = "Sign a message with the key controlling an inscription")]
+  Sign(sign::Sign),
   #[clap(about = "See wallet transactions")]
   Transactions(transactions::Transactions),
   #[clap(about = "List wallet UTXOs")]
@@ -36,21 +85,103 @@ pub(crate) enum Wallet {
 impl Wallet {
   pub(crate) fn run(self, options: Options) -> Result {
     match self {
-      Self::Balance => balance::run(options),
+      Self::Balance(balance) => balance.run(options),
+      Self::Broadcast(broadcast) => broadcast.run(options),
+      Self::Bump(bump) => bump.run(options),
+      Self::Cpfp(cpfp) => cpfp.run(options),
       Self::Create(create) => create.run(options),
+      Self::Extract(extract) => extract.run(options),
       Self::Inscribe(inscribe) => inscribe.run(options),
       Self::Inscriptions(inscriptions) => inscriptions.run(options),
+      Self::Offer(offer) => offer.run(options),
       Self::Receive(receive) => receive.run(options),
+      Self::Recover(recover) => recover.run(options),
+      Self::Restore(restore) => restore.run(options),
       Self::Sats(sats) => sats.run(options),
       Self::Send(send) => send.run(options),
+      Self::SendBatch(send_batch) => send_batch.run(options),
+      Self::Sign(sign) => sign.run(options),
       Self::Transactions(transactions) => transactions.run(options),
       Self::Utxos(utxos) => utxos.run(options),
     }
   }
 }
 
+/// Create a blank wallet named `name` in bitcoind and import BIP86 taproot receive
+/// and change descriptors derived from `seed`. If `rescan` is true, the
+/// descriptors are imported with a timestamp of zero so that bitcoind rescans
+/// the whole chain for their transactions.
+fn initialize_wallet(
+  client: &Client,
+  name: &str,
+  network: Network,
+  seed: &[u8],
+  rescan: bool,
+) -> Result {
+  client.create_wallet(name, None, Some(true), None, None)?;
+
+  let timestamp = if rescan { json!(0) } else { json!("now") };
+
+  let params = descriptors(network, seed)?
+    .into_iter()
+    .zip([false, true])
+    .map(|(descriptor, internal)| {
+      let info = client.get_descriptor_info(&descriptor)?;
+
+      Ok(json!({
+        "desc": format!("{descriptor}#{}", info.checksum),
+        "active": true,
+        "timestamp": timestamp,
+        "internal": internal,
+      }))
+    })
+    .collect::<Result<Vec<serde_json::Value>>>()?;
+
+  #[derive(Deserialize)]
+  struct ImportDescriptorsResult {
+    success: bool,
+  }
+
+  let response: Vec<ImportDescriptorsResult> = client
+    .call("importdescriptors", &[params.into()])
+    .context("could not import wallet descriptors")?;
+
+  for result in response {
+    if !result.success {
+      bail!("wallet descriptor import failed");
+    }
+  }
+
+  Ok(())
+}
+
+/// Returns BIP86 receive and change descriptors for the account at
+/// `m/86'/coin_type'/0'` derived from `seed`.
+fn descriptors(network: Network, seed: &[u8]) -> Result<[String; 2]> {
+  let secp256k1 = Secp256k1::new();
+
+  let master_private_key = ExtendedPrivKey::new_master(network, seed)?;
+
+  let fingerprint = master_private_key.fingerprint(&secp256k1);
+
+  let coin_type = u32::from(network != Network::Bitcoin);
+
+  let account_private_key = master_private_key.derive_priv(
+    &secp256k1,
+    &DerivationPath::from(vec![
+      ChildNumber::from_hardened_idx(86)?,
+      ChildNumber::from_hardened_idx(coin_type)?,
+      ChildNumber::from_hardened_idx(0)?,
+    ]),
+  )?;
+
+  Ok([0, 1].map(|change| {
+    format!("tr([{fingerprint}/86'/{coin_type}'/0']{account_private_key}/{change}/*)")
+  }))
+}
+
 fn list_unspent(options: &Options, index: &Index) -> Result<Vec<(OutPoint, Vec<(u64, u64)>)>> {
-  let client = options.bitcoin_rpc_client()?;
+  let client = options.bitcoin_rpc_client_for_wallet()?;
 
   client
     .list_unspent(None, None, None, None, None)?
@@ -67,7 +198,7 @@ fn list_unspent(options: &Options, index: &Index) -> Result<Vec<(OutPoint, Vec<(
 }
 
 fn list_utxos(options: &Options) -> Result<BTreeMap<OutPoint, Amount>> {
-  let client = options.bitcoin_rpc_client()?;
+  let client = options.bitcoin_rpc_client_for_wallet()?;
 
   Ok(
     client
@@ -84,7 +215,7 @@ fn list_utxos(options: &Options) -> Result<BTreeMap<OutPoint, Amount>> {
 }
 
 fn get_change_addresses(options: &Options, n: usize) -> Result<Vec<Address>> {
-  let client = options.bitcoin_rpc_client()?;
+  let client = options.bitcoin_rpc_client_for_wallet()?;
 
   let mut addresses = Vec::new();
   for _ in 0..n {
@@ -97,3 +228,222 @@ fn get_change_addresses(options: &Options, n: usize) -> Result<Vec<Address>> {
 
   Ok(addresses)
 }
+
+fn parse_amount(s: &str) -> Result<Amount> {
+  let s = s.trim();
+
+  let i = s
+    .find(|c: char| c.is_ascii_alphabetic())
+    .ok_or_else(|| anyhow!("amount `{s}` must have a denomination, e.g. `546sat`"))?;
+
+  let (value, denomination) = s.split_at(i);
+
+  Ok(Amount::from_str_in(
+    value.trim(),
+    Denomination::from_str(denomination)?,
+  )?)
+}
+
+fn transaction_fee(transaction: &Transaction, amounts: &BTreeMap<OutPoint, Amount>) -> Amount {
+  let input_value = transaction
+    .input
+    .iter()
+    .map(|tx_in| amounts[&tx_in.previous_output])
+    .sum::<Amount>();
+
+  let output_value = transaction
+    .output
+    .iter()
+    .map(|tx_out| Amount::from_sat(tx_out.value))
+    .sum::<Amount>();
+
+  input_value - output_value
+}
+
+/// Returns the satpoints in the outputs of `transaction` of the inscriptions
+/// it spends, along with the inscription it reveals, if any. Inscriptions
+/// spent to fees are omitted.
+fn inscription_satpoints(
+  transaction: &Transaction,
+  amounts: &BTreeMap<OutPoint, Amount>,
+  inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+) -> BTreeMap<SatPoint, InscriptionId> {
+  let mut offsets = Vec::new();
+
+  if Inscription::from_transaction(transaction).is_some() {
+    offsets.push((0, transaction.txid()));
+  }
+
+  let mut input_offset = 0;
+  for tx_in in &transaction.input {
+    for (satpoint, inscription_id) in inscriptions {
+      if satpoint.outpoint == tx_in.previous_output {
+        offsets.push((input_offset + satpoint.offset, *inscription_id));
+      }
+    }
+    input_offset += amounts[&tx_in.previous_output].to_sat();
+  }
+
+  offsets
+    .into_iter()
+    .filter_map(|(offset, inscription_id)| {
+      Some((output_satpoint(transaction, offset)?, inscription_id))
+    })
+    .collect()
+}
+
+/// Returns the satpoint in the outputs of `transaction` of the sat at
+/// `input_offset` in its inputs, or `None` if the sat is spent to fees.
+fn output_satpoint(transaction: &Transaction, input_offset: u64) -> Option<SatPoint> {
+  let mut output_start = 0;
+  for (vout, tx_out) in (0..).zip(&transaction.output) {
+    let output_end = output_start + tx_out.value;
+    if (output_start..output_end).contains(&input_offset) {
+      return Some(SatPoint {
+        outpoint: OutPoint::new(transaction.txid(), vout),
+        offset: input_offset - output_start,
+      });
+    }
+    output_start = output_end;
+  }
+
+  None
+}
+
+/// Create a PSBT for `unsigned_transaction`, with the outputs it spends filled
+/// in, and have the wallet add key origins and taproot fields for the inputs it
+/// controls without signing them.
+fn create_psbt(client: &Client, unsigned_transaction: &Transaction) -> Result<String> {
+  Ok(
+    client
+      .wallet_process_psbt(
+        &encode_psbt(&unsigned_psbt(client, unsigned_transaction)?),
+        Some(false),
+        None,
+        Some(true),
+      )
+      .context("wallet failed to process PSBT")?
+      .psbt,
+  )
+}
+
+/// Create a PSBT for `unsigned_transaction`, with the outputs it spends filled
+/// in.
+fn unsigned_psbt(client: &Client, unsigned_transaction: &Transaction) -> Result<Psbt> {
+  let mut psbt = Psbt::from_unsigned_tx(unsigned_transaction.clone())?;
+
+  for (input, tx_in) in psbt.inputs.iter_mut().zip(&unsigned_transaction.input) {
+    let outpoint = tx_in.previous_output;
+
+    let previous_tx = client.get_raw_transaction(&outpoint.txid, None)?;
+
+    let tx_out = previous_tx
+      .output
+      .get(usize::try_from(outpoint.vout)?)
+      .ok_or_else(|| anyhow!("input {outpoint} not found"))?
+      .clone();
+
+    if tx_out.script_pubkey.is_witness_program() {
+      input.witness_utxo = Some(tx_out);
+    } else {
+      input.non_witness_utxo = Some(previous_tx);
+    }
+  }
+
+  Ok(psbt)
+}
+
+fn encode_psbt(psbt: &Psbt) -> String {
+  base64::encode(bitcoin::consensus::serialize(psbt))
+}
+
+fn decode_psbt(psbt: &str) -> Result<Psbt> {
+  Ok(bitcoin::consensus::deserialize(
+    &base64::decode(psbt.trim()).context("PSBT is not valid base64")?,
+  )?)
+}
+
+/// Returns a placeholder witness of the size required to spend
+/// `script_pubkey`, for estimating the size of transactions before signing.
+fn dummy_witness(script_pubkey: &Script) -> Witness {
+  if script_pubkey.is_v1_p2tr() {
+    Witness::from_vec(vec![vec![0; 64]])
+  } else {
+    // Assume a P2WPKH-sized witness for other script types
+    Witness::from_vec(vec![vec![0; 72], vec![0; 33]])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::PackedLockTime};
+
+  #[test]
+  fn parse_amounts() {
+    assert_eq!(parse_amount("546sat").unwrap(), Amount::from_sat(546));
+    assert_eq!(parse_amount("546 sat").unwrap(), Amount::from_sat(546));
+    assert_eq!(
+      parse_amount("0.0001 BTC").unwrap(),
+      Amount::from_sat(10_000)
+    );
+    assert!(parse_amount("546").is_err());
+    assert!(parse_amount("546 foo").is_err());
+  }
+
+  #[test]
+  fn descriptors_follow_bip86() {
+    let mnemonic = bip39::Mnemonic::from_str(
+      "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+    )
+    .unwrap();
+
+    assert_eq!(
+      descriptors(Network::Bitcoin, &mnemonic.to_seed("")).unwrap(),
+      [
+        "tr([73c5da0a/86'/0'/0']xprv9xgqHN7yz9MwCkxsBPN5qetuNdQSUttZNKw1dcYTV4mkaAFiBVGQziHs3NRSWMkCzvgjEe3n9xV8oYywvM8at9yRqyaZVz6TYYhX98VjsUk/0/*)",
+        "tr([73c5da0a/86'/0'/0']xprv9xgqHN7yz9MwCkxsBPN5qetuNdQSUttZNKw1dcYTV4mkaAFiBVGQziHs3NRSWMkCzvgjEe3n9xV8oYywvM8at9yRqyaZVz6TYYhX98VjsUk/1/*)",
+      ]
+    );
+  }
+
+  #[test]
+  fn inscription_satpoints_follow_sat_flow() {
+    let tx = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+      output: vec![
+        tx_out(4_000, change(0)),
+        tx_out(10_000, recipient()),
+        tx_out(5_800, change(1)),
+      ],
+    };
+
+    let amounts = [
+      (outpoint(1), Amount::from_sat(5_000)),
+      (outpoint(2), Amount::from_sat(15_000)),
+    ]
+    .into_iter()
+    .collect();
+
+    let inscriptions = [
+      (satpoint(1, 4_500), txid(1)),
+      (satpoint(2, 14_900), txid(2)),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+      inscription_satpoints(&tx, &amounts, &inscriptions),
+      [(
+        SatPoint {
+          outpoint: OutPoint::new(tx.txid(), 1),
+          offset: 500,
+        },
+        txid(1)
+      )]
+      .into_iter()
+      .collect()
+    );
+  }
+}
@@ -1,15 +1,187 @@
 use super::*;
 
-pub(crate) fn run(options: Options) -> Result {
-  println!(
-    "{}",
-    options
-      .bitcoin_rpc_client()?
-      .get_balances()?
-      .mine
-      .trusted
-      .to_sat()
-  );
-
-  Ok(())
+#[derive(Debug, Parser)]
+pub(crate) struct Balance {
+  #[clap(long, help = "Print balances as JSON.")]
+  json: bool,
+}
+
+/// Wallet balances in sats. Each UTXO is counted in exactly one of
+/// `inscribed`, `rare`, `unconfirmed`, and `cardinal`, in that order of
+/// precedence. `rare` is `None` if the index has no sat index, in which case
+/// UTXOs containing rare sats can't be told apart from cardinal UTXOs.
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct Balances {
+  pub(crate) cardinal: u64,
+  pub(crate) inscribed: u64,
+  pub(crate) inscriptions: u64,
+  pub(crate) rare: Option<u64>,
+  pub(crate) unconfirmed: u64,
+  pub(crate) total: u64,
+}
+
+impl Balance {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let utxos = options
+      .bitcoin_rpc_client_for_wallet()?
+      .list_unspent(Some(0), None, None, None, None)?
+      .iter()
+      .map(|utxo| {
+        (
+          OutPoint::new(utxo.txid, utxo.vout),
+          utxo.amount,
+          utxo.confirmations,
+        )
+      })
+      .collect::<Vec<(OutPoint, Amount, u32)>>();
+
+    let sat_ranges = if index.has_satoshi_index()? {
+      let mut sat_ranges = BTreeMap::new();
+      for (outpoint, _amount, _confirmations) in &utxos {
+        if let Some(List::Unspent(ranges)) = index.list(*outpoint)? {
+          sat_ranges.insert(*outpoint, ranges);
+        }
+      }
+      Some(sat_ranges)
+    } else {
+      None
+    };
+
+    let balances = Self::balances(
+      &utxos,
+      &index.get_inscriptions_by_satpoint()?,
+      sat_ranges.as_ref(),
+    );
+
+    if self.json {
+      println!("{}", serde_json::to_string_pretty(&balances)?);
+    } else {
+      println!("cardinal\t{}", balances.cardinal);
+      println!("inscribed\t{}", balances.inscribed);
+      println!("inscriptions\t{}", balances.inscriptions);
+      if let Some(rare) = balances.rare {
+        println!("rare\t{rare}");
+      }
+      println!("unconfirmed\t{}", balances.unconfirmed);
+      println!("total\t{}", balances.total);
+    }
+
+    Ok(())
+  }
+
+  fn balances(
+    utxos: &[(OutPoint, Amount, u32)],
+    inscriptions: &BTreeMap<SatPoint, Vec<InscriptionId>>,
+    sat_ranges: Option<&BTreeMap<OutPoint, Vec<(u64, u64)>>>,
+  ) -> Balances {
+    let mut balances = Balances {
+      cardinal: 0,
+      inscribed: 0,
+      inscriptions: 0,
+      rare: sat_ranges.map(|_| 0),
+      unconfirmed: 0,
+      total: 0,
+    };
+
+    let mut inscription_counts = BTreeMap::<OutPoint, u64>::new();
+    for (satpoint, ids) in inscriptions {
+      *inscription_counts.entry(satpoint.outpoint).or_default() += ids.len() as u64;
+    }
+
+    for (outpoint, amount, confirmations) in utxos {
+      let amount = amount.to_sat();
+
+      let inscription_count = inscription_counts
+        .get(outpoint)
+        .copied()
+        .unwrap_or_default();
+
+      let rare = sat_ranges
+        .and_then(|sat_ranges| sat_ranges.get(outpoint))
+        .map(|ranges| {
+          ranges
+            .iter()
+            .any(|(start, _end)| Sat(*start).rarity() > Rarity::Common)
+        })
+        .unwrap_or_default();
+
+      if inscription_count > 0 {
+        balances.inscribed += amount;
+        balances.inscriptions += inscription_count;
+      } else if rare {
+        *balances.rare.get_or_insert(0) += amount;
+      } else if *confirmations == 0 {
+        balances.unconfirmed += amount;
+      } else {
+        balances.cardinal += amount;
+      }
+
+      balances.total += amount;
+    }
+
+    balances
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn utxos_are_counted_once() {
+    let utxos = [
+      (outpoint(1), Amount::from_sat(10_000), 1),
+      (outpoint(2), Amount::from_sat(20_000), 1),
+      (outpoint(3), Amount::from_sat(30_000), 1),
+      (outpoint(4), Amount::from_sat(40_000), 0),
+    ];
+
+    let inscriptions = [
+      (satpoint(1, 0), vec![txid(1)]),
+      (satpoint(1, 100), vec![txid(2), txid(3)]),
+    ]
+    .into_iter()
+    .collect();
+
+    let sat_ranges = [
+      (
+        outpoint(1),
+        vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 10_000)],
+      ),
+      (
+        outpoint(2),
+        vec![(50 * COIN_VALUE, 50 * COIN_VALUE + 20_000)],
+      ),
+      (outpoint(3), vec![(1, 30_001)]),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+      Balance::balances(&utxos, &inscriptions, Some(&sat_ranges)),
+      Balances {
+        cardinal: 30_000,
+        inscribed: 10_000,
+        inscriptions: 3,
+        rare: Some(20_000),
+        unconfirmed: 40_000,
+        total: 100_000,
+      }
+    );
+
+    assert_eq!(
+      Balance::balances(&utxos, &inscriptions, None),
+      Balances {
+        cardinal: 50_000,
+        inscribed: 10_000,
+        inscriptions: 3,
+        rare: None,
+        unconfirmed: 40_000,
+        total: 100_000,
+      }
+    );
+  }
 }
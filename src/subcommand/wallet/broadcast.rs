@@ -0,0 +1,45 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Broadcast {
+  #[clap(help = "Finalize and broadcast base64-encoded <PSBT>")]
+  psbt: String,
+  #[clap(
+    long,
+    help = "Sign inputs of <PSBT> with the wallet before finalizing it."
+  )]
+  sign: bool,
+}
+
+impl Broadcast {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet broadcast")?;
+
+    let psbt = if self.sign {
+      client
+        .wallet_process_psbt(self.psbt.trim(), Some(true), None, None)
+        .context("failed to sign PSBT")?
+        .psbt
+    } else {
+      self.psbt.trim().into()
+    };
+
+    let finalized = client
+      .finalize_psbt(&psbt, Some(true))
+      .context("failed to finalize PSBT")?;
+
+    if !finalized.complete {
+      bail!("PSBT is not fully signed");
+    }
+
+    let tx = finalized
+      .hex
+      .ok_or_else(|| anyhow!("finalized PSBT did not contain a transaction"))?;
+
+    let txid = client.send_raw_transaction(&tx)?;
+
+    println!("{txid}");
+
+    Ok(())
+  }
+}
@@ -0,0 +1,345 @@
+use {
+  super::*,
+  bitcoin::{consensus, Witness},
+  inscribe::Inscribe,
+  std::collections::BTreeSet,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Bump {
+  #[clap(help = "Replace unconfirmed transaction <TXID> with one paying a higher fee")]
+  txid: Txid,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+  #[clap(flatten)]
+  sat_protection: SatProtection,
+}
+
+impl Bump {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet bump")?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let txid = self.txid;
+
+    let info = client.get_raw_transaction_info(&txid, None)?;
+
+    if info.confirmations.unwrap_or_default() > 0 {
+      bail!("transaction {txid} is already confirmed");
+    }
+
+    let tx = info.transaction()?;
+
+    let journal = Journal::open(&options)?;
+
+    let network = options.chain().network();
+
+    if Inscription::from_transaction(&tx).is_some() {
+      return self.bump_reveal(&client, &journal, tx, network);
+    }
+
+    // Reveal transactions spending the outputs of a journaled commit
+    // transaction are re-signed to spend its replacement
+    let entry = journal.get(txid)?;
+
+    let reveal_vouts = entry
+      .iter()
+      .flat_map(|entry| &entry.reveals)
+      .map(|(reveal_tx, _key_pair)| reveal_tx.input[0].previous_output.vout)
+      .collect::<BTreeSet<u32>>();
+
+    let mut evicted_reveal_vouts = BTreeSet::new();
+
+    for vout in 0..tx.output.len() {
+      let vout = u32::try_from(vout)?;
+      if client.get_tx_out(&txid, vout, Some(true))?.is_none() {
+        if reveal_vouts.contains(&vout) {
+          evicted_reveal_vouts.insert(vout);
+        } else {
+          bail!(
+            "cannot bump transaction {txid}, since output {txid}:{vout} is spent by an unconfirmed transaction which would be invalidated"
+          );
+        }
+      }
+    }
+
+    let mut amounts = BTreeMap::new();
+    for tx_in in &tx.input {
+      let outpoint = tx_in.previous_output;
+      let previous_tx = client.get_raw_transaction(&outpoint.txid, None)?;
+      let tx_out = previous_tx
+        .output
+        .get(usize::try_from(outpoint.vout)?)
+        .ok_or_else(|| anyhow!("input {outpoint} not found"))?;
+      amounts.insert(outpoint, Amount::from_sat(tx_out.value));
+    }
+
+    let old_fee = transaction_fee(&tx, &amounts);
+
+    // BIP 125 requires replacements to pay for the transactions they evict,
+    // which include any reveal transactions spending the original
+    let mut replaced_fee = old_fee;
+    if let Some(entry) = &entry {
+      for (reveal_tx, _key_pair) in &entry.reveals {
+        let vout = reveal_tx.input[0].previous_output.vout;
+        if evicted_reveal_vouts.contains(&vout) {
+          replaced_fee +=
+            Amount::from_sat(tx.output[usize::try_from(vout)?].value - reveal_tx.output[0].value);
+        }
+      }
+    }
+
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    let inscriptions = index.get_inscriptions(None)?;
+
+    let inscribed_outputs = inscription_satpoints(&tx, &amounts, &inscriptions)
+      .keys()
+      .map(|satpoint| satpoint.outpoint.vout)
+      .collect::<BTreeSet<u32>>();
+
+    let mut replacement = Transaction {
+      version: tx.version,
+      lock_time: tx.lock_time,
+      input: tx
+        .input
+        .iter()
+        .map(|tx_in| TxIn {
+          witness: Witness::new(),
+          ..tx_in.clone()
+        })
+        .collect(),
+      output: tx.output.clone(),
+    };
+
+    let last = tx.output.len() - 1;
+    let last_output = &tx.output[last];
+
+    let change_is_lowerable = !inscribed_outputs.contains(&u32::try_from(last)?)
+      && !reveal_vouts.contains(&u32::try_from(last)?)
+      && match Address::from_script(&last_output.script_pubkey, options.chain().network()) {
+        Ok(address) => client.get_address_info(&address)?.is_mine == Some(true),
+        Err(_) => false,
+      };
+
+    let new_fee = if change_is_lowerable {
+      let new_fee = fee_rate.fee(tx.vsize());
+
+      Self::check_replacement_fee(fee_rate, replaced_fee, new_fee, tx.vsize())?;
+
+      let additional_fee = new_fee - old_fee;
+
+      let change = Amount::from_sat(last_output.value)
+        .checked_sub(additional_fee)
+        .filter(|change| *change >= last_output.script_pubkey.dust_value())
+        .ok_or_else(|| {
+          anyhow!("change output of transaction {txid} is too small to pay additional fee")
+        })?;
+
+      replacement.output[last].value = change.to_sat();
+
+      new_fee
+    } else {
+      let change_address = get_change_addresses(&options, 1)?[0].clone();
+
+      let inscribed_utxos = inscriptions
+        .keys()
+        .map(|satpoint| satpoint.outpoint)
+        .collect::<BTreeSet<OutPoint>>();
+
+      let utxos = list_utxos(&options)?;
+
+      let protected_utxos = self
+        .sat_protection
+        .protected_sats(&index, &utxos)?
+        .into_iter()
+        .map(|satpoint| satpoint.outpoint)
+        .collect::<BTreeSet<OutPoint>>();
+
+      let mut found = None;
+
+      for (outpoint, amount) in utxos {
+        if outpoint.txid == txid
+          || amounts.contains_key(&outpoint)
+          || inscribed_utxos.contains(&outpoint)
+          || protected_utxos.contains(&outpoint)
+        {
+          continue;
+        }
+
+        let script_pubkey = client.get_raw_transaction(&outpoint.txid, None)?.output
+          [usize::try_from(outpoint.vout)?]
+        .script_pubkey
+        .clone();
+
+        let mut candidate = tx.clone();
+
+        candidate.input.push(TxIn {
+          previous_output: outpoint,
+          script_sig: Script::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: dummy_witness(&script_pubkey),
+        });
+
+        candidate.output.push(TxOut {
+          value: 0,
+          script_pubkey: change_address.script_pubkey(),
+        });
+
+        let new_fee = fee_rate.fee(candidate.vsize());
+
+        let Some(change) = (amount + old_fee).checked_sub(new_fee) else {
+          continue;
+        };
+
+        if change < change_address.script_pubkey().dust_value() {
+          continue;
+        }
+
+        Self::check_replacement_fee(fee_rate, replaced_fee, new_fee, candidate.vsize())?;
+
+        found = Some((outpoint, change, new_fee));
+        break;
+      }
+
+      let (outpoint, change, new_fee) = found.ok_or_else(|| {
+        anyhow!("wallet does not contain a cardinal UTXO large enough to pay additional fee")
+      })?;
+
+      replacement.input.push(TxIn {
+        previous_output: outpoint,
+        script_sig: Script::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      });
+
+      replacement.output.push(TxOut {
+        value: change.to_sat(),
+        script_pubkey: change_address.script_pubkey(),
+      });
+
+      new_fee
+    };
+
+    FeeOptions::report(fee_rate, new_fee);
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&replacement, None, None)?
+      .hex;
+
+    let Some(mut entry) = entry else {
+      let replacement_txid = client.send_raw_transaction(&signed_tx)?;
+
+      println!("{replacement_txid}");
+
+      return Ok(());
+    };
+
+    entry.commit = consensus::deserialize(&signed_tx)?;
+
+    for (reveal_tx, key_pair) in &mut entry.reveals {
+      *reveal_tx =
+        Inscribe::re_sign_reveal(reveal_tx, *key_pair, &entry.commit, fee_rate, network)?;
+    }
+
+    journal.write(&entry)?;
+
+    let replacement_txid = match client.send_raw_transaction(&entry.commit) {
+      Ok(replacement_txid) => replacement_txid,
+      Err(err) => {
+        journal.remove(entry.commit.txid())?;
+        return Err(err.into());
+      }
+    };
+
+    journal.remove(txid)?;
+
+    println!("commit\t{replacement_txid}");
+
+    for (reveal_tx, _key_pair) in &entry.reveals {
+      if evicted_reveal_vouts.contains(&reveal_tx.input[0].previous_output.vout) {
+        println!("reveal\t{}", client.send_raw_transaction(reveal_tx)?);
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Replace a reveal transaction with one re-signed at a higher fee rate
+  /// with its journaled key, paying the additional fee out of its postage.
+  fn bump_reveal(
+    &self,
+    client: &Client,
+    journal: &Journal,
+    reveal_tx: Transaction,
+    network: bitcoin::Network,
+  ) -> Result {
+    let txid = reveal_tx.txid();
+
+    let commit_outpoint = reveal_tx.input[0].previous_output;
+
+    let no_key = || {
+      anyhow!(
+        "cannot bump reveal transaction {txid}, since the journal does not contain its key, use `ord wallet cpfp` instead"
+      )
+    };
+
+    let mut entry = journal.get(commit_outpoint.txid)?.ok_or_else(no_key)?;
+
+    let commit_value = entry.commit.output[usize::try_from(commit_outpoint.vout)?].value;
+
+    let (journaled_reveal_tx, key_pair) = entry
+      .reveals
+      .iter_mut()
+      .find(|(journaled_reveal_tx, _key_pair)| {
+        journaled_reveal_tx.input[0].previous_output == commit_outpoint
+      })
+      .ok_or_else(no_key)?;
+
+    let old_fee = Amount::from_sat(commit_value - reveal_tx.output[0].value);
+
+    let fee_rate = self.fee_options.fee_rate(client)?;
+
+    let replacement =
+      Inscribe::re_sign_reveal(&reveal_tx, *key_pair, &entry.commit, fee_rate, network)?;
+
+    let new_fee = Amount::from_sat(commit_value - replacement.output[0].value);
+
+    Self::check_replacement_fee(fee_rate, old_fee, new_fee, replacement.vsize())?;
+
+    FeeOptions::report(fee_rate, new_fee);
+
+    *journaled_reveal_tx = replacement.clone();
+
+    journal.write(&entry)?;
+
+    let replacement_txid = client.send_raw_transaction(&replacement)?;
+
+    println!("{replacement_txid}");
+
+    Ok(())
+  }
+
+  fn check_replacement_fee(
+    fee_rate: FeeRate,
+    old_fee: Amount,
+    new_fee: Amount,
+    vsize: usize,
+  ) -> Result {
+    // BIP 125 requires replacements to pay for their own relay at the
+    // incremental relay fee rate of 1 sat/vB on top of the original fee.
+    let minimum_fee = old_fee + Amount::from_sat(vsize.try_into()?);
+
+    if new_fee < minimum_fee {
+      bail!(
+        "replacement fee of {} sats at {fee_rate} is below minimum replacement fee of {} sats",
+        new_fee.to_sat(),
+        minimum_fee.to_sat(),
+      );
+    }
+
+    Ok(())
+  }
+}
@@ -0,0 +1,327 @@
+use {
+  super::*,
+  bitcoin::{PackedLockTime, Witness},
+  bitcoincore_rpc::Client,
+  std::collections::BTreeSet,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Cpfp {
+  #[clap(help = "Accelerate unconfirmed transaction <TXID> by spending one of its outputs")]
+  txid: Txid,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+  #[clap(flatten)]
+  sat_protection: SatProtection,
+}
+
+struct Package {
+  amounts: BTreeMap<OutPoint, Amount>,
+  fee: Amount,
+  vsize: usize,
+}
+
+impl Cpfp {
+  const MAX_BUILD_ATTEMPTS: usize = 5;
+
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet cpfp")?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let txid = self.txid;
+
+    let info = client.get_raw_transaction_info(&txid, None)?;
+
+    if info.confirmations.unwrap_or_default() > 0 {
+      bail!("transaction {txid} is already confirmed");
+    }
+
+    let parent = info.transaction()?;
+
+    let package = Self::unconfirmed_package(&client, &parent)?;
+
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    if package.fee >= fee_rate.fee(package.vsize) {
+      bail!("transaction {txid} and its unconfirmed ancestors already pay at least {fee_rate}");
+    }
+
+    let mut inscriptions = index.get_inscriptions(None)?;
+
+    let parent_inscriptions = inscription_satpoints(&parent, &package.amounts, &inscriptions);
+
+    let mut cardinal = Vec::new();
+    let mut inscribed = Vec::new();
+
+    for (vout, tx_out) in (0..).zip(&parent.output) {
+      let Ok(address) = Address::from_script(&tx_out.script_pubkey, options.chain().network())
+      else {
+        continue;
+      };
+
+      if client.get_address_info(&address)?.is_mine != Some(true)
+        || client.get_tx_out(&txid, vout, Some(true))?.is_none()
+      {
+        continue;
+      }
+
+      let outpoint = OutPoint::new(txid, vout);
+
+      match parent_inscriptions
+        .keys()
+        .find(|satpoint| satpoint.outpoint == outpoint)
+      {
+        Some(satpoint) => inscribed.push((*satpoint, Amount::from_sat(tx_out.value))),
+        None => cardinal.push((outpoint, tx_out)),
+      }
+    }
+
+    if cardinal.is_empty() && inscribed.is_empty() {
+      bail!("transaction {txid} has no unspent outputs belonging to this wallet");
+    }
+
+    let mut child = None;
+
+    for (outpoint, tx_out) in cardinal {
+      if let Some(transaction) =
+        Self::spend_cardinal(&options, &package, fee_rate, outpoint, tx_out)?
+      {
+        child = Some(transaction);
+        break;
+      }
+    }
+
+    if child.is_none() {
+      if let Some((satpoint, amount)) = inscribed.first() {
+        inscriptions.extend(parent_inscriptions);
+
+        let mut amounts = list_utxos(&options)?
+          .into_iter()
+          .filter(|(outpoint, _)| !package.amounts.contains_key(outpoint))
+          .collect::<BTreeMap<OutPoint, Amount>>();
+
+        let protected_sats = self.sat_protection.protected_sats(&index, &amounts)?;
+
+        amounts.insert(satpoint.outpoint, *amount);
+
+        child = Some(Self::spend_inscription(
+          &options,
+          &package,
+          fee_rate,
+          *satpoint,
+          inscriptions,
+          protected_sats,
+          amounts,
+        )?);
+      }
+    }
+
+    let child = child.ok_or_else(|| {
+      anyhow!("outputs of transaction {txid} belonging to this wallet are too small to pay fee")
+    })?;
+
+    let mut amounts = list_utxos(&options)?;
+    for (vout, tx_out) in (0..).zip(&parent.output) {
+      amounts.insert(OutPoint::new(txid, vout), Amount::from_sat(tx_out.value));
+    }
+
+    FeeOptions::report(fee_rate, transaction_fee(&child, &amounts));
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&child, None, None)?
+      .hex;
+
+    let child_txid = client.send_raw_transaction(&signed_tx)?;
+
+    println!("{child_txid}");
+
+    Ok(())
+  }
+
+  /// Collect `parent` and its unconfirmed ancestors, whose fees must be paid
+  /// for by the child along with its own.
+  fn unconfirmed_package(client: &Client, parent: &Transaction) -> Result<Package> {
+    let mut package = Package {
+      amounts: BTreeMap::new(),
+      fee: Amount::ZERO,
+      vsize: 0,
+    };
+
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![parent.clone()];
+
+    while let Some(tx) = pending.pop() {
+      if !seen.insert(tx.txid()) {
+        continue;
+      }
+
+      for tx_in in &tx.input {
+        let outpoint = tx_in.previous_output;
+
+        let info = client.get_raw_transaction_info(&outpoint.txid, None)?;
+        let previous_tx = info.transaction()?;
+
+        let tx_out = previous_tx
+          .output
+          .get(usize::try_from(outpoint.vout)?)
+          .ok_or_else(|| anyhow!("input {outpoint} not found"))?;
+
+        package
+          .amounts
+          .insert(outpoint, Amount::from_sat(tx_out.value));
+
+        if info.confirmations.unwrap_or_default() == 0 {
+          pending.push(previous_tx);
+        }
+      }
+
+      package.fee += transaction_fee(&tx, &package.amounts);
+      package.vsize += tx.vsize();
+    }
+
+    Ok(package)
+  }
+
+  /// The fee a child of `child_vsize` vbytes must pay to bring `package` to
+  /// `fee_rate`, which is never less than the child's own minimum relay fee
+  /// of 1 sat/vB.
+  fn child_fee(package: &Package, fee_rate: FeeRate, child_vsize: usize) -> Result<Amount> {
+    Ok(
+      (fee_rate.fee(package.vsize + child_vsize) - package.fee)
+        .max(Amount::from_sat(child_vsize.try_into()?)),
+    )
+  }
+
+  fn spend_cardinal(
+    options: &Options,
+    package: &Package,
+    fee_rate: FeeRate,
+    outpoint: OutPoint,
+    tx_out: &TxOut,
+  ) -> Result<Option<Transaction>> {
+    let change_address = get_change_addresses(options, 1)?[0].clone();
+
+    let mut child = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint,
+        script_sig: Script::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: dummy_witness(&tx_out.script_pubkey),
+      }],
+      output: vec![TxOut {
+        value: 0,
+        script_pubkey: change_address.script_pubkey(),
+      }],
+    };
+
+    let fee = Self::child_fee(package, fee_rate, child.vsize())?;
+
+    let Some(change) = Amount::from_sat(tx_out.value)
+      .checked_sub(fee)
+      .filter(|change| *change >= change_address.script_pubkey().dust_value())
+    else {
+      return Ok(None);
+    };
+
+    child.input[0].witness = Witness::new();
+    child.output[0].value = change.to_sat();
+
+    Ok(Some(child))
+  }
+
+  /// Spend the inscription back to the wallet using `TransactionBuilder`,
+  /// whose invariants guarantee that the inscribed sat lands at the start of
+  /// an output we control. Since the child's size depends on whether padding
+  /// or postage inputs are needed, rebuild at the fee rate implied by each
+  /// attempt until its size settles.
+  fn spend_inscription(
+    options: &Options,
+    package: &Package,
+    fee_rate: FeeRate,
+    satpoint: SatPoint,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
+    amounts: BTreeMap<OutPoint, Amount>,
+  ) -> Result<Transaction> {
+    let mut change = get_change_addresses(options, 3)?;
+    let recipient = change.remove(0);
+
+    let mut child_fee_rate = fee_rate;
+    let mut last_vsize = None;
+
+    for _ in 0..Self::MAX_BUILD_ATTEMPTS {
+      let child = TransactionBuilder::build_transaction(
+        satpoint,
+        inscriptions.clone(),
+        protected_sats.clone(),
+        amounts.clone(),
+        recipient.clone(),
+        change.clone(),
+        child_fee_rate,
+        TransactionBuilder::TARGET_POSTAGE,
+      )?;
+
+      let vsize = TransactionBuilder::estimate_transaction_vsize(&child);
+
+      if last_vsize == Some(vsize) {
+        return Ok(child);
+      }
+
+      last_vsize = Some(vsize);
+
+      let child_fee = Self::child_fee(package, fee_rate, vsize)?;
+
+      child_fee_rate = FeeRate::try_from(child_fee.to_sat() as f64 / vsize as f64)
+        .context("fee rate required for child transaction is invalid")?;
+    }
+
+    bail!("failed to construct child transaction")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn child_fee_brings_package_to_fee_rate() {
+    let package = Package {
+      amounts: BTreeMap::new(),
+      fee: Amount::from_sat(200),
+      vsize: 200,
+    };
+
+    assert_eq!(
+      Cpfp::child_fee(&package, FeeRate::try_from(5.0).unwrap(), 100).unwrap(),
+      Amount::from_sat(1300)
+    );
+
+    assert_eq!(
+      Cpfp::child_fee(&package, FeeRate::try_from(1.5).unwrap(), 100).unwrap(),
+      Amount::from_sat(250)
+    );
+
+    assert_eq!(
+      Cpfp::child_fee(&package, FeeRate::try_from(1.1).unwrap(), 100).unwrap(),
+      Amount::from_sat(130)
+    );
+  }
+
+  #[test]
+  fn child_fee_pays_for_own_relay() {
+    let package = Package {
+      amounts: BTreeMap::new(),
+      fee: Amount::from_sat(250),
+      vsize: 200,
+    };
+
+    assert_eq!(
+      Cpfp::child_fee(&package, FeeRate::try_from(1.1).unwrap(), 100).unwrap(),
+      Amount::from_sat(100)
+    );
+  }
+}
@@ -1,13 +1,36 @@
-use super::*;
+use {
+  super::*,
+  bip39::Mnemonic,
+  bitcoin::secp256k1::rand::{self, RngCore},
+};
 
 #[derive(Debug, Parser)]
-pub(crate) struct Create {}
+pub(crate) struct Create {
+  #[clap(
+    long,
+    default_value = "",
+    help = "Use <PASSPHRASE> to derive wallet seed."
+  )]
+  passphrase: String,
+}
 
 impl Create {
   pub(crate) fn run(self, options: Options) -> Result {
-    options
-      .bitcoin_rpc_client_mainnet_forbidden("ord wallet create")?
-      .create_wallet("ord", None, None, None, None)?;
+    let mut entropy = [0; 16];
+    rand::thread_rng().fill_bytes(&mut entropy);
+
+    let mnemonic = Mnemonic::from_entropy(&entropy)?;
+
+    initialize_wallet(
+      &options.bitcoin_rpc_client_mainnet_forbidden("ord wallet create")?,
+      &options.wallet,
+      options.chain().network(),
+      &mnemonic.to_seed(&self.passphrase),
+      false,
+    )?;
+
+    println!("{mnemonic}");
+
     Ok(())
   }
 }
@@ -0,0 +1,115 @@
+use super::*;
+
+#[derive(Debug)]
+enum Target {
+  Sat(Sat),
+  SatPoint(SatPoint),
+}
+
+impl FromStr for Target {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(if s.contains(':') {
+      Self::SatPoint(s.parse()?)
+    } else {
+      Self::Sat(s.parse()?)
+    })
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Extract {
+  #[clap(help = "Extract <TARGET>, a sat or satpoint, into its own output")]
+  target: Target,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+  #[clap(flatten)]
+  sat_protection: SatProtection,
+  #[clap(
+    long,
+    value_parser = parse_amount,
+    help = "Target <POSTAGE> of output carrying the extracted sat. [default: 10000sat]"
+  )]
+  postage: Option<Amount>,
+  #[clap(
+    long,
+    help = "Print transaction, including where inscriptions and rare sats in its inputs will land, instead of signing and broadcasting it."
+  )]
+  dry_run: bool,
+}
+
+impl Extract {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet extract")?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let satpoint = match self.target {
+      Target::SatPoint(satpoint) => satpoint,
+      Target::Sat(sat) => index
+        .find(sat.n())?
+        .ok_or_else(|| anyhow!("sat {sat} has not been mined as of index height"))?,
+    };
+
+    let utxos = list_utxos(&options)?;
+
+    let amount = *utxos
+      .get(&satpoint.outpoint)
+      .ok_or_else(|| anyhow!("satpoint {satpoint} not in wallet"))?;
+
+    let postage = self.postage.unwrap_or(TransactionBuilder::TARGET_POSTAGE);
+
+    if satpoint.offset == 0 && amount <= postage {
+      bail!("sat at {satpoint} is already at the start of an output of at most {postage}");
+    }
+
+    let inscriptions = index.get_inscriptions(None)?;
+
+    let protected_sats = self.sat_protection.protected_sats(&index, &utxos)?;
+
+    let mut addresses = get_change_addresses(&options, 3)?;
+    let recipient = addresses.remove(0);
+
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    let unsigned_transaction = TransactionBuilder::build_transaction_to_wallet(
+      satpoint,
+      inscriptions.clone(),
+      protected_sats,
+      utxos.clone(),
+      recipient,
+      addresses,
+      fee_rate,
+      postage,
+    )?;
+
+    FeeOptions::report(fee_rate, transaction_fee(&unsigned_transaction, &utxos));
+
+    if self.dry_run {
+      let sat_ranges = Preview::input_sat_ranges(&index, &unsigned_transaction)?;
+
+      let preview = Preview::new(
+        &unsigned_transaction,
+        TransactionBuilder::estimate_transaction_vsize(&unsigned_transaction),
+        &utxos,
+        &inscriptions,
+        sat_ranges.as_ref(),
+        options.chain().network(),
+      );
+
+      println!("{}", serde_json::to_string_pretty(&preview)?);
+      return Ok(());
+    }
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let txid = client.send_raw_transaction(&signed_tx)?;
+
+    println!("{txid}");
+    Ok(())
+  }
+}
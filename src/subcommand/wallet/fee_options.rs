@@ -0,0 +1,110 @@
+use {super::*, bitcoincore_rpc::Client};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum FeePriority {
+  Low,
+  Medium,
+  High,
+}
+
+impl FeePriority {
+  fn conf_target(self) -> u16 {
+    match self {
+      Self::Low => 144,
+      Self::Medium => 6,
+      Self::High => 1,
+    }
+  }
+}
+
+impl FromStr for FeePriority {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "low" => Ok(Self::Low),
+      "medium" => Ok(Self::Medium),
+      "high" => Ok(Self::High),
+      _ => bail!("invalid fee priority `{s}`, expected `low`, `medium`, or `high`"),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct FeeOptions {
+  #[clap(
+    long,
+    conflicts_with_all = &["conf-target", "fee-priority"],
+    help = "Use fee rate of <FEE_RATE> sats/vB. [default: 1.0]"
+  )]
+  fee_rate: Option<FeeRate>,
+  #[clap(
+    long,
+    conflicts_with = "fee-priority",
+    help = "Use fee rate estimated by node for confirmation within <CONF_TARGET> blocks"
+  )]
+  conf_target: Option<u16>,
+  #[clap(
+    long,
+    help = "Use fee rate estimated by node for <FEE_PRIORITY>: low (144 blocks), medium (6 blocks), or high (1 block)"
+  )]
+  fee_priority: Option<FeePriority>,
+}
+
+impl FeeOptions {
+  const DEFAULT_FEE_RATE: f64 = 1.0;
+
+  fn conf_target(&self) -> Option<u16> {
+    self
+      .conf_target
+      .or_else(|| self.fee_priority.map(FeePriority::conf_target))
+  }
+
+  pub(crate) fn fee_rate(&self, client: &Client) -> Result<FeeRate> {
+    if let Some(fee_rate) = self.fee_rate {
+      return Ok(fee_rate);
+    }
+
+    let Some(conf_target) = self.conf_target() else {
+      return FeeRate::try_from(Self::DEFAULT_FEE_RATE);
+    };
+
+    let estimate = client
+      .estimate_smart_fee(conf_target, None)
+      .context("failed to estimate fee rate")?;
+
+    match estimate.fee_rate {
+      Some(btc_per_kvb) => FeeRate::try_from((btc_per_kvb.to_sat() as f64 / 1000.0).max(1.0))
+        .context("estimated fee rate is invalid"),
+      None => {
+        eprintln!(
+          "Node has no fee estimate for confirmation within {conf_target} blocks, falling back to {} sat/vB",
+          Self::DEFAULT_FEE_RATE
+        );
+        FeeRate::try_from(Self::DEFAULT_FEE_RATE)
+      }
+    }
+  }
+
+  /// Whether a fee rate or fee estimate was explicitly requested.
+  pub(crate) fn is_specified(&self) -> bool {
+    self.fee_rate.is_some() || self.conf_target().is_some()
+  }
+
+  pub(crate) fn report(fee_rate: FeeRate, fee: Amount) {
+    eprintln!("Paying fee of {} sats at {fee_rate}", fee.to_sat());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fee_priority_conf_targets() {
+    assert_eq!("low".parse::<FeePriority>().unwrap().conf_target(), 144);
+    assert_eq!("medium".parse::<FeePriority>().unwrap().conf_target(), 6);
+    assert_eq!("high".parse::<FeePriority>().unwrap().conf_target(), 1);
+    assert!("urgent".parse::<FeePriority>().is_err());
+  }
+}
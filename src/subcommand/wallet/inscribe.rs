@@ -2,13 +2,15 @@ use {
   super::*,
   bitcoin::{
     blockdata::{opcodes, script},
+    consensus,
+    psbt::Psbt,
     schnorr::{TapTweak, TweakedKeyPair, TweakedPublicKey, UntweakedKeyPair},
     secp256k1::{
       self, constants::SCHNORR_SIGNATURE_SIZE, rand, schnorr::Signature, Secp256k1, XOnlyPublicKey,
     },
     util::key::PrivateKey,
     util::sighash::{Prevouts, SighashCache},
-    util::taproot::{LeafVersion, TapLeafHash, TaprootBuilder},
+    util::taproot::{ControlBlock, LeafVersion, TapLeafHash, TaprootBuilder, TaprootSpendInfo},
     PackedLockTime, SchnorrSighashType, Witness,
   },
   bitcoincore_rpc::Client,
@@ -20,77 +22,692 @@ use {
 pub(crate) struct Inscribe {
   #[clap(long, help = "Inscribe <SATPOINT>")]
   satpoint: Option<SatPoint>,
-  #[clap(long, help = "Inscribe sat with contents of <FILE>")]
-  file: PathBuf,
+  #[clap(
+    long,
+    conflicts_with = "satpoint",
+    help = "Inscribe <SAT>, which must be in the wallet. Requires index with `--index-sats`."
+  )]
+  sat: Option<Sat>,
+  #[clap(
+    long,
+    conflicts_with_all = &["satpoint", "sat"],
+    help = "Inscribe a sat in the wallet of <RARITY>. Requires index with `--index-sats`."
+  )]
+  rarity: Option<Rarity>,
+  #[clap(
+    long,
+    required_unless_present_any = &["delegate", "batch", "resume"],
+    help = "Inscribe sat with contents of <FILE>"
+  )]
+  file: Option<PathBuf>,
+  #[clap(
+    long,
+    conflicts_with = "file",
+    help = "Inscribe sat with a content-less inscription that delegates to <DELEGATE>"
+  )]
+  delegate: Option<InscriptionId>,
+  #[clap(
+    long,
+    conflicts_with_all = &["satpoint", "sat", "rarity", "file", "delegate", "dry-run"],
+    help = "Inscribe each file in directory <BATCH>, or each file listed in manifest <BATCH>, using a single commit transaction"
+  )]
+  batch: Option<PathBuf>,
+  #[clap(
+    long,
+    help = "Send inscription to <DESTINATION> instead of to a new wallet address"
+  )]
+  destination: Option<Address>,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+  #[clap(flatten)]
+  sat_protection: SatProtection,
+  #[clap(
+    long,
+    value_parser = parse_amount,
+    help = "Include <POSTAGE> in the inscription output. [default: 10000sat]"
+  )]
+  postage: Option<Amount>,
+  #[clap(
+    long,
+    help = "Print PSBTs of unsigned commit transaction and signed reveal transaction instead of broadcasting them."
+  )]
+  psbt: bool,
+  #[clap(
+    long,
+    conflicts_with = "psbt",
+    help = "Print commit and reveal transactions, including where inscriptions and rare sats in their inputs will land, instead of signing and broadcasting them."
+  )]
+  dry_run: bool,
+  #[clap(
+    long,
+    conflicts_with_all = &["satpoint", "sat", "rarity", "file", "delegate", "batch", "destination", "postage", "psbt", "dry-run"],
+    help = "Broadcast journaled commit and reveal transactions that failed to broadcast, re-signing reveal transactions if a fee rate is given."
+  )]
+  resume: bool,
+}
+
+#[derive(Serialize)]
+struct DryRun {
+  commit: Preview,
+  reveal: Preview,
 }
 
 impl Inscribe {
   pub(crate) fn run(self, options: Options) -> Result {
     let client = options.bitcoin_rpc_client_mainnet_forbidden("ord wallet inscribe")?;
 
-    let inscription = Inscription::from_file(options.chain(), &self.file)?;
+    if self.resume {
+      return self.resume(&options, &client);
+    }
+
+    if let Some(destination) = &self.destination {
+      if !destination.is_valid_for_network(options.chain().network()) {
+        bail!(
+          "Address `{}` is not valid for {}",
+          destination,
+          options.chain()
+        );
+      }
+    }
 
     let index = Index::open(&options)?;
     index.update()?;
 
+    if let Some(batch) = &self.batch {
+      return self.inscribe_batch(&options, &client, &index, batch);
+    }
+
+    let inscription = match (&self.file, self.delegate) {
+      (Some(file), None) => Inscription::from_file(options.chain(), file)?,
+      (None, Some(delegate)) => {
+        if index.get_inscription_by_inscription_id(delegate)?.is_none() {
+          bail!("delegate {delegate} is not an inscription");
+        }
+        Inscription::delegated(delegate)
+      }
+      _ => unreachable!(),
+    };
+
     let utxos = list_utxos(&options)?;
 
     let inscriptions = index.get_inscriptions(None)?;
 
+    let satpoint = match (self.satpoint, self.sat, self.rarity) {
+      (None, None, None) => None,
+      (Some(satpoint), None, None) => Some(satpoint),
+      (None, sat, rarity) => {
+        if !index.has_satoshi_index()? {
+          bail!("selecting sats requires index created with `--index-sats` flag");
+        }
+
+        let sat_ranges = list_unspent(&options, &index)?;
+
+        Some(match (sat, rarity) {
+          (Some(sat), None) => Inscribe::find_sat(sat, &sat_ranges)
+            .ok_or_else(|| anyhow!("sat {sat} not in wallet"))?,
+          (None, Some(rarity)) => Inscribe::find_rarity(rarity, &sat_ranges, &inscriptions)
+            .ok_or_else(|| anyhow!("wallet contains no uninscribed {rarity} sats"))?,
+          _ => unreachable!(),
+        })
+      }
+      _ => unreachable!(),
+    };
+
+    let protected_sats = self.sat_protection.protected_sats(&index, &utxos)?;
+
     let commit_tx_change = get_change_addresses(&options, 2)?;
 
-    let reveal_tx_destination = get_change_addresses(&options, 1)?[0].clone();
+    let reveal_tx_destination = match &self.destination {
+      Some(destination) => destination.clone(),
+      None => get_change_addresses(&options, 1)?[0].clone(),
+    };
+
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    let (unsigned_commit_tx, reveal_tx, key_pair) = Inscribe::create_inscription_transactions(
+      satpoint,
+      inscription,
+      inscriptions.clone(),
+      protected_sats,
+      options.chain().network(),
+      utxos.clone(),
+      commit_tx_change,
+      reveal_tx_destination,
+      fee_rate,
+      self.postage.unwrap_or(TransactionBuilder::TARGET_POSTAGE),
+    )?;
+
+    let commit_fee = transaction_fee(&unsigned_commit_tx, &utxos);
+
+    let reveal_fee = Amount::from_sat(
+      unsigned_commit_tx.output[usize::try_from(reveal_tx.input[0].previous_output.vout)?].value
+        - reveal_tx.output[0].value,
+    );
+
+    FeeOptions::report(fee_rate, commit_fee + reveal_fee);
+
+    if self.dry_run {
+      let network = options.chain().network();
+
+      let commit_sat_ranges = Preview::input_sat_ranges(&index, &unsigned_commit_tx)?;
+
+      let reveal_outpoint = reveal_tx.input[0].previous_output;
+      let reveal_vout = usize::try_from(reveal_outpoint.vout)?;
+
+      let reveal_sat_ranges = commit_sat_ranges.as_ref().map(|sat_ranges| {
+        [(
+          reveal_outpoint,
+          Preview::output_sat_ranges(&unsigned_commit_tx, sat_ranges).swap_remove(reveal_vout),
+        )]
+        .into_iter()
+        .collect()
+      });
+
+      let reveal_amounts = [(
+        reveal_outpoint,
+        Amount::from_sat(unsigned_commit_tx.output[reveal_vout].value),
+      )]
+      .into_iter()
+      .collect();
+
+      let dry_run = DryRun {
+        commit: Preview::new(
+          &unsigned_commit_tx,
+          TransactionBuilder::estimate_transaction_vsize(&unsigned_commit_tx),
+          &utxos,
+          &inscriptions,
+          commit_sat_ranges.as_ref(),
+          network,
+        ),
+        reveal: Preview::new(
+          &reveal_tx,
+          reveal_tx.vsize(),
+          &reveal_amounts,
+          &inscriptions,
+          reveal_sat_ranges.as_ref(),
+          network,
+        ),
+      };
+
+      println!("{}", serde_json::to_string_pretty(&dry_run)?);
+      return Ok(());
+    }
+
+    Inscribe::backup_recovery_key(
+      &client,
+      Inscribe::recovery_key_pair(key_pair, &reveal_tx)?,
+      options.chain().network(),
+    )?;
+
+    if self.psbt {
+      println!("commit\t{}", create_psbt(&client, &unsigned_commit_tx)?);
+      println!(
+        "reveal\t{}",
+        Inscribe::reveal_psbt(&unsigned_commit_tx, &reveal_tx)?
+      );
+      return Ok(());
+    }
+
+    let (commit_txid, reveal_txids) = Inscribe::broadcast(
+      &client,
+      &Journal::open(&options)?,
+      &unsigned_commit_tx,
+      vec![(reveal_tx, key_pair)],
+    )?;
+
+    println!("commit\t{commit_txid}");
+    println!("reveal\t{}", reveal_txids[0]);
+    Ok(())
+  }
+
+  fn inscribe_batch(
+    &self,
+    options: &Options,
+    client: &Client,
+    index: &Index,
+    batch: &Path,
+  ) -> Result {
+    let files = Self::batch_files(batch)?;
+
+    let batch = files
+      .iter()
+      .map(|file| Inscription::from_file(options.chain(), file))
+      .collect::<Result<Vec<Inscription>>>()?;
+
+    let utxos = list_utxos(options)?;
+
+    let inscriptions = index.get_inscriptions(None)?;
+
+    let protected_sats = self.sat_protection.protected_sats(index, &utxos)?;
+
+    let commit_tx_change = get_change_addresses(options, 1)?;
+
+    let reveal_tx_destinations = match &self.destination {
+      Some(destination) => vec![destination.clone(); batch.len()],
+      None => get_change_addresses(options, batch.len())?,
+    };
+
+    let fee_rate = self.fee_options.fee_rate(client)?;
+
+    let (unsigned_commit_tx, reveals) = Inscribe::create_batch_inscription_transactions(
+      batch,
+      inscriptions,
+      protected_sats,
+      options.chain().network(),
+      utxos.clone(),
+      commit_tx_change,
+      reveal_tx_destinations,
+      fee_rate,
+      self.postage.unwrap_or(TransactionBuilder::TARGET_POSTAGE),
+    )?;
+
+    let mut fee = transaction_fee(&unsigned_commit_tx, &utxos);
+    for (reveal_tx, _key_pair) in &reveals {
+      fee += Amount::from_sat(
+        unsigned_commit_tx.output[usize::try_from(reveal_tx.input[0].previous_output.vout)?].value
+          - reveal_tx.output[0].value,
+      );
+    }
+
+    FeeOptions::report(fee_rate, fee);
 
-    let (unsigned_commit_tx, reveal_tx, recovery_key_pair) =
-      Inscribe::create_inscription_transactions(
-        self.satpoint,
-        inscription,
-        inscriptions,
+    for (reveal_tx, key_pair) in &reveals {
+      Inscribe::backup_recovery_key(
+        client,
+        Inscribe::recovery_key_pair(*key_pair, reveal_tx)?,
         options.chain().network(),
-        utxos,
-        commit_tx_change,
-        reveal_tx_destination,
       )?;
+    }
 
-    Inscribe::backup_recovery_key(&client, recovery_key_pair, options.chain().network())?;
+    if self.psbt {
+      println!("commit\t{}", create_psbt(client, &unsigned_commit_tx)?);
+      for ((reveal_tx, _key_pair), file) in reveals.iter().zip(&files) {
+        println!(
+          "reveal\t{}\t{}",
+          Inscribe::reveal_psbt(&unsigned_commit_tx, reveal_tx)?,
+          file.display()
+        );
+      }
+      return Ok(());
+    }
+
+    let (commit_txid, reveal_txids) = Inscribe::broadcast(
+      client,
+      &Journal::open(options)?,
+      &unsigned_commit_tx,
+      reveals,
+    )?;
 
-    let signed_raw_commit_tx = client
-      .sign_raw_transaction_with_wallet(&unsigned_commit_tx, None, None)?
-      .hex;
+    println!("commit\t{commit_txid}");
 
-    let commit_txid = client
-      .send_raw_transaction(&signed_raw_commit_tx)
-      .context("Failed to send commit transaction")?;
+    for (reveal_txid, file) in reveal_txids.iter().zip(&files) {
+      println!("reveal\t{reveal_txid}\t{}", file.display());
+    }
 
-    let reveal_txid = client
-      .send_raw_transaction(&reveal_tx)
-      .context("Failed to send reveal transaction")?;
+    Ok(())
+  }
+
+  /// Bitcoin Core by default accepts at most 25 unconfirmed transactions
+  /// descending from an unconfirmed transaction, including itself, so at most
+  /// 24 reveal transactions can be broadcast before their commit transaction
+  /// confirms.
+  const MAX_UNCONFIRMED_REVEALS: usize = 24;
+
+  /// Sign the commit transaction and journal it along with its reveal
+  /// transactions before broadcasting them, so that if any fail to broadcast
+  /// they can be retried with `--resume`, and so that they can be re-signed
+  /// by `ord wallet bump` until they confirm. Reveal transactions beyond
+  /// `MAX_UNCONFIRMED_REVEALS` are broadcast with `--resume` once the commit
+  /// transaction confirms.
+  fn broadcast(
+    client: &Client,
+    journal: &Journal,
+    unsigned_commit_tx: &Transaction,
+    reveals: Vec<(Transaction, UntweakedKeyPair)>,
+  ) -> Result<(Txid, Vec<Txid>)> {
+    let entry = journal::Entry {
+      commit: consensus::deserialize(
+        &client
+          .sign_raw_transaction_with_wallet(unsigned_commit_tx, None, None)?
+          .hex,
+      )?,
+      reveals,
+    };
+
+    journal.write(&entry)?;
+
+    let commit_txid = match client.send_raw_transaction(&entry.commit) {
+      Ok(commit_txid) => commit_txid,
+      Err(err) => {
+        journal.remove(entry.commit.txid())?;
+        return Err(anyhow::Error::from(err).context("Failed to send commit transaction"));
+      }
+    };
+
+    let mut reveal_txids = Vec::new();
+    for (reveal_tx, _key_pair) in entry.reveals.iter().take(Self::MAX_UNCONFIRMED_REVEALS) {
+      reveal_txids.push(client.send_raw_transaction(reveal_tx).with_context(|| {
+        format!(
+          "Failed to send reveal transaction, retry with `ord wallet inscribe --resume`, or recover commit output with `ord wallet recover {commit_txid}`"
+        )
+      })?);
+    }
+
+    if entry.reveals.len() > Self::MAX_UNCONFIRMED_REVEALS {
+      reveal_txids.extend(
+        entry.reveals[Self::MAX_UNCONFIRMED_REVEALS..]
+          .iter()
+          .map(|(reveal_tx, _key_pair)| reveal_tx.txid()),
+      );
+      Self::report_deferred_reveals(
+        commit_txid,
+        entry.reveals.len() - Self::MAX_UNCONFIRMED_REVEALS,
+      );
+    }
+
+    Ok((commit_txid, reveal_txids))
+  }
+
+  fn report_deferred_reveals(commit_txid: Txid, deferred: usize) {
+    eprintln!(
+      "{deferred} reveal transactions were not broadcast, since at most {} may spend commit transaction {commit_txid} before it confirms. Broadcast them with `ord wallet inscribe --resume` once it confirms.",
+      Self::MAX_UNCONFIRMED_REVEALS,
+    );
+  }
+
+  /// Broadcast the transactions of every journal entry with reveal
+  /// transactions left to broadcast. Failures are reported for each entry,
+  /// without stopping the remaining entries from being resumed.
+  fn resume(&self, options: &Options, client: &Client) -> Result {
+    let journal = Journal::open(options)?;
+
+    let network = options.chain().network();
+
+    let fee_rate = if self.fee_options.is_specified() {
+      Some(self.fee_options.fee_rate(client)?)
+    } else {
+      None
+    };
+
+    let mut resumed = 0;
+    let mut failed = 0;
+
+    for entry in journal.entries()? {
+      let commit_txid = entry.commit.txid();
+
+      match Self::resume_entry(client, &journal, entry, fee_rate, network) {
+        Ok(true) => resumed += 1,
+        Ok(false) => {}
+        Err(err) => {
+          eprintln!("error: failed to resume commit transaction {commit_txid}: {err:#}");
+          resumed += 1;
+          failed += 1;
+        }
+      }
+    }
+
+    if resumed == 0 {
+      bail!("journal contains no inscriptions to resume");
+    }
+
+    if failed > 0 {
+      bail!("failed to resume {failed} of {resumed} journal entries");
+    }
 
-    println!("commit\t{commit_txid}");
-    println!("reveal\t{reveal_txid}");
     Ok(())
   }
 
+  /// Broadcast the transactions of `entry`, skipping its commit transaction
+  /// if it is already known to the wallet, and reveal transactions whose
+  /// commit output has already been spent. While the commit transaction is
+  /// unconfirmed, at most `MAX_UNCONFIRMED_REVEALS` reveal transactions may
+  /// spend it. The entry is removed once all of its reveal transactions have
+  /// confirmed. Returns whether any transactions were left to broadcast.
+  fn resume_entry(
+    client: &Client,
+    journal: &Journal,
+    mut entry: journal::Entry,
+    fee_rate: Option<FeeRate>,
+    network: bitcoin::Network,
+  ) -> Result<bool> {
+    let commit_txid = entry.commit.txid();
+
+    let commit_confirmed = match client.get_transaction(&commit_txid, None) {
+      Ok(commit) => Some(commit.info.confirmations > 0),
+      Err(_) => None,
+    };
+
+    let mut pending = Vec::new();
+    for (i, (reveal_tx, _key_pair)) in entry.reveals.iter().enumerate() {
+      let vout = reveal_tx.input[0].previous_output.vout;
+      if commit_confirmed.is_none() || client.get_tx_out(&commit_txid, vout, Some(true))?.is_some()
+      {
+        pending.push(i);
+      }
+    }
+
+    if pending.is_empty() {
+      if Self::reveals_confirmed(client, &entry)? {
+        journal.remove(commit_txid)?;
+      }
+      return Ok(false);
+    }
+
+    if commit_confirmed.is_none() {
+      client
+        .send_raw_transaction(&entry.commit)
+        .context("Failed to send commit transaction")?;
+    }
+
+    println!("commit\t{commit_txid}");
+
+    let mut unconfirmed_reveals = 0;
+    let mut deferred = 0;
+
+    for i in 0..entry.reveals.len() {
+      let (reveal_tx, key_pair) = &entry.reveals[i];
+
+      if !pending.contains(&i) {
+        unconfirmed_reveals += 1;
+        println!("reveal\t{}", reveal_tx.txid());
+        continue;
+      }
+
+      if commit_confirmed != Some(true) && unconfirmed_reveals >= Self::MAX_UNCONFIRMED_REVEALS {
+        deferred += 1;
+        continue;
+      }
+
+      let reveal_tx = match fee_rate {
+        Some(fee_rate) => {
+          Inscribe::re_sign_reveal(reveal_tx, *key_pair, &entry.commit, fee_rate, network)?
+        }
+        None => reveal_tx.clone(),
+      };
+
+      let reveal_txid = client.send_raw_transaction(&reveal_tx).with_context(|| {
+        format!(
+          "Failed to send reveal transaction, retry with a higher `--fee-rate`, or recover commit output with `ord wallet recover {commit_txid}`"
+        )
+      })?;
+
+      entry.reveals[i].0 = reveal_tx;
+      journal.write(&entry)?;
+
+      unconfirmed_reveals += 1;
+
+      println!("reveal\t{reveal_txid}");
+    }
+
+    if deferred > 0 {
+      Self::report_deferred_reveals(commit_txid, deferred);
+    }
+
+    Ok(true)
+  }
+
+  /// Whether every reveal transaction of `entry` has confirmed, which is the
+  /// case once their commit outputs are spent by confirmed transactions.
+  pub(crate) fn reveals_confirmed(client: &Client, entry: &journal::Entry) -> Result<bool> {
+    let commit_txid = entry.commit.txid();
+
+    match client.get_transaction(&commit_txid, None) {
+      Ok(commit) if commit.info.confirmations > 0 => {}
+      _ => return Ok(false),
+    }
+
+    for (reveal_tx, _key_pair) in &entry.reveals {
+      let vout = reveal_tx.input[0].previous_output.vout;
+      if client
+        .get_tx_out(&commit_txid, vout, Some(false))?
+        .is_some()
+      {
+        return Ok(false);
+      }
+    }
+
+    Ok(true)
+  }
+
+  /// Re-sign journaled `reveal_tx` with `key_pair` at `fee_rate`, spending
+  /// its output of `commit_tx`, which may be a replacement of the commit
+  /// transaction it was originally signed for.
+  pub(crate) fn re_sign_reveal(
+    reveal_tx: &Transaction,
+    key_pair: UntweakedKeyPair,
+    commit_tx: &Transaction,
+    fee_rate: FeeRate,
+    network: bitcoin::Network,
+  ) -> Result<Transaction> {
+    Ok(
+      Reveal::with_key_pair(
+        key_pair,
+        Inscribe::reveal_script(reveal_tx)?,
+        reveal_tx.output[0].script_pubkey.clone(),
+        fee_rate,
+        network,
+      )
+      .sign(commit_tx, network)?
+      .0,
+    )
+  }
+
+  fn find_sat(sat: Sat, sat_ranges: &[(OutPoint, Vec<(u64, u64)>)]) -> Option<SatPoint> {
+    for (outpoint, ranges) in sat_ranges {
+      let mut offset = 0;
+      for (start, end) in ranges {
+        if (*start..*end).contains(&sat.n()) {
+          return Some(SatPoint {
+            outpoint: *outpoint,
+            offset: offset + sat.n() - start,
+          });
+        }
+        offset += end - start;
+      }
+    }
+
+    None
+  }
+
+  /// Find a sat of exactly `rarity` in an output without inscriptions. Sats
+  /// rarer than common are always the first sat of a range, so only range
+  /// starts need to be checked.
+  fn find_rarity(
+    rarity: Rarity,
+    sat_ranges: &[(OutPoint, Vec<(u64, u64)>)],
+    inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+  ) -> Option<SatPoint> {
+    let inscribed_utxos = inscriptions
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    for (outpoint, ranges) in sat_ranges {
+      if inscribed_utxos.contains(outpoint) {
+        continue;
+      }
+
+      let mut offset = 0;
+      for (start, end) in ranges {
+        if Sat(*start).rarity() == rarity {
+          return Some(SatPoint {
+            outpoint: *outpoint,
+            offset,
+          });
+        }
+        offset += end - start;
+      }
+    }
+
+    None
+  }
+
+  /// A batch is either a directory, in which case every file in it is
+  /// inscribed in order of file name, or a manifest listing one file per
+  /// line, relative to the manifest's directory.
+  fn batch_files(batch: &Path) -> Result<Vec<PathBuf>> {
+    let files = if batch.is_dir() {
+      let mut files = Vec::new();
+      for entry in
+        fs::read_dir(batch).with_context(|| format!("I/O error reading `{}`", batch.display()))?
+      {
+        let path = entry?.path();
+        if path.is_file() {
+          files.push(path);
+        }
+      }
+      files.sort();
+      files
+    } else {
+      Self::parse_manifest(
+        &fs::read_to_string(batch)
+          .with_context(|| format!("I/O error reading `{}`", batch.display()))?,
+        batch.parent().unwrap_or_else(|| Path::new("")),
+      )
+    };
+
+    if files.is_empty() {
+      bail!("batch `{}` contains no files", batch.display());
+    }
+
+    Ok(files)
+  }
+
+  /// Parse a manifest of file paths, one per line, skipping empty lines and
+  /// lines starting with `#`.
+  fn parse_manifest(manifest: &str, base: &Path) -> Vec<PathBuf> {
+    manifest
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(|line| base.join(line))
+      .collect()
+  }
+
   fn create_inscription_transactions(
     satpoint: Option<SatPoint>,
     inscription: Inscription,
     inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
     network: bitcoin::Network,
     utxos: BTreeMap<OutPoint, Amount>,
     change: Vec<Address>,
     destination: Address,
-  ) -> Result<(Transaction, Transaction, TweakedKeyPair)> {
+    fee_rate: FeeRate,
+    postage: Amount,
+  ) -> Result<(Transaction, Transaction, UntweakedKeyPair)> {
     let satpoint = if let Some(satpoint) = satpoint {
       satpoint
     } else {
-      let inscribed_utxos = inscriptions
+      let unavailable_utxos = inscriptions
         .keys()
+        .chain(&protected_sats)
         .map(|satpoint| satpoint.outpoint)
         .collect::<BTreeSet<OutPoint>>();
 
       utxos
         .keys()
-        .find(|outpoint| !inscribed_utxos.contains(outpoint))
+        .find(|outpoint| !unavailable_utxos.contains(outpoint))
         .map(|outpoint| SatPoint {
           outpoint: *outpoint,
           offset: 0,
@@ -111,8 +728,201 @@ impl Inscribe {
       }
     }
 
-    let secp256k1 = Secp256k1::new();
-    let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+    Self::check_postage(postage, &destination)?;
+
+    let reveal = Reveal::new(&inscription, destination, fee_rate, network);
+
+    let unsigned_commit_tx = TransactionBuilder::build_transaction(
+      satpoint,
+      inscriptions,
+      protected_sats,
+      utxos,
+      reveal.commit_tx_address.clone(),
+      change,
+      fee_rate,
+      postage + reveal.fee,
+    )?;
+
+    let (reveal_tx, key_pair) = reveal.sign(&unsigned_commit_tx, network)?;
+
+    Ok((unsigned_commit_tx, reveal_tx, key_pair))
+  }
+
+  /// Build one commit transaction with an output for each of `batch`, funded
+  /// only by cardinal UTXOs, so that each inscription lands on the first sat
+  /// of its commit output, along with a reveal transaction spending each
+  /// commit output to the corresponding address in `destinations`.
+  fn create_batch_inscription_transactions(
+    batch: Vec<Inscription>,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
+    network: bitcoin::Network,
+    utxos: BTreeMap<OutPoint, Amount>,
+    change: Vec<Address>,
+    destinations: Vec<Address>,
+    fee_rate: FeeRate,
+    postage: Amount,
+  ) -> Result<(Transaction, Vec<(Transaction, UntweakedKeyPair)>)> {
+    assert_eq!(batch.len(), destinations.len());
+
+    for destination in &destinations {
+      Self::check_postage(postage, destination)?;
+    }
+
+    let reveals = batch
+      .iter()
+      .zip(destinations)
+      .map(|(inscription, destination)| Reveal::new(inscription, destination, fee_rate, network))
+      .collect::<Vec<Reveal>>();
+
+    let unsigned_commit_tx = TransactionBuilder::build_transaction_with_values(
+      inscriptions,
+      protected_sats,
+      utxos,
+      reveals
+        .iter()
+        .map(|reveal| (reveal.commit_tx_address.clone(), postage + reveal.fee))
+        .collect(),
+      change,
+      fee_rate,
+    )?;
+
+    let reveals = reveals
+      .into_iter()
+      .map(|reveal| reveal.sign(&unsigned_commit_tx, network))
+      .collect::<Result<Vec<(Transaction, UntweakedKeyPair)>>>()?;
+
+    Ok((unsigned_commit_tx, reveals))
+  }
+
+  fn check_postage(postage: Amount, destination: &Address) -> Result {
+    let dust_value = destination.script_pubkey().dust_value();
+
+    if postage < dust_value {
+      bail!(
+        "postage of {} sats is below dust limit of {} sats",
+        postage.to_sat(),
+        dust_value.to_sat()
+      );
+    }
+
+    Ok(())
+  }
+
+  /// The reveal transaction is signed with a throwaway key when it is built, so
+  /// its PSBT is already finalized.
+  fn reveal_psbt(commit_tx: &Transaction, reveal_tx: &Transaction) -> Result<String> {
+    let mut psbt = Psbt::from_unsigned_tx(Transaction {
+      input: reveal_tx
+        .input
+        .iter()
+        .map(|tx_in| TxIn {
+          witness: Witness::new(),
+          ..tx_in.clone()
+        })
+        .collect(),
+      ..reveal_tx.clone()
+    })?;
+
+    let vout = usize::try_from(reveal_tx.input[0].previous_output.vout)?;
+
+    psbt.inputs[0].witness_utxo = Some(commit_tx.output[vout].clone());
+    psbt.inputs[0].final_script_witness = Some(reveal_tx.input[0].witness.clone());
+
+    Ok(encode_psbt(&psbt))
+  }
+
+  fn taproot_spend_info(key_pair: UntweakedKeyPair, reveal_script: &Script) -> TaprootSpendInfo {
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    TaprootBuilder::new()
+      .add_leaf(0, reveal_script.clone())
+      .expect("adding leaf should work")
+      .finalize(&Secp256k1::new(), public_key)
+      .expect("finalizing taproot builder should work")
+  }
+
+  /// The reveal script of a signed reveal transaction, which is the second to
+  /// last element of its script path spend witness.
+  fn reveal_script(reveal_tx: &Transaction) -> Result<Script> {
+    reveal_tx.input[0]
+      .witness
+      .second_to_last()
+      .map(|script| Script::from(script.to_vec()))
+      .ok_or_else(|| anyhow!("reveal transaction {} is not signed", reveal_tx.txid()))
+  }
+
+  /// The key that spends the commit output of `reveal_tx` with a key path
+  /// spend, which can recover the commit output if the reveal transaction is
+  /// never confirmed.
+  pub(crate) fn recovery_key_pair(
+    key_pair: UntweakedKeyPair,
+    reveal_tx: &Transaction,
+  ) -> Result<TweakedKeyPair> {
+    let taproot_spend_info =
+      Inscribe::taproot_spend_info(key_pair, &Inscribe::reveal_script(reveal_tx)?);
+
+    Ok(key_pair.tap_tweak(&Secp256k1::new(), taproot_spend_info.merkle_root()))
+  }
+
+  fn backup_recovery_key(
+    client: &Client,
+    recovery_key_pair: TweakedKeyPair,
+    network: bitcoin::Network,
+  ) -> Result {
+    let recovery_private_key = PrivateKey::new(recovery_key_pair.to_inner().secret_key(), network);
+
+    let info = client.get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
+
+    let params = json!([
+      {
+        "desc": format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum),
+        "active": false,
+        "timestamp": "now",
+        "internal": false,
+        "label": format!("commit tx recovery key")
+      }
+    ]);
+
+    #[derive(Deserialize)]
+    struct ImportDescriptorsResult {
+      success: bool,
+    }
+
+    let response: Vec<ImportDescriptorsResult> = client
+      .call("importdescriptors", &[params])
+      .context("could not import commit tx recovery key")?;
+
+    for result in response {
+      if !result.success {
+        return Err(anyhow!("commit tx recovery key import failed"));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// A reveal transaction, prepared before the commit transaction it spends,
+/// since the commit output must pay the reveal fee.
+struct Reveal {
+  commit_tx_address: Address,
+  control_block: ControlBlock,
+  fee: Amount,
+  key_pair: UntweakedKeyPair,
+  reveal_script: Script,
+  taproot_spend_info: TaprootSpendInfo,
+  transaction: Transaction,
+}
+
+impl Reveal {
+  fn new(
+    inscription: &Inscription,
+    destination: Address,
+    fee_rate: FeeRate,
+    network: bitcoin::Network,
+  ) -> Self {
+    let key_pair = UntweakedKeyPair::new(&Secp256k1::new(), &mut rand::thread_rng());
     let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
 
     let reveal_script = inscription.append_reveal_script(
@@ -121,11 +931,25 @@ impl Inscribe {
         .push_opcode(opcodes::all::OP_CHECKSIG),
     );
 
-    let taproot_spend_info = TaprootBuilder::new()
-      .add_leaf(0, reveal_script.clone())
-      .expect("adding leaf should work")
-      .finalize(&secp256k1, public_key)
-      .expect("finalizing taproot builder should work");
+    Self::with_key_pair(
+      key_pair,
+      reveal_script,
+      destination.script_pubkey(),
+      fee_rate,
+      network,
+    )
+  }
+
+  /// Prepare a reveal transaction for an existing key pair and reveal script,
+  /// for example to re-sign a journaled reveal transaction at a new fee rate.
+  fn with_key_pair(
+    key_pair: UntweakedKeyPair,
+    reveal_script: Script,
+    destination: Script,
+    fee_rate: FeeRate,
+    network: bitcoin::Network,
+  ) -> Self {
+    let taproot_spend_info = Inscribe::taproot_spend_info(key_pair, &reveal_script);
 
     let control_block = taproot_spend_info
       .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
@@ -133,41 +957,23 @@ impl Inscribe {
 
     let commit_tx_address = Address::p2tr_tweaked(taproot_spend_info.output_key(), network);
 
-    let unsigned_commit_tx = TransactionBuilder::build_transaction(
-      satpoint,
-      inscriptions,
-      utxos,
-      commit_tx_address.clone(),
-      change,
-    )?;
-
-    let (vout, output) = unsigned_commit_tx
-      .output
-      .iter()
-      .enumerate()
-      .find(|(_vout, output)| output.script_pubkey == commit_tx_address.script_pubkey())
-      .expect("should find sat commit/inscription output");
-
-    let mut reveal_tx = Transaction {
+    let transaction = Transaction {
       input: vec![TxIn {
-        previous_output: OutPoint {
-          txid: unsigned_commit_tx.txid(),
-          vout: vout.try_into().unwrap(),
-        },
+        previous_output: OutPoint::null(),
         script_sig: script::Builder::new().into_script(),
         witness: Witness::new(),
         sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
       }],
       output: vec![TxOut {
-        script_pubkey: destination.script_pubkey(),
-        value: output.value,
+        script_pubkey: destination,
+        value: 0,
       }],
       lock_time: PackedLockTime::ZERO,
       version: 1,
     };
 
     let fee = {
-      let mut reveal_tx = reveal_tx.clone();
+      let mut reveal_tx = transaction.clone();
 
       reveal_tx.input[0].witness.push(
         Signature::from_slice(&[0; SCHNORR_SIGNATURE_SIZE])
@@ -177,10 +983,52 @@ impl Inscribe {
       reveal_tx.input[0].witness.push(&reveal_script);
       reveal_tx.input[0].witness.push(&control_block.serialize());
 
-      TransactionBuilder::TARGET_FEE_RATE * reveal_tx.vsize().try_into().unwrap()
+      fee_rate.fee(reveal_tx.vsize())
+    };
+
+    Self {
+      commit_tx_address,
+      control_block,
+      fee,
+      key_pair,
+      reveal_script,
+      taproot_spend_info,
+      transaction,
+    }
+  }
+
+  /// Spend the output of `commit_tx` paying to the commit address, returning
+  /// the signed reveal transaction and the key pair it was signed with.
+  fn sign(
+    self,
+    commit_tx: &Transaction,
+    network: bitcoin::Network,
+  ) -> Result<(Transaction, UntweakedKeyPair)> {
+    let Self {
+      commit_tx_address,
+      control_block,
+      fee,
+      key_pair,
+      reveal_script,
+      taproot_spend_info,
+      transaction: mut reveal_tx,
+    } = self;
+
+    let secp256k1 = Secp256k1::new();
+
+    let (vout, output) = commit_tx
+      .output
+      .iter()
+      .enumerate()
+      .find(|(_vout, output)| output.script_pubkey == commit_tx_address.script_pubkey())
+      .expect("should find sat commit/inscription output");
+
+    reveal_tx.input[0].previous_output = OutPoint {
+      txid: commit_tx.txid(),
+      vout: vout.try_into().unwrap(),
     };
 
-    reveal_tx.output[0].value = reveal_tx.output[0]
+    reveal_tx.output[0].value = output
       .value
       .checked_sub(fee.to_sat())
       .context("commit transaction output value insufficient to pay transaction fee")?;
@@ -224,44 +1072,7 @@ impl Inscribe {
       commit_tx_address
     );
 
-    Ok((unsigned_commit_tx, reveal_tx, recovery_key_pair))
-  }
-
-  fn backup_recovery_key(
-    client: &Client,
-    recovery_key_pair: TweakedKeyPair,
-    network: bitcoin::Network,
-  ) -> Result {
-    let recovery_private_key = PrivateKey::new(recovery_key_pair.to_inner().secret_key(), network);
-
-    let info = client.get_descriptor_info(&format!("rawtr({})", recovery_private_key.to_wif()))?;
-
-    let params = json!([
-      {
-        "desc": format!("rawtr({})#{}", recovery_private_key.to_wif(), info.checksum),
-        "active": false,
-        "timestamp": "now",
-        "internal": false,
-        "label": format!("commit tx recovery key")
-      }
-    ]);
-
-    #[derive(Deserialize)]
-    struct ImportDescriptorsResult {
-      success: bool,
-    }
-
-    let response: Vec<ImportDescriptorsResult> = client
-      .call("importdescriptors", &[params])
-      .context("could not import commit tx recovery key")?;
-
-    for result in response {
-      if !result.success {
-        return Err(anyhow!("commit tx recovery key import failed"));
-      }
-    }
-
-    Ok(())
+    Ok((reveal_tx, key_pair))
   }
 }
 
@@ -280,14 +1091,17 @@ mod tests {
       Some(satpoint(1, 0)),
       inscription,
       BTreeMap::new(),
+      BTreeSet::new(),
       bitcoin::Network::Signet,
       utxos.into_iter().collect(),
       vec![commit_address, change(1)],
       reveal_address,
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .unwrap();
 
-    let fee = TransactionBuilder::TARGET_FEE_RATE * reveal_tx.vsize().try_into().unwrap();
+    let fee = Amount::from_sat(reveal_tx.vsize().try_into().unwrap());
 
     assert_eq!(
       reveal_tx.output[0].value,
@@ -307,10 +1121,13 @@ mod tests {
       satpoint,
       inscription,
       BTreeMap::new(),
+      BTreeSet::new(),
       bitcoin::Network::Signet,
       utxos.into_iter().collect(),
       vec![commit_address, change(1)],
       reveal_address,
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .unwrap_err()
     .to_string()
@@ -329,10 +1146,13 @@ mod tests {
       satpoint,
       inscription,
       BTreeMap::new(),
+      BTreeSet::new(),
       bitcoin::Network::Signet,
       utxos.into_iter().collect(),
       vec![commit_address, change(1)],
       reveal_address,
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .unwrap_err()
     .to_string();
@@ -355,10 +1175,13 @@ mod tests {
       Some(satpoint(1, 0)),
       inscription,
       BTreeMap::new(),
+      BTreeSet::new(),
       bitcoin::Network::Signet,
       utxos.into_iter().collect(),
       vec![commit_address, change(1)],
       reveal_address,
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .unwrap();
 
@@ -387,10 +1210,13 @@ mod tests {
       satpoint,
       inscription,
       inscriptions,
+      BTreeSet::new(),
       bitcoin::Network::Signet,
       utxos.into_iter().collect(),
       vec![commit_address, change(1)],
       reveal_address,
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .unwrap_err()
     .to_string();
@@ -426,11 +1252,232 @@ mod tests {
       satpoint,
       inscription,
       inscriptions,
+      BTreeSet::new(),
       bitcoin::Network::Signet,
       utxos.into_iter().collect(),
       vec![commit_address, change(1)],
       reveal_address,
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .is_ok())
   }
+
+  #[test]
+  fn reveal_transaction_pays_fee_at_fee_rate() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(20_000))];
+    let inscription = inscription("text/plain", "ord");
+    let commit_address = change(0);
+    let reveal_address = recipient();
+    let fee_rate = FeeRate::try_from(3.3).unwrap();
+
+    let (commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
+      Some(satpoint(1, 0)),
+      inscription,
+      BTreeMap::new(),
+      BTreeSet::new(),
+      bitcoin::Network::Signet,
+      utxos.into_iter().collect(),
+      vec![commit_address, change(1)],
+      reveal_address,
+      fee_rate,
+      TransactionBuilder::TARGET_POSTAGE,
+    )
+    .unwrap();
+
+    let fee = fee_rate.fee(reveal_tx.vsize());
+
+    assert_eq!(
+      reveal_tx.output[0].value,
+      20_000 - fee.to_sat() - (20_000 - commit_tx.output[0].value),
+    );
+  }
+
+  #[test]
+  fn reveal_output_carries_postage() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(20_000))];
+
+    let (_commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
+      Some(satpoint(1, 0)),
+      inscription("text/plain", "ord"),
+      BTreeMap::new(),
+      BTreeSet::new(),
+      bitcoin::Network::Signet,
+      utxos.into_iter().collect(),
+      vec![change(0), change(1)],
+      recipient(),
+      FeeRate::try_from(1.0).unwrap(),
+      Amount::from_sat(546),
+    )
+    .unwrap();
+
+    assert_eq!(reveal_tx.output[0].value, 546);
+  }
+
+  #[test]
+  fn postage_below_dust_limit() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(20_000))];
+
+    let error = Inscribe::create_inscription_transactions(
+      Some(satpoint(1, 0)),
+      inscription("text/plain", "ord"),
+      BTreeMap::new(),
+      BTreeSet::new(),
+      bitcoin::Network::Signet,
+      utxos.into_iter().collect(),
+      vec![change(0), change(1)],
+      recipient(),
+      FeeRate::try_from(1.0).unwrap(),
+      Amount::from_sat(100),
+    )
+    .unwrap_err()
+    .to_string();
+
+    assert_eq!(error, "postage of 100 sats is below dust limit of 294 sats");
+  }
+
+  #[test]
+  fn reveal_psbt_is_finalized() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(20_000))];
+
+    let (commit_tx, reveal_tx, _private_key) = Inscribe::create_inscription_transactions(
+      Some(satpoint(1, 0)),
+      inscription("text/plain", "ord"),
+      BTreeMap::new(),
+      BTreeSet::new(),
+      bitcoin::Network::Signet,
+      utxos.into_iter().collect(),
+      vec![change(0), change(1)],
+      recipient(),
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
+    )
+    .unwrap();
+
+    let psbt: Psbt = bitcoin::consensus::deserialize(
+      &base64::decode(Inscribe::reveal_psbt(&commit_tx, &reveal_tx).unwrap()).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      psbt.inputs[0].witness_utxo,
+      Some(commit_tx.output[0].clone())
+    );
+    assert_eq!(psbt.extract_tx(), reveal_tx);
+  }
+
+  #[test]
+  fn batch_commit_has_one_output_per_inscription() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(20_000)),
+      (outpoint(2), Amount::from_sat(50_000)),
+    ];
+
+    let (commit_tx, reveals) = Inscribe::create_batch_inscription_transactions(
+      vec![
+        inscription("text/plain", "foo"),
+        inscription("text/plain", "bar"),
+      ],
+      BTreeMap::from([(satpoint(1, 0), txid(1))]),
+      BTreeSet::new(),
+      bitcoin::Network::Signet,
+      utxos.into_iter().collect(),
+      vec![change(0)],
+      vec![recipient(), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
+    )
+    .unwrap();
+
+    assert_eq!(commit_tx.input.len(), 1);
+    assert_eq!(commit_tx.input[0].previous_output, outpoint(2));
+    assert_eq!(commit_tx.output.len(), 3);
+    assert_eq!(reveals.len(), 2);
+
+    for (vout, ((reveal_tx, _key_pair), destination)) in
+      reveals.iter().zip([recipient(), change(1)]).enumerate()
+    {
+      assert_eq!(
+        reveal_tx.input[0].previous_output,
+        OutPoint {
+          txid: commit_tx.txid(),
+          vout: vout.try_into().unwrap(),
+        }
+      );
+      assert_eq!(
+        reveal_tx.output,
+        [tx_out(
+          TransactionBuilder::TARGET_POSTAGE.to_sat(),
+          destination
+        )]
+      );
+    }
+  }
+
+  #[test]
+  fn parse_manifest() {
+    assert_eq!(
+      Inscribe::parse_manifest("# images\n\nfoo.png\n  bar/baz.txt  \n", Path::new("dir")),
+      [Path::new("dir/foo.png"), Path::new("dir/bar/baz.txt")]
+    );
+  }
+
+  #[test]
+  fn batch_files_in_directory_are_sorted() {
+    let tempdir = TempDir::new().unwrap();
+    fs::write(tempdir.path().join("b.txt"), "b").unwrap();
+    fs::write(tempdir.path().join("a.txt"), "a").unwrap();
+    fs::create_dir(tempdir.path().join("c")).unwrap();
+
+    assert_eq!(
+      Inscribe::batch_files(tempdir.path()).unwrap(),
+      [tempdir.path().join("a.txt"), tempdir.path().join("b.txt")]
+    );
+  }
+
+  #[test]
+  fn find_sat_in_sat_ranges() {
+    let sat_ranges = [
+      (outpoint(1), vec![(10, 20), (50, 60)]),
+      (outpoint(2), vec![(100, 200)]),
+    ];
+
+    assert_eq!(
+      Inscribe::find_sat(Sat(55), &sat_ranges),
+      Some(satpoint(1, 15))
+    );
+    assert_eq!(
+      Inscribe::find_sat(Sat(100), &sat_ranges),
+      Some(satpoint(2, 0))
+    );
+    assert_eq!(Inscribe::find_sat(Sat(20), &sat_ranges), None);
+  }
+
+  #[test]
+  fn find_rarity_skips_inscribed_utxos() {
+    let sat_ranges = [
+      (outpoint(1), vec![(50 * COIN_VALUE, 51 * COIN_VALUE)]),
+      (
+        outpoint(2),
+        vec![(1, 100), (100 * COIN_VALUE, 101 * COIN_VALUE)],
+      ),
+    ];
+
+    assert_eq!(
+      Inscribe::find_rarity(Rarity::Uncommon, &sat_ranges, &BTreeMap::new()),
+      Some(satpoint(1, 0))
+    );
+    assert_eq!(
+      Inscribe::find_rarity(
+        Rarity::Uncommon,
+        &sat_ranges,
+        &BTreeMap::from([(satpoint(1, 100), txid(1))])
+      ),
+      Some(satpoint(2, 99))
+    );
+    assert_eq!(
+      Inscribe::find_rarity(Rarity::Rare, &sat_ranges, &BTreeMap::new()),
+      None
+    );
+  }
 }
@@ -8,12 +8,14 @@ impl Inscriptions {
     let index = Index::open(&options)?;
     index.update()?;
 
-    let inscriptions = index.get_inscriptions(None)?;
+    let inscriptions = index.get_inscriptions_by_satpoint()?;
     let utxos = list_utxos(&options)?;
 
-    for (satpoint, inscription_id) in inscriptions {
+    for (satpoint, inscription_ids) in inscriptions {
       if utxos.contains_key(&satpoint.outpoint) {
-        println!("{}\t{}", inscription_id, satpoint);
+        for inscription_id in inscription_ids {
+          println!("{}\t{}", inscription_id, satpoint);
+        }
       }
     }
 
@@ -0,0 +1,226 @@
+use {
+  super::*,
+  bitcoin::{consensus, schnorr::UntweakedKeyPair, secp256k1::KeyPair},
+};
+
+/// Commit and reveal transactions of inscriptions, written to the data dir
+/// before they are broadcast, so that if broadcasting the reveal transaction
+/// fails it can be retried with `ord wallet inscribe --resume`, or the commit
+/// output swept back into the wallet with `ord wallet recover`, and so that
+/// reveal transactions can be re-signed by `ord wallet bump`. Entries are
+/// removed once all of their reveal transactions have confirmed, or their
+/// commit outputs have been recovered.
+pub(crate) struct Journal {
+  dir: PathBuf,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Entry {
+  pub(crate) commit: Transaction,
+  pub(crate) reveals: Vec<(Transaction, UntweakedKeyPair)>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SerializedEntry {
+  commit: String,
+  reveals: Vec<SerializedReveal>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SerializedReveal {
+  transaction: String,
+  secret_key: String,
+}
+
+impl Journal {
+  pub(crate) fn open(options: &Options) -> Result<Self> {
+    Self::new(options.data_dir()?.join("journal"))
+  }
+
+  /// Journal entries contain secret keys, so on unix the journal dir and the
+  /// entries written to it are only accessible by their owner.
+  fn new(dir: PathBuf) -> Result<Self> {
+    let mut builder = fs::DirBuilder::new();
+
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+
+    builder
+      .create(&dir)
+      .with_context(|| format!("failed to create journal dir `{}`", dir.display()))?;
+
+    #[cfg(unix)]
+    fs::set_permissions(&dir, std::os::unix::fs::PermissionsExt::from_mode(0o700)).with_context(
+      || {
+        format!(
+          "failed to set permissions of journal dir `{}`",
+          dir.display()
+        )
+      },
+    )?;
+
+    Ok(Self { dir })
+  }
+
+  pub(crate) fn write(&self, entry: &Entry) -> Result {
+    let serialized = SerializedEntry {
+      commit: consensus::encode::serialize_hex(&entry.commit),
+      reveals: entry
+        .reveals
+        .iter()
+        .map(|(transaction, key_pair)| SerializedReveal {
+          transaction: consensus::encode::serialize_hex(transaction),
+          secret_key: key_pair.display_secret().to_string(),
+        })
+        .collect(),
+    };
+
+    let path = self.path(entry.commit.txid());
+
+    let json = serde_json::to_string_pretty(&serialized)?;
+
+    let mut options = fs::OpenOptions::new();
+
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options
+      .open(&path)
+      .and_then(|mut file| io::Write::write_all(&mut file, json.as_bytes()))
+      .with_context(|| format!("I/O error writing `{}`", path.display()))
+  }
+
+  pub(crate) fn get(&self, commit: Txid) -> Result<Option<Entry>> {
+    let path = self.path(commit);
+
+    if !path.exists() {
+      return Ok(None);
+    }
+
+    Self::read(&path).map(Some)
+  }
+
+  pub(crate) fn entries(&self) -> Result<Vec<Entry>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(&self.dir)
+      .with_context(|| format!("I/O error reading `{}`", self.dir.display()))?
+    {
+      let path = entry?.path();
+      if path.extension() == Some("json".as_ref()) {
+        paths.push(path);
+      }
+    }
+
+    paths.sort();
+
+    paths.iter().map(|path| Self::read(path)).collect()
+  }
+
+  pub(crate) fn remove(&self, commit: Txid) -> Result {
+    let path = self.path(commit);
+
+    fs::remove_file(&path).with_context(|| format!("I/O error removing `{}`", path.display()))
+  }
+
+  fn path(&self, commit: Txid) -> PathBuf {
+    self.dir.join(format!("{commit}.json"))
+  }
+
+  fn read(path: &Path) -> Result<Entry> {
+    let serialized: SerializedEntry = serde_json::from_str(
+      &fs::read_to_string(path)
+        .with_context(|| format!("I/O error reading `{}`", path.display()))?,
+    )
+    .with_context(|| format!("failed to parse journal entry `{}`", path.display()))?;
+
+    let secp256k1 = Secp256k1::new();
+
+    Ok(Entry {
+      commit: consensus::deserialize(&hex::decode(serialized.commit)?)?,
+      reveals: serialized
+        .reveals
+        .into_iter()
+        .map(|reveal| {
+          Ok((
+            consensus::deserialize(&hex::decode(reveal.transaction)?)?,
+            KeyPair::from_seckey_str(&secp256k1, &reveal.secret_key)?,
+          ))
+        })
+        .collect::<Result<Vec<(Transaction, UntweakedKeyPair)>>>()?,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::{secp256k1::rand, PackedLockTime},
+  };
+
+  fn entry(n: u64) -> Entry {
+    Entry {
+      commit: Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(n))],
+        output: vec![tx_out(10_000, change(0))],
+      },
+      reveals: vec![(
+        Transaction {
+          version: 1,
+          lock_time: PackedLockTime::ZERO,
+          input: vec![tx_in(outpoint(n))],
+          output: vec![tx_out(9_000, recipient())],
+        },
+        KeyPair::new(&Secp256k1::new(), &mut rand::thread_rng()),
+      )],
+    }
+  }
+
+  #[test]
+  fn entries_round_trip() {
+    let tempdir = TempDir::new().unwrap();
+    let journal = Journal::new(tempdir.path().join("journal")).unwrap();
+
+    let first = entry(1);
+    let second = entry(2);
+
+    journal.write(&first).unwrap();
+    journal.write(&second).unwrap();
+
+    assert_eq!(
+      journal.get(first.commit.txid()).unwrap().as_ref(),
+      Some(&first)
+    );
+    assert_eq!(journal.entries().unwrap().len(), 2);
+
+    journal.remove(first.commit.txid()).unwrap();
+
+    assert_eq!(journal.get(first.commit.txid()).unwrap(), None);
+    assert_eq!(journal.entries().unwrap(), [second]);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn entries_are_only_accessible_by_owner() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tempdir = TempDir::new().unwrap();
+    let dir = tempdir.path().join("journal");
+    let journal = Journal::new(dir.clone()).unwrap();
+
+    let entry = entry(1);
+
+    journal.write(&entry).unwrap();
+
+    let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+    assert_eq!(mode(&dir), 0o700);
+    assert_eq!(mode(&journal.path(entry.commit.txid())), 0o600);
+  }
+}
@@ -0,0 +1,21 @@
+use super::*;
+
+mod accept;
+mod create;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Offer {
+  #[clap(about = "Accept an offer to sell an inscription")]
+  Accept(accept::Accept),
+  #[clap(about = "Create an offer to sell an inscription")]
+  Create(create::Create),
+}
+
+impl Offer {
+  pub(crate) fn run(self, options: Options) -> Result {
+    match self {
+      Self::Accept(accept) => accept.run(options),
+      Self::Create(create) => create.run(options),
+    }
+  }
+}
@@ -0,0 +1,496 @@
+use {
+  super::*,
+  bitcoin::{secp256k1::constants::SCHNORR_SIGNATURE_SIZE, SchnorrSighashType},
+  std::collections::BTreeSet,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Accept {
+  #[clap(help = "Accept offer in base64-encoded <PSBT>")]
+  psbt: String,
+  #[clap(
+    long,
+    help = "Buy <INSCRIPTION>, which must be the one sold by the offer"
+  )]
+  inscription: InscriptionId,
+  #[clap(
+    long,
+    value_parser = parse_amount,
+    help = "Pay <PRICE>, which must match the price of the offer, e.g. `0.1btc`"
+  )]
+  price: Amount,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+  #[clap(flatten)]
+  sat_protection: SatProtection,
+}
+
+impl Accept {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet offer accept")?;
+
+    let offer = Self::decode_offer(&self.psbt)?;
+
+    let seller_outpoint = offer.input[0].previous_output;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let satpoint = match index.get_inscription_by_inscription_id(self.inscription)? {
+      Some((_inscription, satpoint)) => satpoint,
+      None => bail!("No inscription found for {}", self.inscription),
+    };
+
+    if satpoint.outpoint != seller_outpoint {
+      bail!("offer does not sell inscription {}", self.inscription);
+    }
+
+    let seller_value = client
+      .get_tx_out(&seller_outpoint.txid, seller_outpoint.vout, Some(true))?
+      .ok_or_else(|| anyhow!("output {seller_outpoint} sold by offer has already been spent"))?
+      .value;
+
+    let price = offer.output[0]
+      .value
+      .checked_sub(seller_value.to_sat())
+      .map(Amount::from_sat)
+      .ok_or_else(|| anyhow!("offer pays seller less than value of output {seller_outpoint}"))?;
+
+    if price != self.price {
+      bail!(
+        "offer price of {} does not match expected price of {}",
+        price,
+        self.price
+      );
+    }
+
+    let inscribed_outpoints = index
+      .get_inscriptions(None)?
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let mut utxos = list_utxos(&options)?;
+
+    let protected_outpoints = self
+      .sat_protection
+      .protected_sats(&index, &utxos)?
+      .into_iter()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let cardinal_utxos = utxos
+      .clone()
+      .into_iter()
+      .filter(|(outpoint, _amount)| {
+        *outpoint != seller_outpoint
+          && !inscribed_outpoints.contains(outpoint)
+          && !protected_outpoints.contains(outpoint)
+      })
+      .collect::<Vec<(OutPoint, Amount)>>();
+
+    let [recipient, alignment, change]: [Address; 3] = get_change_addresses(&options, 3)?
+      .try_into()
+      .map_err(|_| anyhow!("wallet returned wrong number of change addresses"))?;
+
+    let (padding, funding) = Self::select_padding(
+      cardinal_utxos,
+      satpoint.offset,
+      alignment.script_pubkey().dust_value(),
+    )?;
+
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    let unsigned_transaction = Self::purchase_transaction(
+      &offer,
+      seller_value,
+      satpoint.offset,
+      padding,
+      funding,
+      recipient,
+      [alignment, change],
+      fee_rate,
+    )?;
+
+    utxos.insert(seller_outpoint, seller_value);
+
+    FeeOptions::report(fee_rate, transaction_fee(&unsigned_transaction, &utxos));
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let txid = client.send_raw_transaction(&signed_tx)?;
+
+    println!("{txid}");
+
+    Ok(())
+  }
+
+  /// Decode an offer PSBT, and return its transaction with the seller's
+  /// signature in the witness of its input.
+  fn decode_offer(psbt: &str) -> Result<Transaction> {
+    let psbt = decode_psbt(psbt).context("failed to decode offer PSBT")?;
+
+    if psbt.unsigned_tx.input.len() != 1 || psbt.unsigned_tx.output.len() != 1 {
+      bail!("offer must have exactly one input and one output");
+    }
+
+    let witness = psbt.inputs[0]
+      .final_script_witness
+      .clone()
+      .ok_or_else(|| anyhow!("offer is not signed"))?;
+
+    let witness_elements = witness.to_vec();
+
+    match witness_elements.as_slice() {
+      [signature]
+        if signature.len() == SCHNORR_SIGNATURE_SIZE + 1
+          && SchnorrSighashType::from_consensus_u8(signature[SCHNORR_SIGNATURE_SIZE]).ok()
+            == Some(SchnorrSighashType::SinglePlusAnyoneCanPay) => {}
+      _ => bail!("offer must be signed with SIGHASH_SINGLE|ANYONECANPAY"),
+    }
+
+    let mut offer = psbt.unsigned_tx;
+    offer.input[0].witness = witness;
+
+    Ok(offer)
+  }
+
+  /// Select two padding UTXOs from `cardinal_utxos`, preferring small ones,
+  /// whose values together with the `inscription_offset` sats before the
+  /// inscription are at least `dust`, so that the alignment output they fund
+  /// isn't dust. The remaining UTXOs are returned, largest first, for funding.
+  fn select_padding(
+    mut cardinal_utxos: Vec<(OutPoint, Amount)>,
+    inscription_offset: u64,
+    dust: Amount,
+  ) -> Result<([(OutPoint, Amount); 2], Vec<(OutPoint, Amount)>)> {
+    cardinal_utxos.sort_by_key(|(_outpoint, amount)| *amount);
+
+    for j in 1..cardinal_utxos.len() {
+      for i in 0..j {
+        if cardinal_utxos[i].1 + cardinal_utxos[j].1 + Amount::from_sat(inscription_offset) >= dust
+        {
+          let second = cardinal_utxos.remove(j);
+          let first = cardinal_utxos.remove(i);
+          cardinal_utxos.reverse();
+          return Ok(([first, second], cardinal_utxos));
+        }
+      }
+    }
+
+    bail!("wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.")
+  }
+
+  /// Build a transaction accepting `offer`. Two padding inputs precede the
+  /// seller's input, so that the seller's input and output are both at index
+  /// two, as required by its `SIGHASH_SINGLE` signature. The first output
+  /// absorbs the padding and any sats before the inscription, so that the
+  /// inscription lands at the start of the second output, which is sent to
+  /// `recipient`, and must not be dust. Funding inputs are added in order until
+  /// they pay for the price, postage, and fee.
+  fn purchase_transaction(
+    offer: &Transaction,
+    seller_value: Amount,
+    inscription_offset: u64,
+    padding: [(OutPoint, Amount); 2],
+    funding: Vec<(OutPoint, Amount)>,
+    recipient: Address,
+    [alignment, change]: [Address; 2],
+    fee_rate: FeeRate,
+  ) -> Result<Transaction> {
+    let postage = TransactionBuilder::TARGET_POSTAGE;
+
+    let price = Amount::from_sat(offer.output[0].value) - seller_value;
+
+    let buyer_input = |outpoint: OutPoint| TxIn {
+      previous_output: outpoint,
+      script_sig: Script::new(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness: Witness::new(),
+    };
+
+    let alignment_value =
+      Amount::from_sat(padding[0].1.to_sat() + padding[1].1.to_sat() + inscription_offset);
+
+    if alignment_value < alignment.script_pubkey().dust_value() {
+      bail!(
+        "padding inputs and sats before inscription total {} sats, below dust limit of {} sats",
+        alignment_value.to_sat(),
+        alignment.script_pubkey().dust_value().to_sat()
+      );
+    }
+
+    let mut transaction = Transaction {
+      version: offer.version,
+      lock_time: offer.lock_time,
+      input: vec![
+        buyer_input(padding[0].0),
+        buyer_input(padding[1].0),
+        offer.input[0].clone(),
+      ],
+      output: vec![
+        TxOut {
+          value: alignment_value.to_sat(),
+          script_pubkey: alignment.script_pubkey(),
+        },
+        TxOut {
+          value: postage.to_sat(),
+          script_pubkey: recipient.script_pubkey(),
+        },
+        offer.output[0].clone(),
+      ],
+    };
+
+    // Sats from funding inputs pay for the sats before the inscription, which
+    // go to the alignment output, as well as postage, price, and fee
+    let required = Amount::from_sat(inscription_offset) + postage + price;
+
+    let change_output = TxOut {
+      value: 0,
+      script_pubkey: change.script_pubkey(),
+    };
+
+    let mut funding_value = Amount::ZERO;
+    for (outpoint, amount) in funding {
+      transaction.input.push(buyer_input(outpoint));
+      funding_value += amount;
+
+      let mut with_change = transaction.clone();
+      with_change.output.push(change_output.clone());
+      let fee = fee_rate.fee(Self::estimate_vsize(&with_change));
+
+      if funding_value >= required + fee + change.script_pubkey().dust_value() {
+        with_change.output[3].value = (funding_value - required - fee).to_sat();
+        return Ok(with_change);
+      }
+
+      if funding_value >= required + fee_rate.fee(Self::estimate_vsize(&transaction)) {
+        return Ok(transaction);
+      }
+    }
+
+    bail!("wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.")
+  }
+
+  /// Estimate the vsize of `transaction` once the buyer's inputs are signed,
+  /// assuming that they are taproot key path spends, like those of outputs
+  /// controlled by ord wallets.
+  fn estimate_vsize(transaction: &Transaction) -> usize {
+    Transaction {
+      input: transaction
+        .input
+        .iter()
+        .map(|tx_in| TxIn {
+          witness: if tx_in.witness.is_empty() {
+            Witness::from_vec(vec![vec![0; SCHNORR_SIGNATURE_SIZE]])
+          } else {
+            tx_in.witness.clone()
+          },
+          ..tx_in.clone()
+        })
+        .collect(),
+      ..transaction.clone()
+    }
+    .vsize()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::PackedLockTime};
+
+  fn offer() -> Transaction {
+    Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![TxIn {
+        witness: Witness::from_vec(vec![
+          [[0; SCHNORR_SIGNATURE_SIZE].as_slice(), &[0x83]].concat()
+        ]),
+        ..tx_in(outpoint(1))
+      }],
+      output: vec![tx_out(60_000, change(3))],
+    }
+  }
+
+  #[test]
+  fn purchase_transaction_keeps_seller_input_and_output_aligned() {
+    let offer = offer();
+
+    let transaction = Accept::purchase_transaction(
+      &offer,
+      Amount::from_sat(10_000),
+      1_000,
+      [
+        (outpoint(2), Amount::from_sat(600)),
+        (outpoint(3), Amount::from_sat(700)),
+      ],
+      vec![(outpoint(4), Amount::from_sat(100_000))],
+      recipient(),
+      [change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(transaction.input[2], offer.input[0]);
+    assert_eq!(transaction.output[2], offer.output[0]);
+
+    assert_eq!(
+      transaction
+        .input
+        .iter()
+        .map(|tx_in| tx_in.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(2), outpoint(3), outpoint(1), outpoint(4)]
+    );
+
+    let fee = FeeRate::try_from(1.0)
+      .unwrap()
+      .fee(Accept::estimate_vsize(&transaction))
+      .to_sat();
+
+    assert_eq!(
+      transaction.output,
+      [
+        tx_out(2_300, change(0)),
+        tx_out(10_000, recipient()),
+        tx_out(60_000, change(3)),
+        tx_out(100_000 - 1_000 - 10_000 - 50_000 - fee, change(1)),
+      ]
+    );
+
+    assert_eq!(
+      output_satpoint(&transaction, 600 + 700 + 1_000),
+      Some(SatPoint {
+        outpoint: OutPoint::new(transaction.txid(), 1),
+        offset: 0,
+      })
+    );
+  }
+
+  #[test]
+  fn purchase_transaction_requires_enough_funding() {
+    assert_eq!(
+      Accept::purchase_transaction(
+        &offer(),
+        Amount::from_sat(10_000),
+        0,
+        [
+          (outpoint(2), Amount::from_sat(600)),
+          (outpoint(3), Amount::from_sat(700)),
+        ],
+        vec![(outpoint(4), Amount::from_sat(55_000))],
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "wallet does not contain enough cardinal UTXOs, please add additional funds to wallet."
+    );
+  }
+
+  #[test]
+  fn purchase_transaction_rejects_dust_alignment_output() {
+    assert_eq!(
+      Accept::purchase_transaction(
+        &offer(),
+        Amount::from_sat(10_000),
+        50,
+        [
+          (outpoint(2), Amount::from_sat(100)),
+          (outpoint(3), Amount::from_sat(100)),
+        ],
+        vec![(outpoint(4), Amount::from_sat(100_000))],
+        recipient(),
+        [change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "padding inputs and sats before inscription total 250 sats, below dust limit of 294 sats"
+    );
+  }
+
+  #[test]
+  fn select_padding_skips_pairs_below_dust() {
+    let dust = change(0).script_pubkey().dust_value();
+
+    assert_eq!(
+      Accept::select_padding(
+        vec![
+          (outpoint(1), Amount::from_sat(100_000)),
+          (outpoint(2), Amount::from_sat(100)),
+          (outpoint(3), Amount::from_sat(150)),
+          (outpoint(4), Amount::from_sat(250)),
+          (outpoint(5), Amount::from_sat(50_000)),
+        ],
+        0,
+        dust,
+      )
+      .unwrap(),
+      (
+        [
+          (outpoint(2), Amount::from_sat(100)),
+          (outpoint(4), Amount::from_sat(250)),
+        ],
+        vec![
+          (outpoint(1), Amount::from_sat(100_000)),
+          (outpoint(5), Amount::from_sat(50_000)),
+          (outpoint(3), Amount::from_sat(150)),
+        ]
+      )
+    );
+
+    assert_eq!(
+      Accept::select_padding(
+        vec![
+          (outpoint(1), Amount::from_sat(100)),
+          (outpoint(2), Amount::from_sat(100)),
+        ],
+        50,
+        dust,
+      )
+      .unwrap_err()
+      .to_string(),
+      "wallet does not contain enough cardinal UTXOs, please add additional funds to wallet."
+    );
+  }
+
+  #[test]
+  fn decode_offer_requires_single_anyonecanpay_signature() {
+    let offer = offer();
+
+    let encode = |witness: Option<Witness>| {
+      let mut psbt = Psbt::from_unsigned_tx(Transaction {
+        input: vec![tx_in(outpoint(1))],
+        ..offer.clone()
+      })
+      .unwrap();
+      psbt.inputs[0].final_script_witness = witness;
+      encode_psbt(&psbt)
+    };
+
+    assert_eq!(
+      Accept::decode_offer(&encode(Some(offer.input[0].witness.clone()))).unwrap(),
+      offer
+    );
+
+    assert_eq!(
+      Accept::decode_offer(&encode(None)).unwrap_err().to_string(),
+      "offer is not signed"
+    );
+
+    assert_eq!(
+      Accept::decode_offer(&encode(Some(Witness::from_vec(vec![vec![
+        0;
+        SCHNORR_SIGNATURE_SIZE
+      ]]))))
+      .unwrap_err()
+      .to_string(),
+      "offer must be signed with SIGHASH_SINGLE|ANYONECANPAY"
+    );
+  }
+}
@@ -0,0 +1,101 @@
+use {
+  super::*,
+  bitcoin::{EcdsaSighashType, PackedLockTime},
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Create {
+  #[clap(help = "Offer to sell <INSCRIPTION>")]
+  inscription: InscriptionId,
+  #[clap(
+    long,
+    value_parser = parse_amount,
+    help = "Sell inscription for <PRICE>, an amount with denomination, e.g. `0.1btc`"
+  )]
+  price: Amount,
+}
+
+impl Create {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet offer create")?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let satpoint = match index.get_inscription_by_inscription_id(self.inscription)? {
+      Some((_inscription, satpoint)) => satpoint,
+      None => bail!("No inscription found for {}", self.inscription),
+    };
+
+    let utxos = list_utxos(&options)?;
+
+    let value = *utxos
+      .get(&satpoint.outpoint)
+      .ok_or_else(|| anyhow!("inscription {} not in wallet", self.inscription))?;
+
+    if index
+      .get_inscriptions_by_satpoint()?
+      .iter()
+      .filter(|(inscription_satpoint, _ids)| inscription_satpoint.outpoint == satpoint.outpoint)
+      .map(|(_satpoint, ids)| ids.len())
+      .sum::<usize>()
+      > 1
+    {
+      bail!(
+        "output {} contains more than one inscription, all of which would be sold",
+        satpoint.outpoint
+      );
+    }
+
+    let unsigned_transaction = Self::offer_transaction(
+      satpoint.outpoint,
+      value,
+      self.price,
+      client.get_new_address(None, None)?,
+    );
+
+    let processed = client
+      .wallet_process_psbt(
+        &encode_psbt(&unsigned_psbt(&client, &unsigned_transaction)?),
+        Some(true),
+        Some(EcdsaSighashType::SinglePlusAnyoneCanPay.into()),
+        None,
+      )
+      .context("wallet failed to sign offer")?;
+
+    if !processed.complete {
+      bail!("wallet failed to sign offer");
+    }
+
+    println!("{}", processed.psbt);
+
+    Ok(())
+  }
+
+  /// An offer spends the output holding the inscription and pays its value
+  /// plus `price` to the seller. The seller signs with
+  /// `SIGHASH_SINGLE|ANYONECANPAY`, so that the buyer can add inputs and
+  /// outputs, as long as the seller's output stays at the same index as its
+  /// input.
+  fn offer_transaction(
+    outpoint: OutPoint,
+    value: Amount,
+    price: Amount,
+    seller: Address,
+  ) -> Transaction {
+    Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint,
+        script_sig: Script::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        value: (value + price).to_sat(),
+        script_pubkey: seller.script_pubkey(),
+      }],
+    }
+  }
+}
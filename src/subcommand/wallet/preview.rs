@@ -0,0 +1,283 @@
+use {super::*, std::collections::VecDeque};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Preview {
+  pub(crate) txid: Txid,
+  pub(crate) vsize: usize,
+  pub(crate) fee: u64,
+  pub(crate) inputs: Vec<PreviewInput>,
+  pub(crate) outputs: Vec<PreviewOutput>,
+  pub(crate) inscriptions: Vec<InscriptionFlow>,
+  pub(crate) rare_sats: Option<Vec<RareSatFlow>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PreviewInput {
+  pub(crate) outpoint: OutPoint,
+  pub(crate) value: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PreviewOutput {
+  pub(crate) outpoint: OutPoint,
+  pub(crate) address: Option<Address>,
+  pub(crate) value: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct InscriptionFlow {
+  pub(crate) inscription_id: InscriptionId,
+  pub(crate) input: Option<SatPoint>,
+  pub(crate) output: Option<SatPoint>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RareSatFlow {
+  pub(crate) sat: Sat,
+  pub(crate) rarity: String,
+  pub(crate) input: SatPoint,
+  pub(crate) output: Option<SatPoint>,
+}
+
+impl Preview {
+  /// Preview `transaction`, showing where the inscriptions and rare sats in
+  /// its inputs will land. Sats and inscriptions in `None` outputs are spent
+  /// to fees. Rare sats are only shown if `sat_ranges` are available, which
+  /// requires an index with `--index-sats`.
+  pub(crate) fn new(
+    transaction: &Transaction,
+    vsize: usize,
+    amounts: &BTreeMap<OutPoint, Amount>,
+    inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+    sat_ranges: Option<&BTreeMap<OutPoint, Vec<(u64, u64)>>>,
+    network: bitcoin::Network,
+  ) -> Self {
+    let txid = transaction.txid();
+
+    let mut inscription_flows = Vec::new();
+
+    if Inscription::from_transaction(transaction).is_some() {
+      inscription_flows.push(InscriptionFlow {
+        inscription_id: txid,
+        input: None,
+        output: output_satpoint(transaction, 0),
+      });
+    }
+
+    let mut rare_sat_flows = sat_ranges.map(|_| Vec::new());
+
+    let mut input_offset = 0;
+    for tx_in in &transaction.input {
+      let outpoint = tx_in.previous_output;
+
+      for (satpoint, inscription_id) in inscriptions {
+        if satpoint.outpoint == outpoint {
+          inscription_flows.push(InscriptionFlow {
+            inscription_id: *inscription_id,
+            input: Some(*satpoint),
+            output: output_satpoint(transaction, input_offset + satpoint.offset),
+          });
+        }
+      }
+
+      if let (Some(sat_ranges), Some(rare_sat_flows)) = (sat_ranges, &mut rare_sat_flows) {
+        let mut offset = 0;
+        for (start, end) in sat_ranges.get(&outpoint).into_iter().flatten() {
+          let sat = Sat(*start);
+          if !sat.is_common() {
+            rare_sat_flows.push(RareSatFlow {
+              sat,
+              rarity: sat.rarity().to_string(),
+              input: SatPoint { outpoint, offset },
+              output: output_satpoint(transaction, input_offset + offset),
+            });
+          }
+          offset += end - start;
+        }
+      }
+
+      input_offset += amounts[&outpoint].to_sat();
+    }
+
+    Self {
+      txid,
+      vsize,
+      fee: transaction_fee(transaction, amounts).to_sat(),
+      inputs: transaction
+        .input
+        .iter()
+        .map(|tx_in| PreviewInput {
+          outpoint: tx_in.previous_output,
+          value: amounts[&tx_in.previous_output].to_sat(),
+        })
+        .collect(),
+      outputs: (0..)
+        .zip(&transaction.output)
+        .map(|(vout, tx_out)| PreviewOutput {
+          outpoint: OutPoint::new(txid, vout),
+          address: Address::from_script(&tx_out.script_pubkey, network).ok(),
+          value: tx_out.value,
+        })
+        .collect(),
+      inscriptions: inscription_flows,
+      rare_sats: rare_sat_flows,
+    }
+  }
+
+  /// Look up the sat ranges of the inputs of `transaction`, if the index has
+  /// a sat index.
+  pub(crate) fn input_sat_ranges(
+    index: &Index,
+    transaction: &Transaction,
+  ) -> Result<Option<BTreeMap<OutPoint, Vec<(u64, u64)>>>> {
+    if !index.has_satoshi_index()? {
+      return Ok(None);
+    }
+
+    let mut sat_ranges = BTreeMap::new();
+
+    for tx_in in &transaction.input {
+      let outpoint = tx_in.previous_output;
+      match index.list(outpoint)? {
+        Some(List::Unspent(ranges)) => {
+          sat_ranges.insert(outpoint, ranges);
+        }
+        Some(List::Spent) => bail!("output {outpoint} is spent according to index"),
+        None => bail!("index has not seen {outpoint}"),
+      }
+    }
+
+    Ok(Some(sat_ranges))
+  }
+
+  /// Distribute the sat ranges of the inputs of `transaction` over its
+  /// outputs, the same way the updater does, returning the sat ranges of
+  /// each output.
+  pub(crate) fn output_sat_ranges(
+    transaction: &Transaction,
+    sat_ranges: &BTreeMap<OutPoint, Vec<(u64, u64)>>,
+  ) -> Vec<Vec<(u64, u64)>> {
+    let mut input_sat_ranges = transaction
+      .input
+      .iter()
+      .flat_map(|tx_in| {
+        sat_ranges
+          .get(&tx_in.previous_output)
+          .into_iter()
+          .flatten()
+          .copied()
+      })
+      .collect::<VecDeque<(u64, u64)>>();
+
+    let mut output_sat_ranges = Vec::new();
+
+    for tx_out in &transaction.output {
+      let mut ranges = Vec::new();
+
+      let mut remaining = tx_out.value;
+      while remaining > 0 {
+        let Some(range) = input_sat_ranges.pop_front() else {
+          break;
+        };
+
+        let count = range.1 - range.0;
+
+        let assigned = if count > remaining {
+          let middle = range.0 + remaining;
+          input_sat_ranges.push_front((middle, range.1));
+          (range.0, middle)
+        } else {
+          range
+        };
+
+        ranges.push(assigned);
+
+        remaining -= assigned.1 - assigned.0;
+      }
+
+      output_sat_ranges.push(ranges);
+    }
+
+    output_sat_ranges
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::PackedLockTime};
+
+  #[test]
+  fn rare_sats_and_inscriptions_follow_sat_flow() {
+    let tx = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+      output: vec![tx_out(6_000, recipient()), tx_out(3_000, change(0))],
+    };
+
+    let amounts = [
+      (outpoint(1), Amount::from_sat(5_000)),
+      (outpoint(2), Amount::from_sat(5_000)),
+    ]
+    .into_iter()
+    .collect();
+
+    let inscriptions = [(satpoint(2, 100), txid(1))].into_iter().collect();
+
+    let uncommon = 50 * COIN_VALUE;
+
+    let sat_ranges = [
+      (outpoint(1), vec![(1, 5_001)]),
+      (
+        outpoint(2),
+        vec![(uncommon - 3_000, uncommon), (uncommon, uncommon + 2_000)],
+      ),
+    ]
+    .into_iter()
+    .collect();
+
+    let preview = Preview::new(
+      &tx,
+      200,
+      &amounts,
+      &inscriptions,
+      Some(&sat_ranges),
+      bitcoin::Network::Signet,
+    );
+
+    assert_eq!(preview.fee, 1_000);
+
+    assert_eq!(
+      preview.inscriptions,
+      [InscriptionFlow {
+        inscription_id: txid(1),
+        input: Some(satpoint(2, 100)),
+        output: Some(SatPoint {
+          outpoint: OutPoint::new(tx.txid(), 0),
+          offset: 5_100,
+        }),
+      }]
+    );
+
+    assert_eq!(
+      preview.rare_sats,
+      Some(vec![RareSatFlow {
+        sat: Sat(uncommon),
+        rarity: "uncommon".into(),
+        input: satpoint(2, 3_000),
+        output: Some(SatPoint {
+          outpoint: OutPoint::new(tx.txid(), 1),
+          offset: 2_000,
+        }),
+      }])
+    );
+
+    assert_eq!(
+      Preview::output_sat_ranges(&tx, &sat_ranges),
+      [
+        vec![(1, 5_001), (uncommon - 3_000, uncommon - 2_000)],
+        vec![(uncommon - 2_000, uncommon), (uncommon, uncommon + 1_000)],
+      ]
+    );
+  }
+}
@@ -0,0 +1,271 @@
+use {
+  super::*,
+  bitcoin::{
+    schnorr::TweakedKeyPair,
+    secp256k1::{self, constants::SCHNORR_SIGNATURE_SIZE},
+    util::sighash::{Prevouts, SighashCache},
+    PackedLockTime, SchnorrSighashType,
+  },
+  inscribe::Inscribe,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Recover {
+  #[clap(help = "Recover unspent outputs of journaled commit transaction <COMMIT>")]
+  commit: Txid,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+}
+
+impl Recover {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet recover")?;
+
+    let journal = Journal::open(&options)?;
+
+    let entry = journal.get(self.commit)?.ok_or_else(|| {
+      anyhow!(
+        "journal contains no inscriptions with commit transaction {}",
+        self.commit
+      )
+    })?;
+
+    let mut outputs = Vec::new();
+    for (reveal_tx, key_pair) in &entry.reveals {
+      let vout = reveal_tx.input[0].previous_output.vout;
+
+      if client.get_tx_out(&self.commit, vout, Some(true))?.is_some() {
+        outputs.push((vout, Inscribe::recovery_key_pair(*key_pair, reveal_tx)?));
+      }
+    }
+
+    if outputs.is_empty() {
+      if client.get_transaction(&self.commit, None).is_err() {
+        journal.remove(self.commit)?;
+        bail!(
+          "commit transaction {} was never broadcast, removed it from journal",
+          self.commit
+        );
+      }
+
+      if Inscribe::reveals_confirmed(&client, &entry)? {
+        journal.remove(self.commit)?;
+        bail!(
+          "outputs of commit transaction {} have already been spent by confirmed reveal transactions, removed it from journal",
+          self.commit
+        );
+      }
+
+      bail!(
+        "outputs of commit transaction {} have already been spent",
+        self.commit
+      );
+    }
+
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    let recovery_tx = Self::recovery_transaction(
+      &entry.commit,
+      outputs,
+      get_change_addresses(&options, 1)?.remove(0),
+      fee_rate,
+    )?;
+
+    let txid = client.send_raw_transaction(&recovery_tx)?;
+
+    journal.remove(self.commit)?;
+
+    println!("{txid}");
+
+    Ok(())
+  }
+
+  /// Sweep the commit outputs at `outputs` to `destination` with key path
+  /// spends signed by their recovery keys.
+  fn recovery_transaction(
+    commit_tx: &Transaction,
+    outputs: Vec<(u32, TweakedKeyPair)>,
+    destination: Address,
+    fee_rate: FeeRate,
+  ) -> Result<Transaction> {
+    let commit_txid = commit_tx.txid();
+
+    let prevouts = outputs
+      .iter()
+      .map(|(vout, _key_pair)| Ok(commit_tx.output[usize::try_from(*vout)?].clone()))
+      .collect::<Result<Vec<TxOut>>>()?;
+
+    let mut recovery_tx = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: outputs
+        .iter()
+        .map(|(vout, _key_pair)| TxIn {
+          previous_output: OutPoint {
+            txid: commit_txid,
+            vout: *vout,
+          },
+          script_sig: Script::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::from_vec(vec![vec![0; SCHNORR_SIGNATURE_SIZE]]),
+        })
+        .collect(),
+      output: vec![TxOut {
+        script_pubkey: destination.script_pubkey(),
+        value: 0,
+      }],
+    };
+
+    let fee = fee_rate.fee(recovery_tx.vsize());
+
+    let input_value = prevouts.iter().map(|tx_out| tx_out.value).sum::<u64>();
+
+    recovery_tx.output[0].value = input_value
+      .checked_sub(fee.to_sat())
+      .filter(|value| Amount::from_sat(*value) >= destination.script_pubkey().dust_value())
+      .ok_or_else(|| {
+        anyhow!(
+          "outputs of commit transaction {commit_txid} are too small to pay fee of {} sats for recovery transaction",
+          fee.to_sat()
+        )
+      })?;
+
+    let secp256k1 = Secp256k1::new();
+
+    let mut sighash_cache = SighashCache::new(&mut recovery_tx);
+
+    for (i, (_vout, key_pair)) in outputs.iter().enumerate() {
+      let signature_hash = sighash_cache
+        .taproot_key_spend_signature_hash(i, &Prevouts::All(&prevouts), SchnorrSighashType::Default)
+        .expect("signature hash should compute");
+
+      let signature = secp256k1.sign_schnorr(
+        &secp256k1::Message::from_slice(signature_hash.as_inner())
+          .expect("should be cryptographically secure hash"),
+        &key_pair.to_inner(),
+      );
+
+      *sighash_cache
+        .witness_mut(i)
+        .expect("getting mutable witness reference should work") =
+        Witness::from_vec(vec![signature.as_ref().to_vec()]);
+    }
+
+    Ok(recovery_tx)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    bitcoin::{
+      schnorr::{TapTweak, TweakedPublicKey, UntweakedKeyPair},
+      secp256k1::{rand, schnorr::Signature},
+    },
+  };
+
+  fn commit_output(value: u64, key_pair: TweakedKeyPair) -> TxOut {
+    TxOut {
+      value,
+      script_pubkey: Address::p2tr_tweaked(
+        TweakedPublicKey::dangerous_assume_tweaked(key_pair.to_inner().x_only_public_key().0),
+        Network::Signet,
+      )
+      .script_pubkey(),
+    }
+  }
+
+  #[test]
+  fn recovery_transaction_spends_commit_outputs_with_key_path() {
+    let secp256k1 = Secp256k1::new();
+
+    let key_pairs = [(); 2].map(|()| {
+      UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng()).tap_tweak(&secp256k1, None)
+    });
+
+    let commit_tx = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![tx_in(outpoint(1))],
+      output: key_pairs
+        .iter()
+        .map(|key_pair| commit_output(10_000, *key_pair))
+        .collect(),
+    };
+
+    let recovery_tx = Recover::recovery_transaction(
+      &commit_tx,
+      vec![(0, key_pairs[0]), (1, key_pairs[1])],
+      recipient(),
+      FeeRate::try_from(1.0).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      recovery_tx.output,
+      [tx_out(
+        20_000 - u64::try_from(recovery_tx.vsize()).unwrap(),
+        recipient()
+      )]
+    );
+
+    let mut sighash_cache = SighashCache::new(&recovery_tx);
+
+    for (i, key_pair) in key_pairs.iter().enumerate() {
+      assert_eq!(
+        recovery_tx.input[i].previous_output,
+        OutPoint {
+          txid: commit_tx.txid(),
+          vout: i.try_into().unwrap(),
+        }
+      );
+
+      let signature_hash = sighash_cache
+        .taproot_key_spend_signature_hash(
+          i,
+          &Prevouts::All(&commit_tx.output),
+          SchnorrSighashType::Default,
+        )
+        .unwrap();
+
+      secp256k1
+        .verify_schnorr(
+          &Signature::from_slice(&recovery_tx.input[i].witness.to_vec()[0]).unwrap(),
+          &secp256k1::Message::from_slice(signature_hash.as_inner()).unwrap(),
+          &key_pair.to_inner().x_only_public_key().0,
+        )
+        .unwrap();
+    }
+  }
+
+  #[test]
+  fn recovery_transaction_must_pay_fee() {
+    let secp256k1 = Secp256k1::new();
+
+    let key_pair =
+      UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng()).tap_tweak(&secp256k1, None);
+
+    let commit_tx = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: vec![tx_in(outpoint(1))],
+      output: vec![commit_output(300, key_pair)],
+    };
+
+    assert_eq!(
+      Recover::recovery_transaction(
+        &commit_tx,
+        vec![(0, key_pair)],
+        recipient(),
+        FeeRate::try_from(1.0).unwrap(),
+      )
+      .unwrap_err()
+      .to_string(),
+      format!(
+        "outputs of commit transaction {} are too small to pay fee of 99 sats for recovery transaction",
+        commit_tx.txid()
+      )
+    );
+  }
+}
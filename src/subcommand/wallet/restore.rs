@@ -0,0 +1,44 @@
+use {super::*, bip39::Mnemonic, std::io::Read};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Restore {
+  #[clap(
+    long,
+    help = "Restore wallet from <MNEMONIC>. Read from standard input if omitted, which keeps it out of shell history and process listings."
+  )]
+  mnemonic: Option<Mnemonic>,
+  #[clap(
+    long,
+    default_value = "",
+    help = "Use <PASSPHRASE> when deriving wallet seed."
+  )]
+  passphrase: String,
+}
+
+impl Restore {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let mnemonic = match self.mnemonic {
+      Some(mnemonic) => mnemonic,
+      None => {
+        let mut input = String::new();
+        io::stdin()
+          .read_to_string(&mut input)
+          .context("failed to read mnemonic from standard input")?;
+        input
+          .trim()
+          .parse::<Mnemonic>()
+          .map_err(|err| anyhow!("invalid mnemonic: {err}"))?
+      }
+    };
+
+    initialize_wallet(
+      &options.bitcoin_rpc_client_mainnet_forbidden("ord wallet restore")?,
+      &options.wallet,
+      options.chain().network(),
+      &mnemonic.to_seed(&self.passphrase),
+      true,
+    )?;
+
+    Ok(())
+  }
+}
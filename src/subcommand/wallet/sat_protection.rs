@@ -0,0 +1,91 @@
+use {super::*, std::collections::BTreeSet};
+
+#[derive(Debug, Parser)]
+pub(crate) struct SatProtection {
+  #[clap(
+    long,
+    help = "Don't spend UTXOs containing <PROTECT_RARITY> or rarer sats as padding, postage, or fees. Requires index with `--index-sats`. [default: uncommon]"
+  )]
+  protect_rarity: Option<Rarity>,
+  #[clap(
+    long,
+    help = "Don't spend UTXOs containing sats listed in first column of tab-separated value file <PROTECTED_SATS> as padding, postage, or fees. Requires index with `--index-sats`."
+  )]
+  protected_sats: Option<PathBuf>,
+  #[clap(
+    long,
+    conflicts_with_all = &["protect-rarity", "protected-sats"],
+    help = "Allow spending UTXOs containing rare or protected sats."
+  )]
+  allow_rare: bool,
+}
+
+impl SatProtection {
+  const DEFAULT_PROTECT_RARITY: Rarity = Rarity::Uncommon;
+
+  /// Find the satpoints of sats in `utxos` that must not be spent as padding,
+  /// postage, or fees. Without a sat index nothing can be protected, which is
+  /// only an error if protection was explicitly requested. Outputs the index
+  /// hasn't seen yet, such as those of unconfirmed transactions, are skipped.
+  pub(crate) fn protected_sats(
+    &self,
+    index: &Index,
+    utxos: &BTreeMap<OutPoint, Amount>,
+  ) -> Result<BTreeSet<SatPoint>> {
+    if self.allow_rare {
+      return Ok(BTreeSet::new());
+    }
+
+    if !index.has_satoshi_index()? {
+      if self.protect_rarity.is_some() || self.protected_sats.is_some() {
+        bail!("protecting sats requires index created with `--index-sats` flag");
+      }
+
+      return Ok(BTreeSet::new());
+    }
+
+    let protect_rarity = self.protect_rarity.unwrap_or(Self::DEFAULT_PROTECT_RARITY);
+
+    let sats = match &self.protected_sats {
+      Some(path) => sats::sats_from_tsv(
+        &fs::read_to_string(path)
+          .with_context(|| format!("I/O error reading `{}`", path.display()))?,
+      )?
+      .into_iter()
+      .map(|(sat, _value)| sat)
+      .collect(),
+      None => Vec::new(),
+    };
+
+    let mut protected_sats = BTreeSet::new();
+
+    for outpoint in utxos.keys() {
+      let Some(List::Unspent(sat_ranges)) = index.list(*outpoint)? else {
+        continue;
+      };
+
+      let mut offset = 0;
+      for (start, end) in sat_ranges {
+        if Sat(start).rarity() >= protect_rarity {
+          protected_sats.insert(SatPoint {
+            outpoint: *outpoint,
+            offset,
+          });
+        }
+
+        for sat in &sats {
+          if (start..end).contains(&sat.n()) {
+            protected_sats.insert(SatPoint {
+              outpoint: *outpoint,
+              offset: offset + sat.n() - start,
+            });
+          }
+        }
+
+        offset += end - start;
+      }
+    }
+
+    Ok(protected_sats)
+  }
+}
@@ -54,11 +54,10 @@ fn rare_satoshis(utxos: Vec<(OutPoint, Vec<(u64, u64)>)>) -> Vec<(OutPoint, Sat,
     .collect()
 }
 
-fn satoshis_from_tsv(
-  utxos: Vec<(OutPoint, Vec<(u64, u64)>)>,
-  tsv: &str,
-) -> Result<Vec<(OutPoint, &str)>> {
-  let mut needles = Vec::new();
+/// Parse the sats in the first column of `tsv`, skipping empty lines and
+/// comments, and return each along with the string it was parsed from.
+pub(super) fn sats_from_tsv(tsv: &str) -> Result<Vec<(Sat, &str)>> {
+  let mut sats = Vec::new();
   for (i, line) in tsv.lines().enumerate() {
     if line.is_empty() || line.starts_with('#') {
       continue;
@@ -72,9 +71,18 @@ fn satoshis_from_tsv(
         )
       })?;
 
-      needles.push((sat, value));
+      sats.push((sat, value));
     }
   }
+
+  Ok(sats)
+}
+
+fn satoshis_from_tsv(
+  utxos: Vec<(OutPoint, Vec<(u64, u64)>)>,
+  tsv: &str,
+) -> Result<Vec<(OutPoint, &str)>> {
+  let mut needles = sats_from_tsv(tsv)?;
   needles.sort();
 
   let mut haystacks = utxos
@@ -246,6 +254,14 @@ mod tests {
     )
   }
 
+  #[test]
+  fn sats_from_tsv_accepts_any_sat_notation() {
+    assert_eq!(
+      sats_from_tsv("# comment\n\n5000000000\tuncommon\n1.1\n").unwrap(),
+      [(Sat(5000000000), "5000000000"), (Sat(5000000001), "1.1")]
+    );
+  }
+
   #[test]
   fn parse_error_reports_line_and_value() {
     assert_eq!(
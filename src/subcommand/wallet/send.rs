@@ -1,7 +1,8 @@
 use super::*;
 
 #[derive(Debug)]
-enum Reference {
+pub(crate) enum Reference {
+  Amount(Amount),
   SatPoint(SatPoint),
   InscriptionId(Txid),
 }
@@ -12,16 +13,68 @@ impl FromStr for Reference {
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     Ok(if s.len() == 64 {
       Self::InscriptionId(s.parse()?)
-    } else {
+    } else if s.contains(':') {
       Self::SatPoint(s.parse()?)
+    } else {
+      Self::Amount(parse_amount(s)?)
     })
   }
 }
 
+impl Reference {
+  /// Resolve to the satpoint to send. Inscriptions must be sent by
+  /// inscription ID, so that the sender is sure of what they're sending.
+  pub(crate) fn resolve(
+    self,
+    index: &Index,
+    inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+  ) -> Result<SatPoint> {
+    match self {
+      Self::Amount(amount) => bail!("amount {amount} does not refer to a sat or inscription"),
+      Self::SatPoint(satpoint) => {
+        for inscription_satpoint in inscriptions.keys() {
+          if satpoint == *inscription_satpoint {
+            bail!("inscriptions must be sent by inscription ID");
+          }
+        }
+        Ok(satpoint)
+      }
+      Self::InscriptionId(txid) => match index.get_inscription_by_inscription_id(txid)? {
+        Some((_inscription, satpoint)) => Ok(satpoint),
+        None => bail!("No inscription found for {txid}"),
+      },
+    }
+  }
+}
+
 #[derive(Debug, Parser)]
 pub(crate) struct Send {
+  #[clap(
+    help = "Send <OUTGOING>, an inscription ID, satpoint, or amount with denomination, e.g. `1000sat`"
+  )]
   outgoing: Reference,
   address: Address,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+  #[clap(flatten)]
+  sat_protection: SatProtection,
+  #[clap(
+    long,
+    value_parser = parse_amount,
+    help = "Target <POSTAGE> of output carrying the sent sat or inscription. [default: 10000sat]"
+  )]
+  postage: Option<Amount>,
+  #[clap(
+    long,
+    help = "Print PSBT of unsigned transaction instead of signing and broadcasting it."
+  )]
+  psbt: bool,
+  #[clap(
+    long,
+    conflicts_with = "psbt",
+    help = "Print transaction, including where inscriptions and rare sats in its inputs will land, instead of signing and broadcasting it."
+  )]
+  dry_run: bool,
 }
 
 impl Send {
@@ -43,25 +96,60 @@ impl Send {
 
     let inscriptions = index.get_inscriptions(None)?;
 
-    let change = get_change_addresses(&options, 2)?;
+    let protected_sats = self.sat_protection.protected_sats(&index, &utxos)?;
 
-    let satpoint = match self.outgoing {
-      Reference::SatPoint(satpoint) => {
-        for inscription_satpoint in inscriptions.keys() {
-          if satpoint == *inscription_satpoint {
-            bail!("inscriptions must be sent by inscription ID");
-          }
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    let unsigned_transaction = match self.outgoing {
+      Reference::Amount(amount) => {
+        if self.postage.is_some() {
+          bail!("--postage may only be used when sending a sat or inscription");
         }
-        satpoint
+
+        TransactionBuilder::build_transaction_with_value(
+          inscriptions.clone(),
+          protected_sats,
+          utxos.clone(),
+          self.address,
+          get_change_addresses(&options, 1)?,
+          fee_rate,
+          amount,
+        )?
       }
-      Reference::InscriptionId(txid) => match index.get_inscription_by_inscription_id(txid)? {
-        Some((_inscription, satpoint)) => satpoint,
-        None => bail!("No inscription found for {txid}"),
-      },
+      reference => TransactionBuilder::build_transaction(
+        reference.resolve(&index, &inscriptions)?,
+        inscriptions.clone(),
+        protected_sats,
+        utxos.clone(),
+        self.address,
+        get_change_addresses(&options, 2)?,
+        fee_rate,
+        self.postage.unwrap_or(TransactionBuilder::TARGET_POSTAGE),
+      )?,
     };
 
-    let unsigned_transaction =
-      TransactionBuilder::build_transaction(satpoint, inscriptions, utxos, self.address, change)?;
+    FeeOptions::report(fee_rate, transaction_fee(&unsigned_transaction, &utxos));
+
+    if self.dry_run {
+      let sat_ranges = Preview::input_sat_ranges(&index, &unsigned_transaction)?;
+
+      let preview = Preview::new(
+        &unsigned_transaction,
+        TransactionBuilder::estimate_transaction_vsize(&unsigned_transaction),
+        &utxos,
+        &inscriptions,
+        sat_ranges.as_ref(),
+        options.chain().network(),
+      );
+
+      println!("{}", serde_json::to_string_pretty(&preview)?);
+      return Ok(());
+    }
+
+    if self.psbt {
+      println!("{}", create_psbt(&client, &unsigned_transaction)?);
+      return Ok(());
+    }
 
     let signed_tx = client
       .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
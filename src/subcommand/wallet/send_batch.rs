@@ -0,0 +1,210 @@
+use {super::*, send::Reference};
+
+#[derive(Debug, Parser)]
+pub(crate) struct SendBatch {
+  #[clap(
+    help = "Send each inscription ID or satpoint in first column of <FILE> to address in second column"
+  )]
+  file: PathBuf,
+  #[clap(flatten)]
+  fee_options: FeeOptions,
+  #[clap(flatten)]
+  sat_protection: SatProtection,
+  #[clap(
+    long,
+    value_parser = parse_amount,
+    help = "Target <POSTAGE> of each output carrying a sent sat or inscription. [default: 10000sat]"
+  )]
+  postage: Option<Amount>,
+  #[clap(
+    long,
+    help = "Print PSBT of unsigned transaction instead of signing and broadcasting it."
+  )]
+  psbt: bool,
+  #[clap(
+    long,
+    conflicts_with = "psbt",
+    help = "Print transaction, including where inscriptions and rare sats in its inputs will land, instead of signing and broadcasting it."
+  )]
+  dry_run: bool,
+}
+
+impl SendBatch {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet send-batch")?;
+
+    let sends = Self::parse(
+      &fs::read_to_string(&self.file)
+        .with_context(|| format!("I/O error reading `{}`", self.file.display()))?,
+    )?;
+
+    if sends.is_empty() {
+      bail!("`{}` contains no sends", self.file.display());
+    }
+
+    for (_reference, address) in &sends {
+      if !address.is_valid_for_network(options.chain().network()) {
+        bail!("Address `{}` is not valid for {}", address, options.chain());
+      }
+    }
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let utxos = list_utxos(&options)?;
+
+    let inscriptions = index.get_inscriptions(None)?;
+
+    let protected_sats = self.sat_protection.protected_sats(&index, &utxos)?;
+
+    let change = get_change_addresses(&options, 2 * sends.len())?;
+
+    let mut outgoing = Vec::new();
+    for (reference, address) in sends {
+      outgoing.push((reference.resolve(&index, &inscriptions)?, address));
+    }
+
+    let fee_rate = self.fee_options.fee_rate(&client)?;
+
+    let unsigned_transaction = TransactionBuilder::build_batch_transaction(
+      outgoing,
+      inscriptions.clone(),
+      protected_sats,
+      utxos.clone(),
+      change,
+      fee_rate,
+      self.postage.unwrap_or(TransactionBuilder::TARGET_POSTAGE),
+    )?;
+
+    FeeOptions::report(fee_rate, transaction_fee(&unsigned_transaction, &utxos));
+
+    if self.dry_run {
+      let sat_ranges = Preview::input_sat_ranges(&index, &unsigned_transaction)?;
+
+      let preview = Preview::new(
+        &unsigned_transaction,
+        TransactionBuilder::estimate_transaction_vsize(&unsigned_transaction),
+        &utxos,
+        &inscriptions,
+        sat_ranges.as_ref(),
+        options.chain().network(),
+      );
+
+      println!("{}", serde_json::to_string_pretty(&preview)?);
+      return Ok(());
+    }
+
+    if self.psbt {
+      println!("{}", create_psbt(&client, &unsigned_transaction)?);
+      return Ok(());
+    }
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let txid = client.send_raw_transaction(&signed_tx)?;
+
+    println!("{txid}");
+    Ok(())
+  }
+
+  /// Parse lines of whitespace-separated inscription IDs or satpoints and
+  /// addresses, skipping empty lines and lines starting with `#`.
+  fn parse(batch: &str) -> Result<Vec<(Reference, Address)>> {
+    let mut sends = Vec::new();
+
+    for (i, line) in batch.lines().enumerate() {
+      let line = line.trim();
+
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let mut columns = line.split_whitespace();
+
+      let (Some(outgoing), Some(address), None) = (columns.next(), columns.next(), columns.next())
+      else {
+        bail!(
+          "expected inscription ID or satpoint and address on line {}",
+          i + 1
+        );
+      };
+
+      let reference = outgoing.parse().with_context(|| {
+        format!(
+          "failed to parse inscription ID or satpoint \"{outgoing}\" on line {}",
+          i + 1
+        )
+      })?;
+
+      if let Reference::Amount(_) = reference {
+        bail!(
+          "amounts cannot be sent in a batch, found \"{outgoing}\" on line {}",
+          i + 1
+        );
+      }
+
+      sends.push((
+        reference,
+        address
+          .parse()
+          .with_context(|| format!("failed to parse address \"{address}\" on line {}", i + 1))?,
+      ));
+    }
+
+    Ok(sends)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse() {
+    let sends = SendBatch::parse(&format!(
+      "# airdrop\n\n{} {}\n{}\t{}\n",
+      txid(1),
+      recipient(),
+      satpoint(2, 0),
+      change(0),
+    ))
+    .unwrap();
+
+    assert_eq!(sends.len(), 2);
+    assert!(matches!(sends[0].0, Reference::InscriptionId(id) if id == txid(1)));
+    assert_eq!(sends[0].1, recipient());
+    assert!(matches!(sends[1].0, Reference::SatPoint(outgoing) if outgoing == satpoint(2, 0)));
+    assert_eq!(sends[1].1, change(0));
+  }
+
+  #[test]
+  fn parse_reports_line_of_error() {
+    assert_eq!(
+      SendBatch::parse(&format!("\n{}:0:0\n", txid(1)))
+        .unwrap_err()
+        .to_string(),
+      "expected inscription ID or satpoint and address on line 2"
+    );
+
+    assert_eq!(
+      SendBatch::parse(&format!(
+        "{}:0:0 {}\nfoo {}\n",
+        txid(1),
+        recipient(),
+        recipient()
+      ))
+      .unwrap_err()
+      .to_string(),
+      "failed to parse inscription ID or satpoint \"foo\" on line 2"
+    );
+
+    assert_eq!(
+      SendBatch::parse(&format!("1000sat {}\n", recipient()))
+        .unwrap_err()
+        .to_string(),
+      "amounts cannot be sent in a batch, found \"1000sat\" on line 1"
+    );
+  }
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Sign {
+  #[clap(
+    long,
+    help = "Sign with the key controlling the output that holds <INSCRIPTION>"
+  )]
+  inscription: InscriptionId,
+  #[clap(long, help = "Sign <MESSAGE>")]
+  message: String,
+}
+
+impl Sign {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let client = options.bitcoin_rpc_client_for_wallet_command("ord wallet sign")?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let satpoint = match index.get_inscription_by_inscription_id(self.inscription)? {
+      Some((_inscription, satpoint)) => satpoint,
+      None => bail!("No inscription found for {}", self.inscription),
+    };
+
+    if !list_utxos(&options)?.contains_key(&satpoint.outpoint) {
+      bail!("inscription {} not in wallet", self.inscription);
+    }
+
+    let tx_out = index
+      .get_transaction(satpoint.outpoint.txid)?
+      .and_then(|tx| {
+        tx.output
+          .into_iter()
+          .nth(usize::try_from(satpoint.outpoint.vout).ok()?)
+      })
+      .ok_or_else(|| anyhow!("output {} not found", satpoint.outpoint))?;
+
+    let to_spend = bip322::to_spend(&tx_out.script_pubkey, self.message.as_bytes());
+
+    let mut psbt = Psbt::from_unsigned_tx(bip322::to_sign(&to_spend, Witness::new()))?;
+    psbt.inputs[0].witness_utxo = Some(to_spend.output[0].clone());
+
+    let processed = client
+      .wallet_process_psbt(&encode_psbt(&psbt), Some(true), None, None)
+      .context("wallet failed to sign message")?;
+
+    let witness = decode_psbt(&processed.psbt)?.inputs[0]
+      .final_script_witness
+      .clone()
+      .filter(|_| processed.complete)
+      .ok_or_else(|| anyhow!("wallet failed to sign message"))?;
+
+    println!("{}", bip322::encode_signature(&witness));
+
+    Ok(())
+  }
+}
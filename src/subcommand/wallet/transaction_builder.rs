@@ -10,12 +10,22 @@
 //!
 //! The external interface is `TransactionBuilder::build_transaction`, which
 //! returns a constructed transaction given the arguments, which include the
-//! outgoing sat to send, the wallets current UTXOs and their sat ranges, and
-//! the recipient's address.
+//! outgoing sat to send, the wallets current UTXOs and their sat ranges, the
+//! satpoints of rare sats that must not be spent as padding, postage, or
+//! fees, and the recipient's address.
+//! `TransactionBuilder::build_batch_transaction` does the same for several
+//! outgoing sats, each with its own recipient, which must be in distinct
+//! UTXOs. `TransactionBuilder::build_transaction_to_wallet` sends the
+//! outgoing sat to an address of the wallet itself, and so may spend protected
+//! sats after it, as long as they are returned to the wallet in a change
+//! output.
 //!
 //! Internally, `TransactionBuilder` calls multiple methods that implement
 //! transformations responsible for individual concerns, such as ensuring that
 //! the transaction fee is paid, and that outgoing outputs aren't too large.
+//! Each outgoing sat gets its own segment of the transaction, consisting of an
+//! optional alignment output, the recipient output, and an optional output
+//! for stripped postage, and each transformation is applied to every segment.
 //!
 //! This module is tested heavily. For all features of transaction
 //! construction, there should be a positive test that checks that the feature
@@ -34,6 +44,10 @@ use {
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum Error {
+  Dust {
+    output_value: Amount,
+    dust_value: Amount,
+  },
   NotInWallet(SatPoint),
   NotEnoughCardinalUtxos,
   UtxoContainsAdditionalInscription {
@@ -41,11 +55,28 @@ pub(crate) enum Error {
     inscribed_satpoint: SatPoint,
     inscription_id: InscriptionId,
   },
+  UtxoContainsProtectedSat {
+    outgoing_satpoint: SatPoint,
+    protected_satpoint: SatPoint,
+  },
+  UtxoContainsAdditionalOutgoing {
+    outgoing_satpoint: SatPoint,
+    additional_satpoint: SatPoint,
+  },
 }
 
 impl fmt::Display for Error {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self {
+      Error::Dust {
+        output_value,
+        dust_value,
+      } => write!(
+        f,
+        "output value of {} sats is below dust limit of {} sats",
+        output_value.to_sat(),
+        dust_value.to_sat()
+      ),
       Error::NotInWallet(outgoing_satpoint) => write!(f, "outgoing satpoint {outgoing_satpoint} not in wallet"),
       Error::NotEnoughCardinalUtxos => write!(
         f,
@@ -59,6 +90,20 @@ impl fmt::Display for Error {
         f,
         "cannot send {outgoing_satpoint} without also sending inscription {inscription_id} at {inscribed_satpoint}"
       ),
+      Error::UtxoContainsProtectedSat {
+        outgoing_satpoint,
+        protected_satpoint,
+      } => write!(
+        f,
+        "cannot send {outgoing_satpoint} without also spending protected sat at {protected_satpoint}"
+      ),
+      Error::UtxoContainsAdditionalOutgoing {
+        outgoing_satpoint,
+        additional_satpoint,
+      } => write!(
+        f,
+        "cannot send {outgoing_satpoint} and {additional_satpoint} in the same transaction, since they are in the same UTXO"
+      ),
     }
   }
 }
@@ -69,118 +114,313 @@ impl std::error::Error for Error {}
 pub(crate) struct TransactionBuilder {
   amounts: BTreeMap<OutPoint, Amount>,
   change_addresses: BTreeSet<Address>,
+  fee_rate: FeeRate,
   inputs: Vec<OutPoint>,
   inscriptions: BTreeMap<SatPoint, InscriptionId>,
   outputs: Vec<(Address, Amount)>,
-  recipient: Address,
-  outgoing: SatPoint,
+  protected_sats: BTreeSet<SatPoint>,
+  outgoing: Vec<(SatPoint, Address)>,
+  target_postage: Amount,
   unused_change_addresses: Vec<Address>,
   utxos: BTreeSet<OutPoint>,
+  wallet_recipient: bool,
 }
 
 type Result<T> = std::result::Result<T, Error>;
 
 impl TransactionBuilder {
-  pub(crate) const TARGET_FEE_RATE: Amount = Amount::from_sat(1);
-  const MAX_POSTAGE: Amount = Amount::from_sat(2 * 10_000);
-  const TARGET_POSTAGE: Amount = Amount::from_sat(10_000);
+  const ADDITIONAL_OUTPUT_VSIZE: usize = 43;
+  pub(crate) const TARGET_POSTAGE: Amount = Amount::from_sat(10_000);
 
   pub(crate) fn build_transaction(
     outgoing: SatPoint,
     inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
+    amounts: BTreeMap<OutPoint, Amount>,
+    recipient: Address,
+    change: Vec<Address>,
+    fee_rate: FeeRate,
+    target_postage: Amount,
+  ) -> Result<Transaction> {
+    Self::build_batch_transaction(
+      vec![(outgoing, recipient)],
+      inscriptions,
+      protected_sats,
+      amounts,
+      change,
+      fee_rate,
+      target_postage,
+    )
+  }
+
+  /// Build a transaction sending each outgoing sat to its recipient. Since
+  /// every segment may need an alignment output and an output for stripped
+  /// postage, `change` should contain two addresses per outgoing sat.
+  pub(crate) fn build_batch_transaction(
+    outgoing: Vec<(SatPoint, Address)>,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
+    amounts: BTreeMap<OutPoint, Amount>,
+    change: Vec<Address>,
+    fee_rate: FeeRate,
+    target_postage: Amount,
+  ) -> Result<Transaction> {
+    Self::new(
+      outgoing,
+      inscriptions,
+      protected_sats,
+      amounts,
+      change,
+      fee_rate,
+      target_postage,
+    )
+    .build_outgoing()
+  }
+
+  /// Build a transaction sending `outgoing` to `recipient`, which must be an
+  /// address of this wallet. Protected sats after `outgoing` stay in the
+  /// wallet, so instead of being an error, they are stripped from the
+  /// recipient output into a change output.
+  pub(crate) fn build_transaction_to_wallet(
+    outgoing: SatPoint,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
     amounts: BTreeMap<OutPoint, Amount>,
     recipient: Address,
     change: Vec<Address>,
+    fee_rate: FeeRate,
+    target_postage: Amount,
   ) -> Result<Transaction> {
-    Self::new(outgoing, inscriptions, amounts, recipient, change)
+    Self {
+      wallet_recipient: true,
+      ..Self::new(
+        vec![(outgoing, recipient)],
+        inscriptions,
+        protected_sats,
+        amounts,
+        change,
+        fee_rate,
+        target_postage,
+      )
+    }
+    .build_outgoing()
+  }
+
+  fn build_outgoing(self) -> Result<Transaction> {
+    for (_satpoint, recipient) in &self.outgoing {
+      let dust_value = recipient.script_pubkey().dust_value();
+
+      if self.target_postage < dust_value {
+        return Err(Error::Dust {
+          output_value: self.target_postage,
+          dust_value,
+        });
+      }
+    }
+
+    self
       .select_outgoing()?
       .align_outgoing()
       .pad_alignment_output()?
       .add_postage()?
       .strip_excess_postage()
+      .strip_protected_sats()?
       .deduct_fee()
       .build()
   }
 
-  fn new(
-    outgoing: SatPoint,
+  /// Build a transaction sending `output_value` to `recipient`, funded only
+  /// by cardinal UTXOs, with any change going to the first of `change`.
+  pub(crate) fn build_transaction_with_value(
     inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
     amounts: BTreeMap<OutPoint, Amount>,
     recipient: Address,
     change: Vec<Address>,
+    fee_rate: FeeRate,
+    output_value: Amount,
+  ) -> Result<Transaction> {
+    Self::build_transaction_with_values(
+      inscriptions,
+      protected_sats,
+      amounts,
+      vec![(recipient, output_value)],
+      change,
+      fee_rate,
+    )
+  }
+
+  /// Build a transaction whose first outputs are `outputs`, in order, funded
+  /// only by cardinal UTXOs, with any change going to the first of `change`.
+  pub(crate) fn build_transaction_with_values(
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
+    amounts: BTreeMap<OutPoint, Amount>,
+    outputs: Vec<(Address, Amount)>,
+    change: Vec<Address>,
+    fee_rate: FeeRate,
+  ) -> Result<Transaction> {
+    for (recipient, output_value) in &outputs {
+      let dust_value = recipient.script_pubkey().dust_value();
+
+      if *output_value < dust_value {
+        return Err(Error::Dust {
+          output_value: *output_value,
+          dust_value,
+        });
+      }
+    }
+
+    let mut builder = Self::new(
+      Vec::new(),
+      inscriptions,
+      protected_sats,
+      amounts,
+      change,
+      fee_rate,
+      Self::TARGET_POSTAGE,
+    );
+
+    let output_value = outputs.iter().map(|(_recipient, amount)| *amount).sum();
+
+    builder.outputs = outputs.clone();
+
+    builder
+      .select_cardinal_inputs(output_value)?
+      .add_value_change(output_value)
+      .build_with_values(outputs)
+  }
+
+  fn new(
+    outgoing: Vec<(SatPoint, Address)>,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    protected_sats: BTreeSet<SatPoint>,
+    amounts: BTreeMap<OutPoint, Amount>,
+    change: Vec<Address>,
+    fee_rate: FeeRate,
+    target_postage: Amount,
   ) -> Self {
     Self {
       utxos: amounts.keys().cloned().collect(),
       amounts,
       change_addresses: change.iter().cloned().collect(),
+      fee_rate,
       inputs: Vec::new(),
       inscriptions,
       outputs: Vec::new(),
-      recipient,
+      protected_sats,
       outgoing,
+      target_postage,
       unused_change_addresses: change,
+      wallet_recipient: false,
     }
   }
 
   fn select_outgoing(mut self) -> Result<Self> {
-    for (inscribed_satpoint, inscription_id) in &self.inscriptions {
-      if self.outgoing.outpoint == inscribed_satpoint.outpoint
-        && self.outgoing.offset != inscribed_satpoint.offset
-      {
-        return Err(Error::UtxoContainsAdditionalInscription {
-          outgoing_satpoint: self.outgoing,
-          inscribed_satpoint: *inscribed_satpoint,
-          inscription_id: *inscription_id,
-        });
+    let outgoing_satpoints = self.outgoing_satpoints();
+
+    for (i, (outgoing, _recipient)) in self.outgoing.iter().enumerate() {
+      for (inscribed_satpoint, inscription_id) in &self.inscriptions {
+        if outgoing.outpoint == inscribed_satpoint.outpoint
+          && !outgoing_satpoints.contains(inscribed_satpoint)
+        {
+          return Err(Error::UtxoContainsAdditionalInscription {
+            outgoing_satpoint: *outgoing,
+            inscribed_satpoint: *inscribed_satpoint,
+            inscription_id: *inscription_id,
+          });
+        }
+      }
+
+      // Protected sats before the outgoing sat are returned to the wallet in
+      // the alignment output, but those after it could end up in the
+      // recipient's output or be spent as fees, unless the recipient is this
+      // wallet, in which case they are stripped into a change output.
+      for protected_satpoint in &self.protected_sats {
+        if !self.wallet_recipient
+          && outgoing.outpoint == protected_satpoint.outpoint
+          && protected_satpoint.offset > outgoing.offset
+        {
+          return Err(Error::UtxoContainsProtectedSat {
+            outgoing_satpoint: *outgoing,
+            protected_satpoint: *protected_satpoint,
+          });
+        }
+      }
+
+      for (additional, _recipient) in &self.outgoing[..i] {
+        if outgoing.outpoint == additional.outpoint {
+          return Err(Error::UtxoContainsAdditionalOutgoing {
+            outgoing_satpoint: *additional,
+            additional_satpoint: *outgoing,
+          });
+        }
       }
     }
 
-    self.utxos.remove(&self.outgoing.outpoint);
-    self.inputs.push(self.outgoing.outpoint);
-    self.outputs.push((
-      self.recipient.clone(),
-      *self
-        .amounts
-        .get(&self.outgoing.outpoint)
-        .ok_or(Error::NotInWallet(self.outgoing))?,
-    ));
+    for (outgoing, recipient) in &self.outgoing {
+      self.utxos.remove(&outgoing.outpoint);
+      self.inputs.push(outgoing.outpoint);
+      self.outputs.push((
+        recipient.clone(),
+        *self
+          .amounts
+          .get(&outgoing.outpoint)
+          .ok_or(Error::NotInWallet(*outgoing))?,
+      ));
+    }
 
     Ok(self)
   }
 
   fn align_outgoing(mut self) -> Self {
-    assert_eq!(self.outputs.len(), 1, "invariant: only one output");
-
     assert_eq!(
-      self.outputs[0].0, self.recipient,
-      "invariant: first output is recipient"
+      self.outputs.len(),
+      self.outgoing.len(),
+      "invariant: only one output per outgoing sat"
     );
 
-    let sat_offset = self.calculate_sat_offset();
-    if sat_offset != 0 {
-      self.outputs.insert(
-        0,
-        (
-          self
-            .unused_change_addresses
-            .pop()
-            .expect("not enough change addresses"),
-          Amount::from_sat(sat_offset),
-        ),
+    for (outgoing, recipient) in self.outgoing.clone() {
+      let sat_offset = self.calculate_sat_offset(outgoing);
+      let (index, output_start) = self.output_containing(sat_offset);
+
+      assert_eq!(
+        self.outputs[index].0, recipient,
+        "invariant: outgoing sat is in recipient output"
       );
-      self.outputs.last_mut().expect("no output").1 -= Amount::from_sat(sat_offset);
+
+      if sat_offset != output_start {
+        self.outputs.insert(
+          index,
+          (
+            self
+              .unused_change_addresses
+              .pop()
+              .expect("not enough change addresses"),
+            Amount::from_sat(sat_offset - output_start),
+          ),
+        );
+        self.outputs[index + 1].1 -= Amount::from_sat(sat_offset - output_start);
+      }
     }
 
     self
   }
 
   fn pad_alignment_output(mut self) -> Result<Self> {
-    if self.outputs[0].0 != self.recipient {
-      let dust_limit = self.recipient.script_pubkey().dust_value();
-      if self.outputs[0].1 < dust_limit {
-        let (utxo, size) = self.select_cardinal_utxo(dust_limit - self.outputs[0].1)?;
-        self.inputs.insert(0, utxo);
-        self.outputs[0].1 += size;
+    for (outgoing, recipient) in self.outgoing.clone() {
+      if outgoing.offset == 0 {
+        continue;
+      }
+
+      let (index, _output_start) = self.output_containing(self.calculate_sat_offset(outgoing));
+
+      let dust_limit = recipient.script_pubkey().dust_value();
+      if self.outputs[index - 1].1 < dust_limit {
+        let (utxo, size) = self.select_cardinal_utxo(dust_limit - self.outputs[index - 1].1)?;
+        let input = self.input_position(outgoing);
+        self.inputs.insert(input, utxo);
+        self.outputs[index - 1].1 += size;
       }
     }
 
@@ -188,49 +428,172 @@ impl TransactionBuilder {
   }
 
   fn add_postage(mut self) -> Result<Self> {
-    let estimated_fee = self.estimate_fee();
-    let dust_limit = self.outputs.last().unwrap().0.script_pubkey().dust_value();
+    let mut stripped_outputs = 0;
 
-    if self.outputs.last().unwrap().1 < dust_limit + estimated_fee {
-      let (utxo, size) =
-        self.select_cardinal_utxo(dust_limit + estimated_fee - self.outputs.last().unwrap().1)?;
-      self.inputs.push(utxo);
-      self.outputs.last_mut().unwrap().1 += size;
+    for (i, (outgoing, recipient)) in self.outgoing.clone().into_iter().enumerate() {
+      let (index, _output_start) = self.output_containing(self.calculate_sat_offset(outgoing));
+
+      // The fee is deducted from the last output, which must cover it even if
+      // the postage of every earlier segment is later stripped into an
+      // additional output.
+      let estimated_fee = if i == self.outgoing.len() - 1 {
+        self
+          .fee_rate
+          .fee(self.estimate_vsize() + stripped_outputs * Self::ADDITIONAL_OUTPUT_VSIZE)
+      } else {
+        Amount::ZERO
+      };
+
+      let dust_limit = recipient.script_pubkey().dust_value();
+
+      if self.outputs[index].1 < dust_limit + estimated_fee {
+        let (utxo, size) =
+          self.select_cardinal_utxo(dust_limit + estimated_fee - self.outputs[index].1)?;
+        let input = self.input_position(outgoing) + 1;
+        self.inputs.insert(input, utxo);
+        self.outputs[index].1 += size;
+      }
+
+      if self.outputs[index].1 > self.max_postage() {
+        stripped_outputs += 1;
+      }
     }
+
     Ok(self)
   }
 
   fn strip_excess_postage(mut self) -> Self {
-    let sat_offset = self.calculate_sat_offset();
-    let total_output_amount = self
-      .outputs
-      .iter()
-      .map(|(_address, amount)| *amount)
-      .sum::<Amount>();
-
-    self
-      .outputs
-      .iter()
-      .position(|(address, _amount)| address == &self.recipient)
-      .expect("couldn't find output that contains the index");
+    for (outgoing, _recipient) in self.outgoing.clone() {
+      let (index, _output_start) = self.output_containing(self.calculate_sat_offset(outgoing));
 
-    let postage = total_output_amount - Amount::from_sat(sat_offset);
-    if postage > Self::MAX_POSTAGE {
-      self.outputs.last_mut().expect("no outputs found").1 = Self::TARGET_POSTAGE;
-      self.outputs.push((
-        self
+      let postage = self.outputs[index].1;
+      if postage > self.max_postage() {
+        let change_address = self
           .unused_change_addresses
-          .pop()
-          .expect("not enough change addresses"),
-        postage - Self::TARGET_POSTAGE,
-      ));
+          .last()
+          .expect("not enough change addresses")
+          .clone();
+
+        let change_fee = self
+          .fee_rate
+          .fee(self.estimate_vsize() + Self::ADDITIONAL_OUTPUT_VSIZE);
+
+        // At high fee rates, the excess postage may be too small to pay for
+        // its own output, in which case it is left in the recipient output.
+        if postage - self.target_postage >= change_address.script_pubkey().dust_value() + change_fee
+        {
+          self.unused_change_addresses.pop();
+          self.outputs[index].1 = self.target_postage;
+          self
+            .outputs
+            .insert(index + 1, (change_address, postage - self.target_postage));
+        }
+      }
     }
 
     self
   }
 
+  fn strip_protected_sats(mut self) -> Result<Self> {
+    if !self.wallet_recipient {
+      return Ok(self);
+    }
+
+    let mut last_protected_sat_offset = None;
+
+    for (outgoing, recipient) in self.outgoing.clone() {
+      let Some(protected_satpoint) = self
+        .protected_sats
+        .iter()
+        .filter(|satpoint| {
+          satpoint.outpoint == outgoing.outpoint && satpoint.offset > outgoing.offset
+        })
+        .min_by_key(|satpoint| satpoint.offset)
+        .copied()
+      else {
+        continue;
+      };
+
+      let (index, _output_start) = self.output_containing(self.calculate_sat_offset(outgoing));
+
+      let postage = self.outputs[index].1;
+      let limit = Amount::from_sat(protected_satpoint.offset - outgoing.offset);
+
+      if limit < recipient.script_pubkey().dust_value() {
+        return Err(Error::UtxoContainsProtectedSat {
+          outgoing_satpoint: outgoing,
+          protected_satpoint,
+        });
+      }
+
+      if postage > limit {
+        self.outputs[index].1 = limit;
+
+        match self.outputs.get_mut(index + 1) {
+          Some((address, amount)) if self.change_addresses.contains(address) => {
+            *amount += postage - limit;
+          }
+          _ => {
+            let change_address = self
+              .unused_change_addresses
+              .pop()
+              .expect("not enough change addresses");
+            self
+              .outputs
+              .insert(index + 1, (change_address, postage - limit));
+          }
+        }
+      }
+
+      for satpoint in &self.protected_sats {
+        if satpoint.outpoint == outgoing.outpoint && satpoint.offset > outgoing.offset {
+          last_protected_sat_offset = last_protected_sat_offset.max(Some(
+            self.calculate_sat_offset(outgoing) - outgoing.offset + satpoint.offset,
+          ));
+        }
+      }
+    }
+
+    // The fee is deducted from the end of the last output, so add cardinal
+    // inputs until it is large enough to pay the fee without spending any
+    // stripped protected sats.
+    if let Some(last_protected_sat_offset) = last_protected_sat_offset {
+      loop {
+        let fee = self.estimate_fee();
+
+        let total_output_amount = self
+          .outputs
+          .iter()
+          .map(|(_address, amount)| *amount)
+          .sum::<Amount>();
+
+        let (address, last_output_amount) = self.outputs.last().expect("no output");
+
+        let last_output_shortfall = (fee + address.script_pubkey().dust_value())
+          .checked_sub(*last_output_amount)
+          .unwrap_or(Amount::ZERO);
+
+        let protected_sat_shortfall = (fee + Amount::from_sat(last_protected_sat_offset + 1))
+          .checked_sub(total_output_amount)
+          .unwrap_or(Amount::ZERO);
+
+        let required = last_output_shortfall.max(protected_sat_shortfall);
+
+        if required == Amount::ZERO {
+          break;
+        }
+
+        let (utxo, size) = self.select_cardinal_utxo(required)?;
+        self.inputs.push(utxo);
+        self.outputs.last_mut().expect("no output").1 += size;
+      }
+    }
+
+    Ok(self)
+  }
+
   fn deduct_fee(mut self) -> Self {
-    let sat_offset = self.calculate_sat_offset();
+    let sat_offset = self.calculate_sat_offset(self.outgoing.last().expect("no outgoing sat").0);
 
     let fee = self.estimate_fee();
 
@@ -255,25 +618,68 @@ impl TransactionBuilder {
     self
   }
 
-  /// Estimate the size in virtual bytes of the transaction being built. Since
-  /// we don't know the size of the input script sigs and witnesses, assume
-  /// they are P2PKH, so that we get a worst case estimate, since it's probably
-  /// better to pay too overestimate and pay too much in fees than to
-  /// underestimate and never get the transaction confirmed, or, even worse, be
-  /// under the minimum relay fee and never even get relayed.
+  fn select_cardinal_inputs(mut self, output_value: Amount) -> Result<Self> {
+    loop {
+      let input_value = self.input_value();
+      let required = output_value + self.estimate_fee();
+
+      if input_value >= required {
+        return Ok(self);
+      }
+
+      let (utxo, _amount) = match self.select_cardinal_utxo(required - input_value) {
+        Err(Error::NotEnoughCardinalUtxos) => self.select_largest_cardinal_utxo()?,
+        result => result?,
+      };
+
+      self.inputs.push(utxo);
+    }
+  }
+
+  fn add_value_change(mut self, output_value: Amount) -> Self {
+    let change_address = self
+      .unused_change_addresses
+      .pop()
+      .expect("not enough change addresses");
+
+    let dust_limit = change_address.script_pubkey().dust_value();
+
+    self.outputs.push((change_address, Amount::ZERO));
+
+    let excess = self.input_value() - output_value;
+    let fee = self.estimate_fee();
+
+    // If the change is too small to pay for its own output, it is left for
+    // the fee to consume.
+    if excess >= fee + dust_limit {
+      self.outputs.last_mut().expect("no change output").1 = excess - fee;
+    } else {
+      let (change_address, _amount) = self.outputs.pop().expect("no change output");
+      self.unused_change_addresses.push(change_address);
+    }
+
+    self
+  }
+
+  fn input_value(&self) -> Amount {
+    self
+      .inputs
+      .iter()
+      .map(|outpoint| self.amounts[outpoint])
+      .sum()
+  }
+
+  /// Estimate the size in virtual bytes of the transaction being built.
   fn estimate_vsize(&self) -> usize {
-    Transaction {
+    Self::estimate_transaction_vsize(&Transaction {
       version: 1,
       lock_time: PackedLockTime::ZERO,
       input: self
         .inputs
         .iter()
-        .map(|_| TxIn {
-          previous_output: OutPoint::null(),
-          script_sig: script::Builder::new()
-            .push_slice(&[0; 71])
-            .push_slice(&[0; 65])
-            .into_script(),
+        .map(|outpoint| TxIn {
+          previous_output: *outpoint,
+          script_sig: Script::new(),
           sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
           witness: Witness::new(),
         })
@@ -286,16 +692,43 @@ impl TransactionBuilder {
           script_pubkey: address.script_pubkey(),
         })
         .collect(),
+    })
+  }
+
+  /// Estimate the size in virtual bytes of an unsigned transaction. Since we
+  /// don't know the size of the input script sigs and witnesses, assume they
+  /// are P2PKH, so that we get a worst case estimate, since it's probably
+  /// better to pay too overestimate and pay too much in fees than to
+  /// underestimate and never get the transaction confirmed, or, even worse, be
+  /// under the minimum relay fee and never even get relayed.
+  pub(crate) fn estimate_transaction_vsize(transaction: &Transaction) -> usize {
+    Transaction {
+      input: transaction
+        .input
+        .iter()
+        .map(|tx_in| TxIn {
+          script_sig: script::Builder::new()
+            .push_slice(&[0; 71])
+            .push_slice(&[0; 65])
+            .into_script(),
+          witness: Witness::new(),
+          ..tx_in.clone()
+        })
+        .collect(),
+      ..transaction.clone()
     }
     .vsize()
   }
 
+  fn max_postage(&self) -> Amount {
+    self.target_postage * 2
+  }
+
   fn estimate_fee(&self) -> Amount {
-    Self::TARGET_FEE_RATE * self.estimate_vsize().try_into().unwrap()
+    self.fee_rate.fee(self.estimate_vsize())
   }
 
   fn build(self) -> Result<Transaction> {
-    let recipient = self.recipient.script_pubkey();
     let transaction = Transaction {
       version: 1,
       lock_time: PackedLockTime::ZERO,
@@ -319,64 +752,123 @@ impl TransactionBuilder {
         .collect(),
     };
 
-    assert_eq!(
-      self
-        .amounts
-        .iter()
-        .filter(|(outpoint, amount)| *outpoint == &self.outgoing.outpoint
-          && self.outgoing.offset < amount.to_sat())
-        .count(),
-      1,
-      "invariant: outgoing sat is contained in utxos"
-    );
+    let outgoing_satpoints = self.outgoing_satpoints();
 
-    assert_eq!(
-      transaction
-        .input
-        .iter()
-        .filter(|tx_in| tx_in.previous_output == self.outgoing.outpoint)
-        .count(),
-      1,
-      "invariant: inputs spend outgoing sat"
-    );
+    let mut sat_offsets = BTreeMap::<Script, BTreeSet<u64>>::new();
 
-    let mut sat_offset = 0;
-    let mut found = false;
-    for tx_in in &transaction.input {
-      if tx_in.previous_output == self.outgoing.outpoint {
-        sat_offset += self.outgoing.offset;
-        found = true;
-        break;
-      } else {
-        sat_offset += self.amounts[&tx_in.previous_output].to_sat();
+    for (outgoing, recipient) in &self.outgoing {
+      let recipient = recipient.script_pubkey();
+
+      assert_eq!(
+        self
+          .amounts
+          .iter()
+          .filter(|(outpoint, amount)| *outpoint == &outgoing.outpoint
+            && outgoing.offset < amount.to_sat())
+          .count(),
+        1,
+        "invariant: outgoing sat is contained in utxos"
+      );
+
+      assert_eq!(
+        transaction
+          .input
+          .iter()
+          .filter(|tx_in| tx_in.previous_output == outgoing.outpoint)
+          .count(),
+        1,
+        "invariant: inputs spend outgoing sat"
+      );
+
+      let mut sat_offset = 0;
+      let mut found = false;
+      for tx_in in &transaction.input {
+        if tx_in.previous_output == outgoing.outpoint {
+          sat_offset += outgoing.offset;
+          found = true;
+          break;
+        } else {
+          sat_offset += self.amounts[&tx_in.previous_output].to_sat();
+        }
+      }
+      assert!(found, "invariant: outgoing sat is found in inputs");
+
+      let mut output_end = 0;
+      let mut found = false;
+      for tx_out in &transaction.output {
+        output_end += tx_out.value;
+        if output_end > sat_offset {
+          assert_eq!(
+            tx_out.script_pubkey, recipient,
+            "invariant: outgoing sat is sent to recipient"
+          );
+          found = true;
+          break;
+        }
       }
+      assert!(found, "invariant: outgoing sat is found in outputs");
+
+      sat_offsets.entry(recipient).or_default().insert(sat_offset);
     }
-    assert!(found, "invariant: outgoing sat is found in inputs");
 
-    let mut output_end = 0;
-    let mut found = false;
-    for tx_out in &transaction.output {
-      output_end += tx_out.value;
-      if output_end > sat_offset {
-        assert_eq!(
-          tx_out.script_pubkey, recipient,
-          "invariant: outgoing sat is sent to recipient"
+    for tx_in in &transaction.input {
+      for satpoint in self.inscriptions.keys() {
+        assert!(
+          satpoint.outpoint != tx_in.previous_output || outgoing_satpoints.contains(satpoint),
+          "invariant: inputs do not spend inscriptions other than outgoing: {satpoint}",
+        );
+      }
+
+      for satpoint in &self.protected_sats {
+        if satpoint.outpoint != tx_in.previous_output
+          || outgoing_satpoints.iter().any(|outgoing| {
+            outgoing.outpoint == satpoint.outpoint && outgoing.offset >= satpoint.offset
+          })
+        {
+          continue;
+        }
+
+        assert!(
+          self.wallet_recipient,
+          "invariant: inputs do not spend protected sats other than outgoing: {satpoint}",
+        );
+
+        let mut sat_offset = 0;
+        for tx_in in &transaction.input {
+          if tx_in.previous_output == satpoint.outpoint {
+            sat_offset += satpoint.offset;
+            break;
+          }
+          sat_offset += self.amounts[&tx_in.previous_output].to_sat();
+        }
+
+        let mut output_end = 0;
+        let output = transaction.output.iter().find(|tx_out| {
+          output_end += tx_out.value;
+          output_end > sat_offset
+        });
+
+        assert!(
+          output.is_some_and(|tx_out| self
+            .change_addresses
+            .iter()
+            .any(|change_address| change_address.script_pubkey() == tx_out.script_pubkey)),
+          "invariant: protected sats after outgoing are sent to change output: {satpoint}",
         );
-        found = true;
-        break;
       }
     }
-    assert!(found, "invariant: outgoing sat is found in outputs");
 
-    assert_eq!(
-      transaction
-        .output
-        .iter()
-        .filter(|tx_out| tx_out.script_pubkey == self.recipient.script_pubkey())
-        .count(),
-      1,
-      "invariant: recipient address appears exactly once in outputs",
-    );
+    for (recipient, sat_offsets) in &sat_offsets {
+      assert_eq!(
+        transaction
+          .output
+          .iter()
+          .filter(|tx_out| tx_out.script_pubkey == *recipient)
+          .count(),
+        sat_offsets.len(),
+        "invariant: recipient address appears exactly once in outputs for each outgoing sat",
+      );
+    }
 
     assert!(
       self
@@ -391,15 +883,29 @@ impl TransactionBuilder {
       "invariant: change addresses appear at most once in outputs",
     );
 
+    // Excess postage is only left in a recipient output if it is smaller than
+    // the dust limit of a change output plus the fee for adding that output.
+    let max_unstripped_postage = self.target_postage
+      + self
+        .change_addresses
+        .iter()
+        .map(|change_address| change_address.script_pubkey().dust_value())
+        .max()
+        .unwrap_or(Amount::ZERO)
+      + self
+        .fee_rate
+        .fee(self.estimate_vsize() + Self::ADDITIONAL_OUTPUT_VSIZE);
+
     let mut offset = 0;
     for output in &transaction.output {
-      if output.script_pubkey == self.recipient.script_pubkey() {
+      if let Some(sat_offsets) = sat_offsets.get(&output.script_pubkey) {
         assert!(
-          Amount::from_sat(output.value) < Self::MAX_POSTAGE,
+          Amount::from_sat(output.value) <= self.max_postage()
+            || Amount::from_sat(output.value) < max_unstripped_postage,
           "invariant: excess postage is stripped"
         );
-        assert_eq!(
-          offset, sat_offset,
+        assert!(
+          sat_offsets.contains(&offset),
           "invariant: sat is at first position in recipient output"
         );
       } else {
@@ -423,13 +929,12 @@ impl TransactionBuilder {
       fee -= Amount::from_sat(output.value);
     }
 
-    let fee_rate = fee.to_sat() as f64 / self.estimate_vsize() as f64;
-    let target_fee_rate = Self::TARGET_FEE_RATE.to_sat() as f64;
+    let target_fee = self.estimate_fee();
     assert!(
-      fee_rate == target_fee_rate,
-      "invariant: fee rate is equal to target fee rate: actual fee rate: {} target_fee rate: {}",
-      fee_rate,
-      target_fee_rate,
+      fee == target_fee,
+      "invariant: fee rate is equal to target fee rate: actual fee: {} target fee: {}",
+      fee,
+      target_fee,
     );
 
     for tx_out in &transaction.output {
@@ -442,53 +947,189 @@ impl TransactionBuilder {
     Ok(transaction)
   }
 
-  fn calculate_sat_offset(&self) -> u64 {
-    let mut sat_offset = 0;
-    for outpoint in &self.inputs {
-      if *outpoint == self.outgoing.outpoint {
-        return sat_offset + self.outgoing.offset;
-      } else {
-        sat_offset += self.amounts[outpoint].to_sat();
+  fn build_with_values(self, outputs: Vec<(Address, Amount)>) -> Result<Transaction> {
+    let transaction = Transaction {
+      version: 1,
+      lock_time: PackedLockTime::ZERO,
+      input: self
+        .inputs
+        .iter()
+        .map(|outpoint| TxIn {
+          previous_output: *outpoint,
+          script_sig: Script::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: self
+        .outputs
+        .iter()
+        .map(|(address, amount)| TxOut {
+          value: amount.to_sat(),
+          script_pubkey: address.script_pubkey(),
+        })
+        .collect(),
+    };
+
+    for tx_in in &transaction.input {
+      for satpoint in self.inscriptions.keys().chain(&self.protected_sats) {
+        assert!(
+          satpoint.outpoint != tx_in.previous_output,
+          "invariant: inputs are cardinal: {satpoint}",
+        );
       }
     }
 
-    panic!("Could not find outgoing sat in inputs");
-  }
+    for (i, (recipient, output_value)) in outputs.iter().enumerate() {
+      assert!(
+        transaction.output.get(i)
+          == Some(&TxOut {
+            value: output_value.to_sat(),
+            script_pubkey: recipient.script_pubkey(),
+          }),
+        "invariant: recipient receives output value in output {i}",
+      );
+    }
 
-  fn select_cardinal_utxo(&mut self, minimum_amount: Amount) -> Result<(OutPoint, Amount)> {
-    let mut found = None;
+    for output in &transaction.output[outputs.len()..] {
+      assert!(
+        self
+          .change_addresses
+          .iter()
+          .any(|change_address| change_address.script_pubkey() == output.script_pubkey),
+        "invariant: all outputs are either change or recipient: unrecognized output {}",
+        output.script_pubkey
+      );
+    }
 
-    let inscribed_utxos = self
-      .inscriptions
-      .keys()
-      .map(|satpoint| satpoint.outpoint)
-      .collect::<BTreeSet<OutPoint>>();
+    let mut fee = Amount::ZERO;
+    for input in &transaction.input {
+      fee += self.amounts[&input.previous_output];
+    }
+    for output in &transaction.output {
+      fee -= Amount::from_sat(output.value);
+    }
 
-    for utxo in &self.utxos {
-      if inscribed_utxos.contains(utxo) {
-        continue;
+    let target_fee = self.estimate_fee();
+    assert!(
+      fee >= target_fee,
+      "invariant: fee rate is at least target fee rate: actual fee: {} target fee: {}",
+      fee,
+      target_fee,
+    );
+
+    for tx_out in &transaction.output {
+      assert!(
+        Amount::from_sat(tx_out.value) >= tx_out.script_pubkey.dust_value(),
+        "invariant: all outputs are above dust limit",
+      );
+    }
+
+    Ok(transaction)
+  }
+
+  fn outgoing_satpoints(&self) -> BTreeSet<SatPoint> {
+    self
+      .outgoing
+      .iter()
+      .map(|(satpoint, _recipient)| *satpoint)
+      .collect()
+  }
+
+  fn calculate_sat_offset(&self, outgoing: SatPoint) -> u64 {
+    let mut sat_offset = 0;
+    for outpoint in &self.inputs {
+      if *outpoint == outgoing.outpoint {
+        return sat_offset + outgoing.offset;
+      } else {
+        sat_offset += self.amounts[outpoint].to_sat();
       }
+    }
 
-      let amount = self.amounts[utxo];
+    panic!("Could not find outgoing sat in inputs");
+  }
 
-      if amount >= minimum_amount {
-        found = Some((*utxo, amount));
-        break;
+  /// Find the index of the output containing the sat at `sat_offset`, along
+  /// with the offset of that output's first sat.
+  fn output_containing(&self, sat_offset: u64) -> (usize, u64) {
+    let mut output_start = 0;
+    for (index, (_address, amount)) in self.outputs.iter().enumerate() {
+      if output_start + amount.to_sat() > sat_offset {
+        return (index, output_start);
       }
+      output_start += amount.to_sat();
     }
 
-    let (utxo, amount) = found.ok_or(Error::NotEnoughCardinalUtxos)?;
+    panic!("Could not find outgoing sat in outputs");
+  }
+
+  fn input_position(&self, outgoing: SatPoint) -> usize {
+    self
+      .inputs
+      .iter()
+      .position(|outpoint| *outpoint == outgoing.outpoint)
+      .expect("Could not find outgoing sat in inputs")
+  }
+
+  fn select_cardinal_utxo(&mut self, minimum_amount: Amount) -> Result<(OutPoint, Amount)> {
+    let (utxo, amount) = self
+      .cardinal_utxos()
+      .find(|(_utxo, amount)| *amount >= minimum_amount)
+      .ok_or(Error::NotEnoughCardinalUtxos)?;
+
+    self.utxos.remove(&utxo);
+
+    Ok((utxo, amount))
+  }
+
+  fn select_largest_cardinal_utxo(&mut self) -> Result<(OutPoint, Amount)> {
+    let (utxo, amount) = self
+      .cardinal_utxos()
+      .max_by_key(|(_utxo, amount)| *amount)
+      .ok_or(Error::NotEnoughCardinalUtxos)?;
 
     self.utxos.remove(&utxo);
 
     Ok((utxo, amount))
   }
+
+  fn cardinal_utxos(&self) -> impl Iterator<Item = (OutPoint, Amount)> + '_ {
+    let unavailable_utxos = self
+      .inscriptions
+      .keys()
+      .chain(&self.protected_sats)
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    self
+      .utxos
+      .iter()
+      .filter(move |utxo| !unavailable_utxos.contains(utxo))
+      .map(|utxo| (*utxo, self.amounts[utxo]))
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use {super::Error, super::*};
 
+  fn build_transaction(
+    outgoing: SatPoint,
+    protected_sats: BTreeSet<SatPoint>,
+    utxos: Vec<(OutPoint, Amount)>,
+  ) -> Result<Transaction> {
+    TransactionBuilder::build_transaction(
+      outgoing,
+      BTreeMap::new(),
+      protected_sats,
+      utxos.into_iter().collect(),
+      recipient(),
+      vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
+    )
+  }
+
   #[test]
   fn select_sat() {
     let mut utxos = vec![
@@ -498,11 +1139,13 @@ mod tests {
     ];
 
     let tx_builder = TransactionBuilder::new(
-      satpoint(2, 0),
+      vec![(satpoint(2, 0), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       utxos.clone().into_iter().collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap();
@@ -532,11 +1175,14 @@ mod tests {
     let tx_builder = TransactionBuilder {
       amounts,
       utxos: BTreeSet::new(),
-      outgoing: satpoint(1, 0),
+      outgoing: vec![(satpoint(1, 0), recipient())],
       inscriptions: BTreeMap::new(),
-      recipient: recipient(),
+      protected_sats: BTreeSet::new(),
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      fee_rate: FeeRate::try_from(1.0).unwrap(),
+      target_postage: TransactionBuilder::TARGET_POSTAGE,
+      wallet_recipient: false,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -564,34 +1210,46 @@ mod tests {
   fn transactions_are_rbf() {
     let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
 
-    assert!(TransactionBuilder::build_transaction(
-      satpoint(1, 0),
-      BTreeMap::new(),
-      utxos.into_iter().collect(),
-      recipient(),
-      vec![change(0), change(1)],
-    )
-    .unwrap()
-    .is_explicitly_rbf())
+    assert!(build_transaction(satpoint(1, 0), BTreeSet::new(), utxos)
+      .unwrap()
+      .is_explicitly_rbf())
   }
 
   #[test]
   fn deduct_fee() {
     let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
 
+    pretty_assert_eq!(
+      build_transaction(satpoint(1, 0), BTreeSet::new(), utxos),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(4780, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn deduct_fee_at_fee_rate() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
+
     pretty_assert_eq!(
       TransactionBuilder::build_transaction(
         satpoint(1, 0),
         BTreeMap::new(),
+        BTreeSet::new(),
         utxos.into_iter().collect(),
         recipient(),
         vec![change(0), change(1)],
+        FeeRate::try_from(2.5).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
       ),
       Ok(Transaction {
         version: 1,
         lock_time: PackedLockTime::ZERO,
         input: vec![tx_in(outpoint(1))],
-        output: vec![tx_out(4780, recipient())],
+        output: vec![tx_out(4450, recipient())],
       })
     )
   }
@@ -602,11 +1260,13 @@ mod tests {
     let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
 
     TransactionBuilder::new(
-      satpoint(1, 4_950),
+      vec![(satpoint(1, 4_950), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       utxos.into_iter().collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap()
@@ -623,13 +1283,7 @@ mod tests {
     ];
 
     pretty_assert_eq!(
-      TransactionBuilder::build_transaction(
-        satpoint(1, 4_950),
-        BTreeMap::new(),
-        utxos.into_iter().collect(),
-        recipient(),
-        vec![change(0), change(1)],
-      ),
+      build_transaction(satpoint(1, 4_950), BTreeSet::new(), utxos),
       Ok(Transaction {
         version: 1,
         lock_time: PackedLockTime::ZERO,
@@ -644,13 +1298,7 @@ mod tests {
     let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
 
     pretty_assert_eq!(
-      TransactionBuilder::build_transaction(
-        satpoint(1, 4_950),
-        BTreeMap::new(),
-        utxos.into_iter().collect(),
-        recipient(),
-        vec![change(0), change(1)],
-      ),
+      build_transaction(satpoint(1, 4_950), BTreeSet::new(), utxos),
       Err(Error::NotEnoughCardinalUtxos),
     )
   }
@@ -663,13 +1311,7 @@ mod tests {
     ];
 
     pretty_assert_eq!(
-      TransactionBuilder::build_transaction(
-        satpoint(1, 4_950),
-        BTreeMap::new(),
-        utxos.into_iter().collect(),
-        recipient(),
-        vec![change(0), change(1)],
-      ),
+      build_transaction(satpoint(1, 4_950), BTreeSet::new(), utxos),
       Err(Error::NotEnoughCardinalUtxos),
     )
   }
@@ -682,13 +1324,7 @@ mod tests {
     ];
 
     pretty_assert_eq!(
-      TransactionBuilder::build_transaction(
-        satpoint(1, 4_950),
-        BTreeMap::new(),
-        utxos.into_iter().collect(),
-        recipient(),
-        vec![change(0), change(1)],
-      ),
+      build_transaction(satpoint(1, 4_950), BTreeSet::new(), utxos),
       Ok(Transaction {
         version: 1,
         lock_time: PackedLockTime::ZERO,
@@ -706,13 +1342,15 @@ mod tests {
   #[should_panic(expected = "invariant: outgoing sat is contained in utxos")]
   fn invariant_satpoint_outpoint_is_contained_in_utxos() {
     TransactionBuilder::new(
-      satpoint(2, 0),
+      vec![(satpoint(2, 0), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       vec![(outpoint(1), Amount::from_sat(4))]
         .into_iter()
         .collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .build()
     .unwrap();
@@ -722,13 +1360,15 @@ mod tests {
   #[should_panic(expected = "invariant: outgoing sat is contained in utxos")]
   fn invariant_satpoint_offset_is_contained_in_utxos() {
     TransactionBuilder::new(
-      satpoint(1, 4),
+      vec![(satpoint(1, 4), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       vec![(outpoint(1), Amount::from_sat(4))]
         .into_iter()
         .collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .build()
     .unwrap();
@@ -738,13 +1378,15 @@ mod tests {
   #[should_panic(expected = "invariant: inputs spend outgoing sat")]
   fn invariant_inputs_spend_sat() {
     TransactionBuilder::new(
-      satpoint(1, 2),
+      vec![(satpoint(1, 2), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       vec![(outpoint(1), Amount::from_sat(5))]
         .into_iter()
         .collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .build()
     .unwrap();
@@ -754,13 +1396,15 @@ mod tests {
   #[should_panic(expected = "invariant: outgoing sat is sent to recipient")]
   fn invariant_sat_is_sent_to_recipient() {
     let mut builder = TransactionBuilder::new(
-      satpoint(1, 2),
+      vec![(satpoint(1, 2), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       vec![(outpoint(1), Amount::from_sat(5))]
         .into_iter()
         .collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap();
@@ -776,13 +1420,15 @@ mod tests {
   #[should_panic(expected = "invariant: outgoing sat is found in outputs")]
   fn invariant_sat_is_found_in_outputs() {
     let mut builder = TransactionBuilder::new(
-      satpoint(1, 2),
+      vec![(satpoint(1, 2), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       vec![(outpoint(1), Amount::from_sat(5))]
         .into_iter()
         .collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap();
@@ -792,41 +1438,138 @@ mod tests {
     builder.build().unwrap();
   }
 
+  #[test]
+  #[should_panic(expected = "invariant: inputs do not spend inscriptions other than outgoing")]
+  fn invariant_inputs_do_not_spend_other_inscriptions() {
+    let mut builder = TransactionBuilder::new(
+      vec![(satpoint(1, 0), recipient())],
+      [(satpoint(1, 0), txid(1)), (satpoint(2, 0), txid(2))]
+        .into_iter()
+        .collect(),
+      BTreeSet::new(),
+      vec![
+        (outpoint(1), Amount::from_sat(10_000)),
+        (outpoint(2), Amount::from_sat(10_000)),
+      ]
+      .into_iter()
+      .collect(),
+      vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
+    )
+    .select_outgoing()
+    .unwrap();
+
+    builder.inputs.push(outpoint(2));
+    builder.outputs[0].1 += Amount::from_sat(10_000);
+
+    builder.build().unwrap();
+  }
+
   #[test]
   fn excess_postage_is_stripped() {
     let utxos = vec![(outpoint(1), Amount::from_sat(1_000_000))];
 
+    pretty_assert_eq!(
+      build_transaction(satpoint(1, 0), BTreeSet::new(), utxos),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(TransactionBuilder::TARGET_POSTAGE.to_sat(), recipient()),
+          tx_out(989_749, change(1))
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn excess_postage_is_stripped_to_target_postage() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(1_000_000))];
+
     pretty_assert_eq!(
       TransactionBuilder::build_transaction(
         satpoint(1, 0),
         BTreeMap::new(),
+        BTreeSet::new(),
         utxos.into_iter().collect(),
         recipient(),
-        vec![change(0), change(1)]
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Amount::from_sat(546),
       ),
       Ok(Transaction {
         version: 1,
         lock_time: PackedLockTime::ZERO,
         input: vec![tx_in(outpoint(1))],
         output: vec![
-          tx_out(TransactionBuilder::TARGET_POSTAGE.to_sat(), recipient()),
-          tx_out(989_749, change(1))
+          tx_out(546, recipient()),
+          tx_out(1_000_000 - 546 - 251, change(1))
         ],
       })
     )
   }
 
+  #[test]
+  fn excess_postage_too_small_to_pay_for_own_output_stays_in_recipient_output() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(23_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(50.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(23_000 - 11_000, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn target_postage_below_dust_limit() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(1_000_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Amount::from_sat(100),
+      ),
+      Err(Error::Dust {
+        output_value: Amount::from_sat(100),
+        dust_value: Amount::from_sat(294),
+      })
+    )
+  }
+
   #[test]
   #[should_panic(expected = "invariant: excess postage is stripped")]
   fn invariant_excess_postage_is_stripped() {
     let utxos = vec![(outpoint(1), Amount::from_sat(1_000_000))];
 
     TransactionBuilder::new(
-      satpoint(1, 0),
+      vec![(satpoint(1, 0), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       utxos.into_iter().collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap()
@@ -839,13 +1582,7 @@ mod tests {
     let utxos = vec![(outpoint(1), Amount::from_sat(10_000))];
 
     pretty_assert_eq!(
-      TransactionBuilder::build_transaction(
-        satpoint(1, 3_333),
-        BTreeMap::new(),
-        utxos.into_iter().collect(),
-        recipient(),
-        vec![change(0), change(1)]
-      ),
+      build_transaction(satpoint(1, 3_333), BTreeSet::new(), utxos),
       Ok(Transaction {
         version: 1,
         lock_time: PackedLockTime::ZERO,
@@ -863,13 +1600,7 @@ mod tests {
     ];
 
     pretty_assert_eq!(
-      TransactionBuilder::build_transaction(
-        satpoint(1, 1),
-        BTreeMap::new(),
-        utxos.into_iter().collect(),
-        recipient(),
-        vec![change(0), change(1)]
-      ),
+      build_transaction(satpoint(1, 1), BTreeSet::new(), utxos),
       Ok(Transaction {
         version: 1,
         lock_time: PackedLockTime::ZERO,
@@ -885,11 +1616,13 @@ mod tests {
     let utxos = vec![(outpoint(1), Amount::from_sat(10_000))];
 
     let mut builder = TransactionBuilder::new(
-      satpoint(1, 3_333),
+      vec![(satpoint(1, 3_333), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       utxos.into_iter().collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap()
@@ -910,11 +1643,13 @@ mod tests {
     let utxos = vec![(outpoint(1), Amount::from_sat(10_000))];
 
     TransactionBuilder::new(
-      satpoint(1, 1),
+      vec![(satpoint(1, 1), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       utxos.into_iter().collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap()
@@ -933,11 +1668,13 @@ mod tests {
     let utxos = vec![(outpoint(1), Amount::from_sat(10_000))];
 
     TransactionBuilder::new(
-      satpoint(1, 3_333),
+      vec![(satpoint(1, 3_333), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       utxos.into_iter().collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap()
@@ -953,11 +1690,13 @@ mod tests {
     let utxos = vec![(outpoint(1), Amount::from_sat(10_000))];
 
     TransactionBuilder::new(
-      satpoint(1, 0),
+      vec![(satpoint(1, 0), recipient())],
       BTreeMap::new(),
+      BTreeSet::new(),
       utxos.into_iter().collect(),
-      recipient(),
       vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
     )
     .select_outgoing()
     .unwrap()
@@ -977,11 +1716,14 @@ mod tests {
     TransactionBuilder {
       amounts,
       utxos: BTreeSet::new(),
-      outgoing: satpoint(1, 0),
+      outgoing: vec![(satpoint(1, 0), recipient())],
       inscriptions: BTreeMap::new(),
-      recipient: recipient(),
+      protected_sats: BTreeSet::new(),
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      fee_rate: FeeRate::try_from(1.0).unwrap(),
+      target_postage: TransactionBuilder::TARGET_POSTAGE,
+      wallet_recipient: false,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1004,11 +1746,14 @@ mod tests {
     TransactionBuilder {
       amounts,
       utxos: BTreeSet::new(),
-      outgoing: satpoint(1, 0),
+      outgoing: vec![(satpoint(1, 0), recipient())],
       inscriptions: BTreeMap::new(),
-      recipient: recipient(),
+      protected_sats: BTreeSet::new(),
       unused_change_addresses: vec![change(0), change(1)],
       change_addresses: vec![change(0), change(1)].into_iter().collect(),
+      fee_rate: FeeRate::try_from(1.0).unwrap(),
+      target_postage: TransactionBuilder::TARGET_POSTAGE,
+      wallet_recipient: false,
       inputs: vec![outpoint(1), outpoint(2), outpoint(3)],
       outputs: vec![
         (recipient(), Amount::from_sat(5_000)),
@@ -1036,9 +1781,12 @@ mod tests {
             .parse()
             .unwrap()
         )]),
+        BTreeSet::new(),
         utxos.into_iter().collect(),
         recipient(),
         vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
       ),
       Err(Error::NotEnoughCardinalUtxos)
     )
@@ -1057,9 +1805,12 @@ mod tests {
             .parse()
             .unwrap()
         )]),
+        BTreeSet::new(),
         utxos.into_iter().collect(),
         recipient(),
         vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
       ),
       Err(Error::UtxoContainsAdditionalInscription {
         outgoing_satpoint: satpoint(1, 0),
@@ -1070,4 +1821,512 @@ mod tests {
       })
     )
   }
+
+  #[test]
+  fn do_not_select_protected_sats_for_cardinal_utxos() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(5_000)),
+      (outpoint(2), Amount::from_sat(5_000)),
+      (outpoint(3), Amount::from_sat(5_000)),
+    ];
+
+    pretty_assert_eq!(
+      build_transaction(satpoint(1, 4_950), BTreeSet::from([satpoint(2, 0)]), utxos),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(3))],
+        output: vec![tx_out(4_950, change(1)), tx_out(4_620, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn protected_sats_are_not_spent_as_postage() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(5_000)),
+      (outpoint(2), Amount::from_sat(5_000)),
+    ];
+
+    pretty_assert_eq!(
+      build_transaction(
+        satpoint(1, 4_950),
+        BTreeSet::from([satpoint(2, 1_000)]),
+        utxos
+      ),
+      Err(Error::NotEnoughCardinalUtxos)
+    )
+  }
+
+  #[test]
+  fn protected_sat_may_be_outgoing() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(5_000))];
+
+    pretty_assert_eq!(
+      build_transaction(satpoint(1, 0), BTreeSet::from([satpoint(1, 0)]), utxos),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(4_780, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn do_not_send_protected_sat_along_with_outgoing() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(1_000))];
+
+    pretty_assert_eq!(
+      build_transaction(satpoint(1, 0), BTreeSet::from([satpoint(1, 500)]), utxos),
+      Err(Error::UtxoContainsProtectedSat {
+        outgoing_satpoint: satpoint(1, 0),
+        protected_satpoint: satpoint(1, 500),
+      })
+    )
+  }
+
+  #[test]
+  fn protected_sats_after_outgoing_sent_to_wallet_are_stripped_into_change() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(20_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_to_wallet(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        BTreeSet::from([satpoint(1, 5_000)]),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(5_000, recipient()), tx_out(14_749, change(1))],
+      })
+    )
+  }
+
+  #[test]
+  fn protected_sats_after_outgoing_sent_to_wallet_are_not_spent_as_fee() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(20_000)),
+      (outpoint(2), Amount::from_sat(10_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_to_wallet(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        BTreeSet::from([satpoint(1, 19_999)]),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![tx_out(19_999, recipient()), tx_out(9_571, change(1))],
+      })
+    )
+  }
+
+  #[test]
+  fn protected_sat_too_close_to_outgoing_sent_to_wallet_is_an_error() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(20_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_to_wallet(
+        satpoint(1, 0),
+        BTreeMap::new(),
+        BTreeSet::from([satpoint(1, 100)]),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Err(Error::UtxoContainsProtectedSat {
+        outgoing_satpoint: satpoint(1, 0),
+        protected_satpoint: satpoint(1, 100),
+      })
+    )
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: protected sats after outgoing are sent to change output")]
+  fn invariant_protected_sats_after_outgoing_sent_to_wallet_are_sent_to_change() {
+    let builder = TransactionBuilder {
+      wallet_recipient: true,
+      ..TransactionBuilder::new(
+        vec![(satpoint(1, 0), recipient())],
+        BTreeMap::new(),
+        BTreeSet::from([satpoint(1, 5_000)]),
+        vec![(outpoint(1), Amount::from_sat(10_000))]
+          .into_iter()
+          .collect(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      )
+    }
+    .select_outgoing()
+    .unwrap();
+
+    builder.build().unwrap();
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: inputs do not spend protected sats other than outgoing")]
+  fn invariant_inputs_do_not_spend_protected_sats() {
+    let mut builder = TransactionBuilder::new(
+      vec![(satpoint(1, 0), recipient())],
+      BTreeMap::new(),
+      BTreeSet::from([satpoint(2, 0)]),
+      vec![
+        (outpoint(1), Amount::from_sat(10_000)),
+        (outpoint(2), Amount::from_sat(10_000)),
+      ]
+      .into_iter()
+      .collect(),
+      vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
+    )
+    .select_outgoing()
+    .unwrap();
+
+    builder.inputs.push(outpoint(2));
+    builder.outputs[0].1 += Amount::from_sat(10_000);
+
+    builder.build().unwrap();
+  }
+
+  fn other_recipient() -> Address {
+    "tb1q5r9tdfu4tn4xrul5u68fqjllr2350t9gqh39wz"
+      .parse()
+      .unwrap()
+  }
+
+  #[test]
+  fn batch_sends_each_outgoing_sat_to_its_own_output() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(10_000)),
+      (outpoint(2), Amount::from_sat(10_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_batch_transaction(
+        vec![
+          (satpoint(1, 0), recipient()),
+          (satpoint(2, 0), other_recipient())
+        ],
+        BTreeMap::from([(satpoint(1, 0), txid(1)), (satpoint(2, 0), txid(2))]),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        vec![change(0), change(1), change(2), change(3)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![
+          tx_out(10_000, recipient()),
+          tx_out(9_570, other_recipient())
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn batch_aligns_pads_and_strips_each_segment() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(5_000)),
+      (outpoint(2), Amount::from_sat(100_000)),
+      (outpoint(3), Amount::from_sat(5_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_batch_transaction(
+        vec![
+          (satpoint(1, 4_950), recipient()),
+          (satpoint(2, 0), other_recipient())
+        ],
+        BTreeMap::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        vec![change(0), change(1), change(2), change(3)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(3)), tx_in(outpoint(2))],
+        output: vec![
+          tx_out(4_950, change(3)),
+          tx_out(5_050, recipient()),
+          tx_out(10_000, other_recipient()),
+          tx_out(89_329, change(2)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn batch_may_send_several_sats_to_same_recipient() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(10_000)),
+      (outpoint(2), Amount::from_sat(10_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_batch_transaction(
+        vec![(satpoint(1, 0), recipient()), (satpoint(2, 0), recipient())],
+        BTreeMap::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        vec![change(0), change(1), change(2), change(3)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1)), tx_in(outpoint(2))],
+        output: vec![tx_out(10_000, recipient()), tx_out(9_570, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn batch_outgoing_sats_must_be_in_distinct_utxos() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(100_000))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_batch_transaction(
+        vec![
+          (satpoint(1, 0), recipient()),
+          (satpoint(1, 50_000), other_recipient())
+        ],
+        BTreeMap::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        vec![change(0), change(1), change(2), change(3)],
+        FeeRate::try_from(1.0).unwrap(),
+        TransactionBuilder::TARGET_POSTAGE,
+      ),
+      Err(Error::UtxoContainsAdditionalOutgoing {
+        outgoing_satpoint: satpoint(1, 0),
+        additional_satpoint: satpoint(1, 50_000),
+      })
+    )
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: outgoing sat is sent to recipient")]
+  fn invariant_batch_sats_are_sent_to_their_recipients() {
+    let mut builder = TransactionBuilder::new(
+      vec![
+        (satpoint(1, 0), recipient()),
+        (satpoint(2, 0), other_recipient()),
+      ],
+      BTreeMap::new(),
+      BTreeSet::new(),
+      vec![
+        (outpoint(1), Amount::from_sat(10_000)),
+        (outpoint(2), Amount::from_sat(10_000)),
+      ]
+      .into_iter()
+      .collect(),
+      vec![change(0), change(1), change(2), change(3)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
+    )
+    .select_outgoing()
+    .unwrap()
+    .align_outgoing()
+    .add_postage()
+    .unwrap()
+    .strip_excess_postage()
+    .deduct_fee();
+
+    builder.outputs.swap(0, 1);
+
+    builder.build().unwrap();
+  }
+
+  #[test]
+  fn value_is_sent_from_cardinal_utxos() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(50_000)),
+      (outpoint(2), Amount::from_sat(50_000)),
+      (outpoint(3), Amount::from_sat(30_000)),
+      (outpoint(4), Amount::from_sat(20_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_value(
+        BTreeMap::from([(satpoint(1, 0), txid(1))]),
+        BTreeSet::from([satpoint(2, 100)]),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Amount::from_sat(40_000),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(3)), tx_in(outpoint(4))],
+        output: vec![tx_out(40_000, recipient()), tx_out(9_570, change(1))],
+      })
+    )
+  }
+
+  #[test]
+  fn values_are_sent_to_outputs_in_order() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(50_000)),
+      (outpoint(2), Amount::from_sat(20_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_values(
+        BTreeMap::from([(satpoint(1, 0), txid(1))]),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        vec![
+          (recipient(), Amount::from_sat(10_000)),
+          (other_recipient(), Amount::from_sat(5_000)),
+        ],
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(2))],
+        output: vec![
+          tx_out(10_000, recipient()),
+          tx_out(5_000, other_recipient()),
+          tx_out(4_718, change(1)),
+        ],
+      })
+    )
+  }
+
+  #[test]
+  fn value_change_too_small_for_own_output_is_left_to_fee() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(10_300))];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_value(
+        BTreeMap::new(),
+        BTreeSet::new(),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Amount::from_sat(10_000),
+      ),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![tx_out(10_000, recipient())],
+      })
+    )
+  }
+
+  #[test]
+  fn value_is_not_sent_from_inscribed_or_protected_utxos() {
+    let utxos = vec![
+      (outpoint(1), Amount::from_sat(50_000)),
+      (outpoint(2), Amount::from_sat(50_000)),
+    ];
+
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_value(
+        BTreeMap::from([(satpoint(1, 0), txid(1))]),
+        BTreeSet::from([satpoint(2, 100)]),
+        utxos.into_iter().collect(),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Amount::from_sat(10_000),
+      ),
+      Err(Error::NotEnoughCardinalUtxos)
+    )
+  }
+
+  #[test]
+  fn value_below_dust_limit() {
+    pretty_assert_eq!(
+      TransactionBuilder::build_transaction_with_value(
+        BTreeMap::new(),
+        BTreeSet::new(),
+        BTreeMap::from([(outpoint(1), Amount::from_sat(10_000))]),
+        recipient(),
+        vec![change(0), change(1)],
+        FeeRate::try_from(1.0).unwrap(),
+        Amount::from_sat(1),
+      ),
+      Err(Error::Dust {
+        output_value: Amount::from_sat(1),
+        dust_value: Amount::from_sat(294),
+      })
+    )
+  }
+
+  #[test]
+  #[should_panic(expected = "invariant: inputs are cardinal")]
+  fn invariant_value_inputs_are_cardinal() {
+    let mut builder = TransactionBuilder::new(
+      Vec::new(),
+      BTreeMap::from([(satpoint(1, 0), txid(1))]),
+      BTreeSet::new(),
+      BTreeMap::from([(outpoint(1), Amount::from_sat(50_000))]),
+      vec![change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      TransactionBuilder::TARGET_POSTAGE,
+    );
+
+    builder.inputs.push(outpoint(1));
+    builder
+      .outputs
+      .push((recipient(), Amount::from_sat(40_000)));
+
+    builder
+      .add_value_change(Amount::from_sat(40_000))
+      .build_with_values(vec![(recipient(), Amount::from_sat(40_000))])
+      .unwrap();
+  }
+
+  #[test]
+  fn protected_sat_before_outgoing_is_returned_as_change() {
+    let utxos = vec![(outpoint(1), Amount::from_sat(100_000))];
+
+    pretty_assert_eq!(
+      build_transaction(satpoint(1, 50_000), BTreeSet::from([satpoint(1, 0)]), utxos),
+      Ok(Transaction {
+        version: 1,
+        lock_time: PackedLockTime::ZERO,
+        input: vec![tx_in(outpoint(1))],
+        output: vec![
+          tx_out(50_000, change(1)),
+          tx_out(10_000, recipient()),
+          tx_out(39_718, change(0))
+        ],
+      })
+    )
+  }
 }
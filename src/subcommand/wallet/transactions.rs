@@ -6,7 +6,7 @@ pub(crate) struct Transactions {}
 impl Transactions {
   pub(crate) fn run(self, options: Options) -> Result {
     let txs = options
-      .bitcoin_rpc_client()?
+      .bitcoin_rpc_client_for_wallet()?
       .list_transactions(None, None, None, None)?
       .iter()
       .map(|tx| (tx.info.txid, tx.info.confirmations))
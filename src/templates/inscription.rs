@@ -2,9 +2,13 @@ use super::*;
 
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionHtml {
+  pub(crate) copies: usize,
+  pub(crate) delegate: Option<InscriptionId>,
   pub(crate) genesis_height: u64,
   pub(crate) inscription_id: InscriptionId,
   pub(crate) inscription: Inscription,
+  pub(crate) number: i64,
+  pub(crate) original: Option<InscriptionId>,
   pub(crate) satpoint: SatPoint,
 }
 
@@ -26,12 +30,16 @@ mod tests {
   fn html() {
     pretty_assert_eq!(
       InscriptionHtml {
+        copies: 1,
+        delegate: None,
         genesis_height: 0,
         inscription_id: InscriptionId::from_str(
           "ec90757eb3b164aa43fc548faa2fa0c52025494f2c15d5ddf11260b4034ac6dc"
         )
         .unwrap(),
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        number: 1,
+        original: None,
         satpoint: satpoint(1, 0),
       }
       .to_string(),
@@ -41,6 +49,8 @@ mod tests {
         <pre class=inscription>HELLOWORLD</pre>
         </a>
         <dl>
+          <dt>number</dt>
+          <dd>1</dd>
           <dt>content size</dt>
           <dd>10 bytes</dd>
           <dt>content type</dt>
@@ -56,4 +66,109 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn delegate() {
+    assert_regex_match!(
+      InscriptionHtml {
+        copies: 1,
+        delegate: Some(txid(2)),
+        genesis_height: 0,
+        inscription_id: txid(1),
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        number: 1,
+        original: None,
+        satpoint: satpoint(1, 0),
+      }
+      .to_string(),
+      "
+        .*
+          <dt>content type</dt>
+          <dd>text/plain;charset=utf-8</dd>
+          <dt>delegate</dt>
+          <dd><a class=monospace href=/inscription/2{64}>2{64}</a></dd>
+          <dt>genesis height</dt>
+        .*
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn first_of_copies() {
+    assert_regex_match!(
+      InscriptionHtml {
+        copies: 3,
+        delegate: None,
+        genesis_height: 0,
+        inscription_id: txid(1),
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        number: 1,
+        original: None,
+        satpoint: satpoint(1, 0),
+      }
+      .to_string(),
+      "
+        .*
+          <dd>text/plain;charset=utf-8</dd>
+          <dt>copies</dt>
+          <dd>first of 3 copies</dd>
+          <dt>genesis height</dt>
+        .*
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn copy_of_original() {
+    assert_regex_match!(
+      InscriptionHtml {
+        copies: 2,
+        delegate: None,
+        genesis_height: 0,
+        inscription_id: txid(1),
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        number: 1,
+        original: Some(txid(2)),
+        satpoint: satpoint(1, 0),
+      }
+      .to_string(),
+      "
+        .*
+          <dt>copies</dt>
+          <dd>copy of <a class=monospace href=/inscription/2{64}>2{64}</a></dd>
+        .*
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn cursed() {
+    assert_regex_match!(
+      InscriptionHtml {
+        copies: 1,
+        delegate: None,
+        genesis_height: 0,
+        inscription_id: txid(1),
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        number: -1,
+        original: None,
+        satpoint: satpoint(1, 0),
+      }
+      .to_string(),
+      "
+        .*
+        <dl>
+          <dt>number</dt>
+          <dd>-1</dd>
+          <dt>cursed</dt>
+          <dd>true</dd>
+          .*
+        </dl>
+      "
+      .unindent()
+    );
+  }
 }
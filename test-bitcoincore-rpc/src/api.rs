@@ -12,6 +12,16 @@ pub trait Api {
   #[rpc(name = "getbalances")]
   fn get_balances(&self) -> Result<GetBalancesResult, jsonrpc_core::Error>;
 
+  #[rpc(name = "estimatesmartfee")]
+  fn estimate_smart_fee(
+    &self,
+    conf_target: u16,
+    estimate_mode: Option<EstimateMode>,
+  ) -> Result<EstimateSmartFeeResult, jsonrpc_core::Error>;
+
+  #[rpc(name = "getaddressinfo")]
+  fn get_address_info(&self, address: bitcoin::Address) -> Result<Value, jsonrpc_core::Error>;
+
   #[rpc(name = "getblockhash")]
   fn get_block_hash(&self, height: usize) -> Result<BlockHash, jsonrpc_core::Error>;
 
@@ -76,6 +86,14 @@ pub trait Api {
     blockhash: Option<BlockHash>,
   ) -> Result<Value, jsonrpc_core::Error>;
 
+  #[rpc(name = "gettxout")]
+  fn get_tx_out(
+    &self,
+    txid: Txid,
+    vout: u32,
+    include_mempool: Option<bool>,
+  ) -> Result<Option<GetTxOutResult>, jsonrpc_core::Error>;
+
   #[rpc(name = "listunspent")]
   fn list_unspent(
     &self,
@@ -116,4 +134,20 @@ pub trait Api {
     skip: Option<usize>,
     include_watchonly: Option<bool>,
   ) -> Result<Vec<ListTransactionResult>, jsonrpc_core::Error>;
+
+  #[rpc(name = "walletprocesspsbt")]
+  fn wallet_process_psbt(
+    &self,
+    psbt: String,
+    sign: Option<bool>,
+    sighash_type: Option<String>,
+    bip32derivs: Option<bool>,
+  ) -> Result<WalletProcessPsbtResult, jsonrpc_core::Error>;
+
+  #[rpc(name = "finalizepsbt")]
+  fn finalize_psbt(
+    &self,
+    psbt: String,
+    extract: Option<bool>,
+  ) -> Result<Value, jsonrpc_core::Error>;
 }
@@ -6,19 +6,23 @@ use {
     consensus::encode::{deserialize, serialize},
     hash_types::BlockHash,
     hashes::Hash,
+    psbt::Psbt,
     util::amount::SignedAmount,
     Amount, Block, BlockHeader, Network, OutPoint, PackedLockTime, Script, Sequence, Transaction,
     TxIn, TxMerkleNode, TxOut, Txid, Witness, Wtxid,
   },
   bitcoincore_rpc::json::{
-    Bip125Replaceable, CreateRawTransactionInput, GetBalancesResult, GetBalancesResultEntry,
-    GetBlockHeaderResult, GetBlockchainInfoResult, GetDescriptorInfoResult, GetNetworkInfoResult,
-    GetRawTransactionResult, GetTransactionResult, GetTransactionResultDetail,
-    GetTransactionResultDetailCategory, GetWalletInfoResult, ListTransactionResult,
-    ListUnspentResultEntry, LoadWalletResult, SignRawTransactionResult, WalletTxInfo,
+    Bip125Replaceable, CreateRawTransactionInput, EstimateMode, EstimateSmartFeeResult,
+    GetBalancesResult, GetBalancesResultEntry, GetBlockHeaderResult, GetBlockchainInfoResult,
+    GetDescriptorInfoResult, GetNetworkInfoResult, GetRawTransactionResult,
+    GetRawTransactionResultVoutScriptPubKey, GetTransactionResult, GetTransactionResultDetail,
+    GetTransactionResultDetailCategory, GetTxOutResult, GetWalletInfoResult, ListTransactionResult,
+    ListUnspentResultEntry, LoadWalletResult, SignRawTransactionResult, WalletProcessPsbtResult,
+    WalletTxInfo,
   },
   jsonrpc_core::{IoHandler, Value},
-  jsonrpc_http_server::{CloseHandle, ServerBuilder},
+  jsonrpc_http_server::{hyper, CloseHandle, ServerBuilder},
+  percent_encoding::percent_decode_str,
   server::Server,
   state::State,
   std::{
@@ -39,7 +43,17 @@ pub fn spawn_with(network: Network, wallet_name: &str) -> Handle {
   let mut io = IoHandler::default();
   io.extend_with(server.to_delegate());
 
+  let request_state = state.clone();
+
   let rpc_server = ServerBuilder::new(io)
+    .request_middleware(move |request: hyper::Request<hyper::Body>| {
+      request_state.lock().unwrap().request_wallet = request
+        .uri()
+        .path()
+        .strip_prefix("/wallet/")
+        .map(|wallet| percent_decode_str(wallet).decode_utf8_lossy().into_owned());
+      request.into()
+    })
     .threads(1)
     .start_http(&"127.0.0.1:0".parse().unwrap())
     .unwrap();
@@ -77,6 +91,7 @@ pub struct TransactionTemplate<'a> {
   pub input_slots: &'a [(usize, usize, usize)],
   pub output_count: usize,
   pub fee: u64,
+  pub witness: Witness,
 }
 
 pub struct Handle {
@@ -98,6 +113,10 @@ impl Handle {
     self.state().wallets.clone()
   }
 
+  pub fn descriptors(&self) -> Vec<Value> {
+    self.state().descriptors.clone()
+  }
+
   pub fn mine_blocks(&self, num: u64) -> Vec<Block> {
     let mut bitcoin_rpc_data = self.state.lock().unwrap();
     (0..num)
@@ -125,6 +144,20 @@ impl Handle {
     state.blocks[&state.hashes[bi]].txdata[ti].clone()
   }
 
+  pub fn set_fee_estimate(&self, fee_rate: Option<Amount>) {
+    self.state().fee_estimate = fee_rate;
+  }
+
+  /// Accept only `limit` more transactions with `sendrawtransaction`, and
+  /// reject any after that, or accept all transactions if `limit` is `None`.
+  pub fn set_broadcast_limit(&self, limit: Option<usize>) {
+    self.state().broadcast_limit = limit;
+  }
+
+  pub fn tx_by_id(&self, txid: Txid) -> Transaction {
+    self.state().find_transaction(txid).unwrap().0.clone()
+  }
+
   pub fn mempool(&self) -> Vec<Transaction> {
     self.state.lock().unwrap().mempool().to_vec()
   }
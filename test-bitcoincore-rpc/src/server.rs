@@ -1,8 +1,10 @@
 use {
   super::*,
   bitcoin::{
-    secp256k1::{rand, KeyPair, Secp256k1, XOnlyPublicKey},
-    Address, Witness,
+    schnorr::{SchnorrSig, TapTweak},
+    secp256k1::{rand, KeyPair, Message, Secp256k1, XOnlyPublicKey},
+    util::sighash::{Prevouts, SighashCache},
+    Address, SchnorrSighashType, Witness,
   },
   serde_json::json,
 };
@@ -25,10 +27,29 @@ impl Server {
   fn not_found() -> jsonrpc_core::Error {
     jsonrpc_core::Error::new(jsonrpc_core::types::error::ErrorCode::ServerError(-8))
   }
+
+  /// Fail wallet RPCs routed to a wallet that hasn't been created, once any
+  /// wallet has been created.
+  fn check_wallet(&self) -> Result<(), jsonrpc_core::Error> {
+    let state = self.state();
+
+    match &state.request_wallet {
+      Some(wallet) if !state.wallets.is_empty() && !state.wallets.contains(wallet) => {
+        Err(jsonrpc_core::Error {
+          code: jsonrpc_core::types::error::ErrorCode::ServerError(-18),
+          message: format!("Requested wallet does not exist or is not loaded: {wallet}"),
+          data: None,
+        })
+      }
+      _ => Ok(()),
+    }
+  }
 }
 
 impl Api for Server {
   fn get_balances(&self) -> Result<GetBalancesResult, jsonrpc_core::Error> {
+    self.check_wallet()?;
+
     Ok(GetBalancesResult {
       mine: GetBalancesResultEntry {
         immature: Amount::from_sat(0),
@@ -89,6 +110,34 @@ impl Api for Server {
     })
   }
 
+  fn estimate_smart_fee(
+    &self,
+    _conf_target: u16,
+    _estimate_mode: Option<EstimateMode>,
+  ) -> Result<EstimateSmartFeeResult, jsonrpc_core::Error> {
+    let fee_rate = self.state().fee_estimate;
+
+    Ok(EstimateSmartFeeResult {
+      fee_rate,
+      errors: match fee_rate {
+        Some(_) => None,
+        None => Some(vec!["Insufficient data or no feerate found".into()]),
+      },
+      blocks: 0,
+    })
+  }
+
+  fn get_address_info(&self, address: Address) -> Result<Value, jsonrpc_core::Error> {
+    let script_pubkey = address.script_pubkey();
+
+    Ok(json!({
+      "address": address,
+      "scriptPubKey": hex::encode(script_pubkey.as_bytes()),
+      "ismine": self.state().addresses.contains_key(&script_pubkey),
+      "labels": [],
+    }))
+  }
+
   fn get_block_hash(&self, height: usize) -> Result<BlockHash, jsonrpc_core::Error> {
     match self.state().hashes.get(height) {
       Some(block_hash) => Ok(*block_hash),
@@ -165,6 +214,8 @@ impl Api for Server {
   }
 
   fn get_wallet_info(&self) -> Result<GetWalletInfoResult, jsonrpc_core::Error> {
+    self.check_wallet()?;
+
     Ok(GetWalletInfoResult {
       avoid_reuse: None,
       balance: Amount::from_sat(0),
@@ -254,7 +305,64 @@ impl Api for Server {
 
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error> {
     let tx: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
-    self.state.lock().unwrap().mempool.push(tx.clone());
+
+    let mut state = self.state.lock().unwrap();
+
+    match state.broadcast_limit {
+      Some(0) => {
+        return Err(jsonrpc_core::Error::new(
+          jsonrpc_core::types::error::ErrorCode::ServerError(-26),
+        ))
+      }
+      Some(limit) => state.broadcast_limit = Some(limit - 1),
+      None => {}
+    }
+
+    if state.exceeds_descendant_limit(&tx) {
+      return Err(jsonrpc_core::Error {
+        code: jsonrpc_core::types::error::ErrorCode::ServerError(-26),
+        message: "too-long-mempool-chain".into(),
+        data: None,
+      });
+    }
+
+    // Evict transactions replaced by `tx`, along with their descendants
+    let mut evicted = state
+      .mempool
+      .iter()
+      .filter(|mempool_tx| {
+        mempool_tx.input.iter().any(|mempool_tx_in| {
+          tx.input
+            .iter()
+            .any(|tx_in| tx_in.previous_output == mempool_tx_in.previous_output)
+        })
+      })
+      .map(|mempool_tx| mempool_tx.txid())
+      .collect::<BTreeSet<Txid>>();
+
+    loop {
+      let count = evicted.len();
+
+      for mempool_tx in &state.mempool {
+        if mempool_tx
+          .input
+          .iter()
+          .any(|tx_in| evicted.contains(&tx_in.previous_output.txid))
+        {
+          evicted.insert(mempool_tx.txid());
+        }
+      }
+
+      if evicted.len() == count {
+        break;
+      }
+    }
+
+    state
+      .mempool
+      .retain(|mempool_tx| !evicted.contains(&mempool_tx.txid()));
+
+    state.mempool.push(tx.clone());
 
     Ok(tx.txid().to_string())
   }
@@ -264,12 +372,18 @@ impl Api for Server {
     txid: Txid,
     _include_watchonly: Option<bool>,
   ) -> Result<Value, jsonrpc_core::Error> {
-    match self.state.lock().unwrap().transactions.get(&txid) {
-      Some(tx) => Ok(
+    let state = self.state();
+
+    match state.find_transaction(txid) {
+      Some((tx, confirmed)) => Ok(
         serde_json::to_value(GetTransactionResult {
           info: WalletTxInfo {
             txid,
-            confirmations: 0,
+            confirmations: if confirmed {
+              state.get_confirmations(tx)
+            } else {
+              0
+            },
             time: 0,
             timereceived: 0,
             blockhash: None,
@@ -300,12 +414,12 @@ impl Api for Server {
   ) -> Result<Value, jsonrpc_core::Error> {
     assert_eq!(blockhash, None, "Blockhash param is unsupported");
     if verbose {
-      match self.state().transactions.get(&txid) {
-        Some(_) => Ok(
+      match self.state().find_transaction(txid) {
+        Some((tx, confirmed)) => Ok(
           serde_json::to_value(GetRawTransactionResult {
             in_active_chain: None,
-            hex: Vec::new(),
-            txid: Txid::all_zeros(),
+            hex: serialize(tx),
+            txid,
             hash: Wtxid::all_zeros(),
             size: 0,
             vsize: 0,
@@ -314,7 +428,7 @@ impl Api for Server {
             vin: Vec::new(),
             vout: Vec::new(),
             blockhash: None,
-            confirmations: Some(1),
+            confirmations: confirmed.then_some(1),
             time: None,
             blocktime: None,
           })
@@ -323,13 +437,67 @@ impl Api for Server {
         None => Err(Self::not_found()),
       }
     } else {
-      match self.state().transactions.get(&txid) {
-        Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
+      match self.state().find_transaction(txid) {
+        Some((tx, _confirmed)) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
     }
   }
 
+  fn get_tx_out(
+    &self,
+    txid: Txid,
+    vout: u32,
+    include_mempool: Option<bool>,
+  ) -> Result<Option<GetTxOutResult>, jsonrpc_core::Error> {
+    let state = self.state();
+
+    let outpoint = OutPoint { txid, vout };
+
+    let Some((tx, confirmed)) = state.find_transaction(txid) else {
+      return Ok(None);
+    };
+
+    let Some(tx_out) = tx.output.get(usize::try_from(vout).unwrap()) else {
+      return Ok(None);
+    };
+
+    let include_mempool = include_mempool.unwrap_or(true);
+
+    if !confirmed && !include_mempool {
+      return Ok(None);
+    }
+
+    if confirmed && !state.utxos.contains_key(&outpoint) {
+      return Ok(None);
+    }
+
+    if include_mempool
+      && state.mempool.iter().any(|mempool_tx| {
+        mempool_tx
+          .input
+          .iter()
+          .any(|tx_in| tx_in.previous_output == outpoint)
+      })
+    {
+      return Ok(None);
+    }
+
+    Ok(Some(GetTxOutResult {
+      bestblock: *state.hashes.last().unwrap(),
+      confirmations: u32::from(confirmed),
+      value: Amount::from_sat(tx_out.value),
+      script_pub_key: GetRawTransactionResultVoutScriptPubKey {
+        asm: tx_out.script_pubkey.asm(),
+        hex: tx_out.script_pubkey.to_bytes(),
+        req_sigs: None,
+        type_: None,
+        addresses: None,
+      },
+      coinbase: false,
+    }))
+  }
+
   fn list_unspent(
     &self,
     minconf: Option<usize>,
@@ -338,42 +506,88 @@ impl Api for Server {
     include_unsafe: Option<bool>,
     query_options: Option<String>,
   ) -> Result<Vec<ListUnspentResultEntry>, jsonrpc_core::Error> {
-    assert_eq!(minconf, None, "minconf param not supported");
+    assert!(
+      matches!(minconf, None | Some(0)),
+      "minconf param other than 0 not supported"
+    );
     assert_eq!(maxconf, None, "maxconf param not supported");
     assert_eq!(address, None, "address param not supported");
     assert_eq!(include_unsafe, None, "include_unsafe param not supported");
     assert_eq!(query_options, None, "query_options param not supported");
 
-    Ok(
-      self
-        .state()
-        .utxos
-        .iter()
-        .map(|(outpoint, &amount)| ListUnspentResultEntry {
-          txid: outpoint.txid,
-          vout: outpoint.vout,
-          address: None,
-          label: None,
-          redeem_script: None,
-          witness_script: None,
-          script_pub_key: Script::new(),
+    self.check_wallet()?;
+
+    let state = self.state();
+
+    let entry = |outpoint: OutPoint, amount: Amount, confirmations: u32| ListUnspentResultEntry {
+      txid: outpoint.txid,
+      vout: outpoint.vout,
+      address: None,
+      label: None,
+      redeem_script: None,
+      witness_script: None,
+      script_pub_key: Script::new(),
+      amount,
+      confirmations,
+      spendable: true,
+      solvable: true,
+      descriptor: None,
+      safe: true,
+    };
+
+    let mut utxos = state
+      .utxos
+      .iter()
+      .map(|(outpoint, &amount)| {
+        entry(
+          *outpoint,
           amount,
-          confirmations: 0,
-          spendable: true,
-          solvable: true,
-          descriptor: None,
-          safe: true,
-        })
-        .collect(),
-    )
+          state
+            .get_confirmations(&state.transactions[&outpoint.txid])
+            .try_into()
+            .unwrap(),
+        )
+      })
+      .collect::<Vec<ListUnspentResultEntry>>();
+
+    // With a minconf of 0, include outputs of mempool transactions, and
+    // exclude outputs spent by them
+    if minconf == Some(0) {
+      let spent = state
+        .mempool
+        .iter()
+        .flat_map(|tx| tx.input.iter().map(|tx_in| tx_in.previous_output))
+        .collect::<BTreeSet<OutPoint>>();
+
+      for tx in &state.mempool {
+        for (vout, tx_out) in (0..).zip(&tx.output) {
+          utxos.push(entry(
+            OutPoint::new(tx.txid(), vout),
+            Amount::from_sat(tx_out.value),
+            0,
+          ));
+        }
+      }
+
+      utxos.retain(|utxo| !spent.contains(&OutPoint::new(utxo.txid, utxo.vout)));
+    }
+
+    Ok(utxos)
   }
 
   fn get_raw_change_address(&self) -> Result<bitcoin::Address, jsonrpc_core::Error> {
+    self.check_wallet()?;
+
     let secp256k1 = Secp256k1::new();
     let key_pair = KeyPair::new(&secp256k1, &mut rand::thread_rng());
     let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
     let address = Address::p2tr(&secp256k1, public_key, None, self.network);
 
+    self
+      .state()
+      .addresses
+      .insert(address.script_pubkey(), key_pair);
+
     Ok(address)
   }
 
@@ -392,9 +606,13 @@ impl Api for Server {
 
   fn import_descriptors(
     &self,
-    _params: Vec<serde_json::Value>,
+    params: Vec<serde_json::Value>,
   ) -> Result<serde_json::Value, jsonrpc_core::Error> {
-    Ok(json!([{"success": true}]))
+    let results = params.iter().map(|_| json!({"success": true})).collect();
+
+    self.state().descriptors.extend(params);
+
+    Ok(Value::Array(results))
   }
 
   fn get_new_address(
@@ -402,11 +620,18 @@ impl Api for Server {
     _label: Option<String>,
     _address_type: Option<()>,
   ) -> Result<bitcoin::Address, jsonrpc_core::Error> {
+    self.check_wallet()?;
+
     let secp256k1 = Secp256k1::new();
     let key_pair = KeyPair::new(&secp256k1, &mut rand::thread_rng());
     let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
     let address = Address::p2tr(&secp256k1, public_key, None, self.network);
 
+    self
+      .state()
+      .addresses
+      .insert(address.script_pubkey(), key_pair);
+
     Ok(address)
   }
 
@@ -452,4 +677,114 @@ impl Api for Server {
         .collect(),
     )
   }
+
+  fn wallet_process_psbt(
+    &self,
+    psbt: String,
+    sign: Option<bool>,
+    sighash_type: Option<String>,
+    _bip32derivs: Option<bool>,
+  ) -> Result<WalletProcessPsbtResult, jsonrpc_core::Error> {
+    if sign != Some(true) {
+      return Ok(WalletProcessPsbtResult {
+        psbt,
+        complete: false,
+      });
+    }
+
+    let mut decoded = base64::decode(&psbt)
+      .ok()
+      .and_then(|bytes| deserialize::<Psbt>(&bytes).ok())
+      .ok_or_else(|| jsonrpc_core::Error::invalid_params("TX decode failed"))?;
+
+    let hash_ty = match sighash_type.as_deref() {
+      None | Some("DEFAULT") => SchnorrSighashType::Default,
+      Some("ALL") => SchnorrSighashType::All,
+      Some("SINGLE|ANYONECANPAY") => SchnorrSighashType::SinglePlusAnyoneCanPay,
+      Some(sighash_type) => panic!("sighash type {sighash_type} not supported"),
+    };
+
+    let prevouts = decoded
+      .inputs
+      .iter()
+      .map(|input| {
+        input
+          .witness_utxo
+          .clone()
+          .expect("signing inputs without witness UTXOs not supported")
+      })
+      .collect::<Vec<TxOut>>();
+
+    let state = self.state();
+
+    let secp256k1 = Secp256k1::new();
+
+    let mut sighash_cache = SighashCache::new(&decoded.unsigned_tx);
+
+    let mut complete = true;
+
+    // Sign taproot key path spends of outputs sent to wallet addresses
+    for (i, input) in decoded.inputs.iter_mut().enumerate() {
+      let Some(key_pair) = state.addresses.get(&prevouts[i].script_pubkey) else {
+        complete = false;
+        continue;
+      };
+
+      let signature_hash = if hash_ty == SchnorrSighashType::SinglePlusAnyoneCanPay {
+        sighash_cache.taproot_key_spend_signature_hash(
+          i,
+          &Prevouts::One(i, prevouts[i].clone()),
+          hash_ty,
+        )
+      } else {
+        sighash_cache.taproot_key_spend_signature_hash(i, &Prevouts::All(&prevouts), hash_ty)
+      }
+      .unwrap();
+
+      let sig = secp256k1.sign_schnorr(
+        &Message::from_slice(signature_hash.as_inner()).unwrap(),
+        &key_pair.tap_tweak(&secp256k1, None).to_inner(),
+      );
+
+      input.final_script_witness =
+        Some(Witness::from_vec(
+          vec![SchnorrSig { sig, hash_ty }.to_vec()],
+        ));
+    }
+
+    Ok(WalletProcessPsbtResult {
+      psbt: base64::encode(serialize(&decoded)),
+      complete,
+    })
+  }
+
+  fn finalize_psbt(
+    &self,
+    psbt: String,
+    extract: Option<bool>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    assert_eq!(extract, Some(true), "extract param must be true");
+
+    let decoded = base64::decode(&psbt)
+      .ok()
+      .and_then(|bytes| deserialize::<Psbt>(&bytes).ok())
+      .ok_or_else(|| jsonrpc_core::Error::invalid_params("TX decode failed"))?;
+
+    let complete = decoded
+      .inputs
+      .iter()
+      .all(|input| input.final_script_witness.is_some() || input.final_script_sig.is_some());
+
+    if complete {
+      Ok(json!({
+        "hex": hex::encode(serialize(&decoded.extract_tx())),
+        "complete": true,
+      }))
+    } else {
+      Ok(json!({
+        "psbt": psbt,
+        "complete": false,
+      }))
+    }
+  }
 }
@@ -1,11 +1,16 @@
-use super::*;
+use {super::*, bitcoin::secp256k1::KeyPair};
 
 pub(crate) struct State {
+  pub(crate) addresses: BTreeMap<Script, KeyPair>,
   pub(crate) blocks: BTreeMap<BlockHash, Block>,
+  pub(crate) broadcast_limit: Option<usize>,
+  pub(crate) descriptors: Vec<Value>,
+  pub(crate) fee_estimate: Option<Amount>,
   pub(crate) hashes: Vec<BlockHash>,
   pub(crate) mempool: Vec<Transaction>,
   pub(crate) network: Network,
   pub(crate) nonce: u32,
+  pub(crate) request_wallet: Option<String>,
   pub(crate) transactions: BTreeMap<Txid, Transaction>,
   pub(crate) utxos: BTreeMap<OutPoint, Amount>,
   pub(crate) wallet_name: String,
@@ -23,11 +28,16 @@ impl State {
     blocks.insert(genesis_block_hash, genesis_block);
 
     Self {
+      addresses: BTreeMap::new(),
       blocks,
+      broadcast_limit: None,
+      descriptors: Vec::new(),
+      fee_estimate: None,
       hashes,
       mempool: Vec::new(),
       network,
       nonce: 0,
+      request_wallet: None,
       transactions: BTreeMap::new(),
       utxos: BTreeMap::new(),
       wallet_name: wallet_name.to_string(),
@@ -128,7 +138,7 @@ impl State {
         previous_output: OutPoint::new(tx.txid(), *vout as u32),
         script_sig: Script::new(),
         sequence: Sequence::MAX,
-        witness: Witness::new(),
+        witness: options.witness.clone(),
       });
     }
 
@@ -154,10 +164,89 @@ impl State {
     tx.txid()
   }
 
+  pub(crate) fn find_transaction(&self, txid: Txid) -> Option<(&Transaction, bool)> {
+    if let Some(tx) = self.transactions.get(&txid) {
+      return Some((tx, true));
+    }
+
+    self
+      .mempool
+      .iter()
+      .find(|tx| tx.txid() == txid)
+      .map(|tx| (tx, false))
+  }
+
   pub(crate) fn mempool(&self) -> &[Transaction] {
     &self.mempool
   }
 
+  /// Whether adding `tx` to the mempool would give any of its unconfirmed
+  /// ancestors more than Bitcoin Core's default limit of 25 unconfirmed
+  /// descendants, including the ancestor itself. Transactions that `tx`
+  /// replaces are not counted.
+  pub(crate) fn exceeds_descendant_limit(&self, tx: &Transaction) -> bool {
+    const DESCENDANT_LIMIT: usize = 25;
+
+    let mempool = self
+      .mempool
+      .iter()
+      .filter(|mempool_tx| {
+        !mempool_tx.input.iter().any(|mempool_tx_in| {
+          tx.input
+            .iter()
+            .any(|tx_in| tx_in.previous_output == mempool_tx_in.previous_output)
+        })
+      })
+      .collect::<Vec<&Transaction>>();
+
+    let mut ancestors = BTreeSet::new();
+    let mut parents = tx
+      .input
+      .iter()
+      .map(|tx_in| tx_in.previous_output.txid)
+      .collect::<Vec<Txid>>();
+
+    while let Some(parent) = parents.pop() {
+      if let Some(parent_tx) = mempool
+        .iter()
+        .find(|mempool_tx| mempool_tx.txid() == parent)
+      {
+        if ancestors.insert(parent) {
+          parents.extend(
+            parent_tx
+              .input
+              .iter()
+              .map(|tx_in| tx_in.previous_output.txid),
+          );
+        }
+      }
+    }
+
+    ancestors.into_iter().any(|ancestor| {
+      let mut descendants = BTreeSet::from([ancestor]);
+
+      loop {
+        let count = descendants.len();
+
+        for mempool_tx in &mempool {
+          if mempool_tx
+            .input
+            .iter()
+            .any(|tx_in| descendants.contains(&tx_in.previous_output.txid))
+          {
+            descendants.insert(mempool_tx.txid());
+          }
+        }
+
+        if descendants.len() == count {
+          break;
+        }
+      }
+
+      descendants.len() + 1 > DESCENDANT_LIMIT
+    })
+  }
+
   pub(crate) fn get_confirmations(&self, tx: &Transaction) -> i32 {
     for (confirmations, hash) in self.hashes.iter().rev().enumerate() {
       if self.blocks.get(hash).unwrap().txdata.contains(tx) {
@@ -28,7 +28,8 @@ pub(crate) struct CommandBuilder {
   expected_stderr: Expected,
   expected_stdout: Expected,
   rpc_server_url: Option<String>,
-  tempdir: TempDir,
+  stdin: Vec<u8>,
+  tempdir: Arc<TempDir>,
 }
 
 impl CommandBuilder {
@@ -39,15 +40,27 @@ impl CommandBuilder {
       expected_stderr: Expected::String(String::new()),
       expected_stdout: Expected::String(String::new()),
       rpc_server_url: None,
-      tempdir: TempDir::new().unwrap(),
+      stdin: Vec::new(),
+      tempdir: Arc::new(TempDir::new().unwrap()),
     }
   }
 
+  pub(crate) fn temp_dir(self, tempdir: Arc<TempDir>) -> Self {
+    Self { tempdir, ..self }
+  }
+
   pub(crate) fn write(self, path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Self {
     fs::write(self.tempdir.path().join(path), contents).unwrap();
     self
   }
 
+  pub(crate) fn stdin(self, stdin: impl AsRef<[u8]>) -> Self {
+    Self {
+      stdin: stdin.as_ref().to_vec(),
+      ..self
+    }
+  }
+
   pub(crate) fn rpc_server(self, rpc_server: &test_bitcoincore_rpc::Handle) -> Self {
     Self {
       rpc_server_url: Some(rpc_server.url()),
@@ -106,10 +119,14 @@ impl CommandBuilder {
 
     command
       .env("ORD_INTEGRATION_TEST", "1")
-      .stdin(Stdio::null())
+      .stdin(if self.stdin.is_empty() {
+        Stdio::null()
+      } else {
+        Stdio::piped()
+      })
       .stdout(Stdio::piped())
       .stderr(Stdio::piped())
-      .current_dir(&self.tempdir)
+      .current_dir(&*self.tempdir)
       .arg("--data-dir")
       .arg(self.tempdir.path())
       .args(&self.args);
@@ -118,7 +135,13 @@ impl CommandBuilder {
   }
 
   pub(crate) fn run(self) -> String {
-    let output = self.command().output().unwrap();
+    let mut child = self.command().spawn().unwrap();
+
+    if let Some(mut stdin) = child.stdin.take() {
+      stdin.write_all(&self.stdin).unwrap();
+    }
+
+    let output = child.wait_with_output().unwrap();
     let stdout = str::from_utf8(&output.stdout).unwrap();
     let stderr = str::from_utf8(&output.stderr).unwrap();
 
@@ -0,0 +1,110 @@
+use {
+  super::*,
+  bitcoin::consensus::encode::{serialize, serialize_hex},
+};
+
+fn inscribe(rpc_server: &test_bitcoincore_rpc::Handle) -> String {
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet inscribe --satpoint {txid}:0:0 --file hello.txt"
+  ))
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  serialize_hex(&rpc_server.mempool()[1])
+}
+
+#[test]
+fn decode_hex() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let reveal = inscribe(&rpc_server);
+
+  CommandBuilder::new(format!("decode {reveal}"))
+    .expected_stdout(
+      r#"{
+  "inputs": [
+    {
+      "input": 0,
+      "envelopes": [
+        {
+          "fields": [
+            {
+              "tag": "01",
+              "value": "746578742f706c61696e3b636861727365743d7574662d38"
+            }
+          ],
+          "duplicate_field": false,
+          "content_type": "text/plain;charset=utf-8",
+          "content_length": 10,
+          "content": null
+        }
+      ],
+      "error": null
+    }
+  ]
+}
+"#,
+    )
+    .run();
+}
+
+#[test]
+fn decode_file_with_content() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let reveal = inscribe(&rpc_server);
+
+  CommandBuilder::new("decode --content reveal.hex")
+    .write("reveal.hex", reveal)
+    .stdout_regex(r#".*"content": "SEVMTE9XT1JMRA=="\n.*"#)
+    .run();
+}
+
+#[test]
+fn decode_binary_transaction_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  inscribe(&rpc_server);
+
+  let reveal = serialize(&rpc_server.mempool()[1]);
+
+  CommandBuilder::new("decode --content reveal.tx")
+    .write("reveal.tx", reveal)
+    .stdout_regex(r#".*"content": "SEVMTE9XT1JMRA=="\n.*"#)
+    .run();
+}
+
+#[test]
+fn decode_txid_reports_inputs_without_envelopes() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!("--regtest decode {txid}"))
+    .rpc_server(&rpc_server)
+    .expected_stdout(
+      r#"{
+  "inputs": [
+    {
+      "input": 0,
+      "envelopes": [],
+      "error": "empty witness"
+    }
+  ]
+}
+"#,
+    )
+    .run();
+}
+
+#[test]
+fn decode_invalid_input() {
+  CommandBuilder::new("decode foo")
+    .expected_exit_code(1)
+    .stderr_regex("error: input is not a txid, hex, or base64\n.*")
+    .run();
+}
@@ -15,3 +15,30 @@ fn custom_index_path() {
 
   assert!(index_path.is_file())
 }
+
+#[test]
+fn index_with_incompatible_schema_must_be_rebuilt() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("index.redb");
+
+  {
+    let database = redb::Database::create(&index_path).unwrap();
+    let wtx = database.begin_write().unwrap();
+    wtx
+      .open_table(redb::TableDefinition::<u64, u64>::new("STATISTIC_TO_COUNT"))
+      .unwrap();
+    wtx.commit().unwrap();
+  }
+
+  CommandBuilder::new(format!("--index {} index", index_path.display()))
+    .rpc_server(&rpc_server)
+    .expected_stderr(format!(
+      "error: index at `{}` was built with an incompatible version of ord, consider deleting and rebuilding the index: index schema 0, ord schema 4\n",
+      index_path.display()
+    ))
+    .expected_exit_code(1)
+    .run();
+}
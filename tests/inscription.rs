@@ -0,0 +1,44 @@
+use super::*;
+
+#[test]
+fn duplicates() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let first = create_inscription(&rpc_server, "foo.txt");
+  let second = create_inscription(&rpc_server, "bar.txt");
+
+  CommandBuilder::new(format!("--regtest inscription duplicates {second}"))
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{first}\n{second}\n"))
+    .run();
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  ord_server.assert_response_regex(
+    &format!("/inscription/{first}"),
+    ".*
+  <dt>copies</dt>
+  <dd>first of 2 copies</dd>.*",
+  );
+
+  ord_server.assert_response_regex(
+    &format!("/inscription/{second}"),
+    &format!(
+      ".*
+  <dt>copies</dt>
+  <dd>copy of <a class=monospace href=/inscription/{first}>{first}</a></dd>.*"
+    ),
+  );
+}
+
+#[test]
+fn duplicates_unknown_inscription() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!("--regtest inscription duplicates {txid}"))
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!("error: inscription {txid} not found\n"))
+    .run();
+}
@@ -9,10 +9,13 @@ use {
   reqwest::{StatusCode, Url},
   std::{
     fs,
+    io::Write,
     net::TcpListener,
     path::Path,
     process::{Child, Command, Stdio},
-    str, thread,
+    str,
+    sync::Arc,
+    thread,
     time::Duration,
   },
   tempfile::TempDir,
@@ -33,6 +36,8 @@ macro_rules! assert_regex_match {
   };
 }
 
+const FEE_REPORT: &str = "Paying fee of [0-9]+ sats at 1 sat/vB\n";
+
 fn reveal_txid_from_inscribe_stdout(stdout: &str) -> Txid {
   stdout
     .lines()
@@ -54,6 +59,7 @@ fn create_inscription(rpc_server: &test_bitcoincore_rpc::Handle, filename: &str)
   .write(filename, "HELLOWORLD")
   .rpc_server(rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let inscription_id = reveal_txid_from_inscribe_stdout(&stdout);
@@ -64,11 +70,13 @@ fn create_inscription(rpc_server: &test_bitcoincore_rpc::Handle, filename: &str)
 }
 
 mod command_builder;
+mod decode;
 mod epochs;
 mod expected;
 mod find;
 mod index;
 mod info;
+mod inscription;
 mod list;
 mod parse;
 mod server;
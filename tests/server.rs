@@ -45,6 +45,7 @@ fn inscription_page() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let reveal_tx = reveal_txid_from_inscribe_stdout(&stdout);
@@ -60,6 +61,8 @@ fn inscription_page() {
 <pre class=inscription>HELLOWORLD</pre>
 </a>
 <dl>
+  <dt>number</dt>
+  <dd>0</dd>
   <dt>content size</dt>
   <dd>10 bytes</dd>
   <dt>content type</dt>
@@ -86,6 +89,7 @@ fn inscription_appears_on_reveal_transaction_page() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let reveal_tx = reveal_txid_from_inscribe_stdout(&stdout);
@@ -109,6 +113,7 @@ fn inscription_page_after_send() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let reveal_txid = reveal_txid_from_inscribe_stdout(&stdout);
@@ -131,6 +136,7 @@ fn inscription_page_after_send() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex(".*")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   rpc_server.mine_blocks(1);
@@ -161,6 +167,7 @@ fn inscription_content() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let reveal_tx = reveal_txid_from_inscribe_stdout(&stdout);
@@ -176,12 +183,33 @@ fn inscription_content() {
     "text/plain;charset=utf-8"
   );
   assert_eq!(
-    response.headers().get("content-security-policy").unwrap(),
-    "default-src 'none' 'unsafe-eval' 'unsafe-inline'"
+    response
+      .headers()
+      .get_all("content-security-policy")
+      .into_iter()
+      .collect::<Vec<_>>(),
+    [
+      "default-src 'self' 'unsafe-eval' 'unsafe-inline' data: blob:",
+      "default-src *:*/content/ *:*/r/ 'unsafe-eval' 'unsafe-inline' data: blob:",
+    ]
   );
   assert_eq!(response.bytes().unwrap(), "HELLOWORLD");
 }
 
+#[test]
+fn recursive_inscription_endpoint() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let inscription_id = create_inscription(&rpc_server, "foo.txt");
+
+  TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+    &format!("/r/inscription/{inscription_id}"),
+    &format!(
+      r#"\{{"content_length":10,"content_type":"text/plain;charset=utf-8","delegate":null,"genesis_height":2,"inscription_id":"{inscription_id}","number":0,"satpoint":"{inscription_id}:0:0"\}}"#
+    ),
+  );
+}
+
 #[test]
 fn home_page_includes_latest_inscriptions() {
   let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
@@ -249,6 +277,7 @@ fn inscriptions_page() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let reveal_tx = reveal_txid_from_inscribe_stdout(&stdout);
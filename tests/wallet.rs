@@ -65,6 +65,7 @@ fn send_works_on_signet() {
   .write("degenerate.png", [1; 520])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let reveal_txid = reveal_txid_from_inscribe_stdout(&stdout);
@@ -76,6 +77,7 @@ fn send_works_on_signet() {
   ))
   .rpc_server(&rpc_server)
   .stdout_regex(r".*")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let txid = rpc_server.mempool()[0].txid();
@@ -129,6 +131,7 @@ fn send_inscribed_sat() {
   .write("degenerate.png", [1; 520])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   rpc_server.mine_blocks(1);
@@ -140,6 +143,7 @@ fn send_inscribed_sat() {
   ))
   .rpc_server(&rpc_server)
   .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   rpc_server.mine_blocks(1);
@@ -195,6 +199,7 @@ fn send_on_mainnnet_works_with_wallet_named_ord() {
   ))
   .rpc_server(&rpc_server)
   .stdout_regex(r".*")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let txid = rpc_server.mempool()[0].txid();
@@ -211,6 +216,7 @@ fn send_on_mainnnet_works_with_wallet_whose_name_starts_with_ord() {
   ))
   .rpc_server(&rpc_server)
   .stdout_regex(r".*")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let txid = rpc_server.mempool()[0].txid();
@@ -242,6 +248,7 @@ fn inscribe() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   rpc_server.mine_blocks(1);
@@ -297,6 +304,7 @@ fn inscribe_png() {
   .write("degenerate.png", [1; 520])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let txid = reveal_txid_from_inscribe_stdout(&stdout);
@@ -341,6 +349,7 @@ fn regtest_has_no_content_size_limit() {
   .write("degenerate.png", [1; 1025])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 }
 
@@ -354,6 +363,7 @@ fn inscribe_does_not_use_inscribed_sats_as_cardinal_utxos() {
   .write("degenerate.png", [1; 100])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let txid = rpc_server.mine_blocks_with_subsidy(1, 100)[0].txdata[0].txid();
@@ -378,6 +388,7 @@ fn send_does_not_use_inscribed_sats_as_cardinal_utxos() {
   .write("degenerate.png", [1; 100])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let txid = rpc_server.mine_blocks_with_subsidy(1, 100)[0].txdata[0].txid();
@@ -402,6 +413,7 @@ fn refuse_to_reinscribe_sats() {
   .write("degenerate.png", [1; 100])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let first_inscription_id = reveal_txid_from_inscribe_stdout(&stdout);
@@ -431,6 +443,7 @@ fn do_not_accidentally_send_an_inscription() {
   .write("degenerate.png", [1; 100])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let inscription_id = reveal_txid_from_inscribe_stdout(&stdout);
@@ -464,6 +477,7 @@ fn refuse_to_inscribe_already_inscribed_utxo() {
   .write("degenerate.png", [1; 100])
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   rpc_server.mine_blocks(1);
@@ -498,6 +512,7 @@ fn inscriptions_cannot_be_sent_by_satpoint() {
   .write("hello.txt", "HELLOWORLD")
   .rpc_server(&rpc_server)
   .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   let reveal_txid = reveal_txid_from_inscribe_stdout(&stdout);
@@ -552,6 +567,7 @@ fn inscriptions() {
     .write("hello.txt", "HELLOWORLD")
     .rpc_server(&rpc_server)
     .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
     .run(),
   );
 
@@ -576,6 +592,7 @@ fn inscriptions() {
   .rpc_server(&rpc_server)
   .expected_exit_code(0)
   .stdout_regex(".*")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   rpc_server.mine_blocks(1);
@@ -599,6 +616,7 @@ fn inscribe_with_optional_satpoint_arg() {
     .write("hello.txt", "HELLOWORLD")
     .rpc_server(&rpc_server)
     .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
     .run();
 
   rpc_server.mine_blocks(1);
@@ -620,9 +638,100 @@ fn create() {
 
   CommandBuilder::new("--chain regtest wallet create")
     .rpc_server(&rpc_server)
+    .stdout_regex("([a-z]+ ){11}[a-z]+\n")
     .run();
 
   assert!(rpc_server.wallets().contains("ord"));
+
+  let descriptors = rpc_server.descriptors();
+  assert_eq!(descriptors.len(), 2);
+
+  for (descriptor, internal) in descriptors.iter().zip([false, true]) {
+    assert!(descriptor["desc"].as_str().unwrap().starts_with("tr(["));
+    assert!(descriptor["desc"]
+      .as_str()
+      .unwrap()
+      .contains("/86'/1'/0']tprv"));
+    assert_eq!(descriptor["active"], true);
+    assert_eq!(descriptor["internal"], internal);
+    assert_eq!(descriptor["timestamp"], "now");
+  }
+}
+
+#[test]
+fn restore() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new([
+    "--chain",
+    "regtest",
+    "wallet",
+    "restore",
+    "--mnemonic",
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+  ])
+  .rpc_server(&rpc_server)
+  .run();
+
+  assert!(rpc_server.wallets().contains("ord"));
+
+  let descriptors = rpc_server.descriptors();
+  assert_eq!(descriptors.len(), 2);
+
+  for (descriptor, change) in descriptors.iter().zip([0, 1]) {
+    let desc = descriptor["desc"].as_str().unwrap();
+    assert!(desc.starts_with("tr([73c5da0a/86'/1'/0']tprv"));
+    assert!(desc.ends_with(&format!("/{change}/*)#")));
+    assert_eq!(descriptor["timestamp"], 0);
+  }
+}
+
+#[test]
+fn restore_reads_mnemonic_from_stdin() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new("--chain regtest wallet restore")
+    .stdin(
+      "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about\n",
+    )
+    .rpc_server(&rpc_server)
+    .run();
+
+  assert!(rpc_server.wallets().contains("ord"));
+
+  let descriptors = rpc_server.descriptors();
+  assert_eq!(descriptors.len(), 2);
+  assert!(descriptors[0]["desc"]
+    .as_str()
+    .unwrap()
+    .starts_with("tr([73c5da0a/86'/1'/0']tprv"));
+}
+
+#[test]
+fn restore_rejects_invalid_mnemonic_from_stdin() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new("--chain regtest wallet restore")
+    .stdin("foo\n")
+    .rpc_server(&rpc_server)
+    .stderr_regex("error: invalid mnemonic: .*")
+    .expected_exit_code(1)
+    .run();
+
+  assert!(!rpc_server.wallets().contains("ord"));
+}
+
+#[test]
+fn restore_rejects_invalid_mnemonic() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new("--chain regtest wallet restore --mnemonic foo")
+    .rpc_server(&rpc_server)
+    .stderr_regex(".*Invalid value.*")
+    .expected_exit_code(2)
+    .run();
+
+  assert!(!rpc_server.wallets().contains("ord"));
 }
 
 #[test]
@@ -634,6 +743,7 @@ fn transactions() {
     .write("degenerate.png", [1; 520])
     .rpc_server(&rpc_server)
     .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
     .run();
 
   let reveal_txid = reveal_txid_from_inscribe_stdout(&stdout);
@@ -655,6 +765,7 @@ fn transactions() {
   ))
   .rpc_server(&rpc_server)
   .stdout_regex(r".*")
+  .stderr_regex(FEE_REPORT)
   .run();
 
   CommandBuilder::new("--chain signet wallet transactions")
@@ -668,12 +779,14 @@ fn inscribe_gif() {
   let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
   rpc_server.mine_blocks(1)[0].txdata[0].txid();
 
-  let stdout =
-    CommandBuilder::new("--chain regtest --index-sats wallet inscribe --file dolphin.gif")
-      .write("dolphin.gif", [1; 520])
-      .rpc_server(&rpc_server)
-      .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
-      .run();
+  let stdout = CommandBuilder::new(
+    "--chain regtest --index-sats wallet inscribe --file dolphin.gif --allow-rare",
+  )
+  .write("dolphin.gif", [1; 520])
+  .rpc_server(&rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
 
   let txid = reveal_txid_from_inscribe_stdout(&stdout);
 
@@ -695,13 +808,1794 @@ fn wallet_balance() {
 
   CommandBuilder::new("--regtest wallet balance")
     .rpc_server(&rpc_server)
-    .expected_stdout("0\n")
+    .expected_stdout("cardinal\t0\ninscribed\t0\ninscriptions\t0\nunconfirmed\t0\ntotal\t0\n")
     .run();
 
   rpc_server.mine_blocks(1);
 
   CommandBuilder::new("--regtest wallet balance")
     .rpc_server(&rpc_server)
-    .expected_stdout("5000000000\n")
+    .expected_stdout(
+      "cardinal\t5000000000\ninscribed\t0\ninscriptions\t0\nunconfirmed\t0\ntotal\t5000000000\n",
+    )
+    .run();
+}
+
+#[test]
+fn wallet_balance_breakdown() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  create_inscription(&rpc_server, "foo.txt");
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--chain regtest --index-sats wallet send 1000sat bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x",
+  )
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  CommandBuilder::new("--chain regtest wallet balance")
+    .rpc_server(&rpc_server)
+    .expected_stdout(
+      "cardinal\t10000000415\ninscribed\t10000\ninscriptions\t1\nunconfirmed\t4999989322\ntotal\t14999999737\n",
+    )
+    .run();
+
+  CommandBuilder::new("--chain regtest --index-sats wallet balance --json")
+    .rpc_server(&rpc_server)
+    .expected_stdout(
+      r#"{
+  "cardinal": 0,
+  "inscribed": 10000,
+  "inscriptions": 1,
+  "rare": 10000000415,
+  "unconfirmed": 4999989322,
+  "total": 14999999737
+}
+"#,
+    )
+    .run();
+}
+
+#[test]
+fn inscribe_with_delegate() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let delegate = create_inscription(&rpc_server, "foo.txt");
+
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new(format!(
+    "--chain regtest wallet inscribe --delegate {delegate}"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let inscription_id = reveal_txid_from_inscribe_stdout(&stdout);
+
+  rpc_server.mine_blocks(1);
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  let response = ord_server.request(&format!("/content/{inscription_id}"));
+  assert_eq!(response.status(), StatusCode::OK);
+  assert_eq!(
+    response.headers().get("content-type").unwrap(),
+    "text/plain;charset=utf-8"
+  );
+  assert_eq!(response.bytes().unwrap(), "HELLOWORLD");
+
+  ord_server.assert_response_regex(
+    &format!("/inscription/{inscription_id}"),
+    &format!(
+      ".*<pre class=inscription>HELLOWORLD</pre>.*
+  <dt>delegate</dt>
+  <dd><a class=monospace href=/inscription/{delegate}>{delegate}</a></dd>.*"
+    ),
+  );
+}
+
+#[test]
+fn inscribe_with_unknown_delegate() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!("--chain regtest wallet inscribe --delegate {txid}"))
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!("error: delegate {txid} is not an inscription\n"))
+    .run();
+}
+
+#[test]
+fn inscribe_with_fee_rate() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt --fee-rate 2.5")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex("Paying fee of [0-9]+ sats at 2.5 sat/vB\n")
+    .run();
+
+  let mempool = rpc_server.mempool();
+  let commit_tx = &mempool[0];
+  let reveal_tx = &mempool[1];
+
+  let reveal_fee =
+    commit_tx.output[usize::try_from(reveal_tx.input[0].previous_output.vout).unwrap()].value
+      - reveal_tx.output[0].value;
+
+  assert_eq!(reveal_fee, (2.5 * reveal_tx.vsize() as f64).ceil() as u64);
+}
+
+#[test]
+fn fee_rate_is_validated() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt --fee-rate 0.5")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(2)
+    .stderr_regex(".*fee rate must be at least 1 sat/vB.*")
+    .run();
+
+  CommandBuilder::new(
+    "--chain regtest wallet send 5e0b6a8a9d3b0f8d9d87a0a8e1a3e4b1b0f6d7a8a9a0b1c2d3e4f5a6b7c8d9e0:0:0 bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x --fee-rate 1001",
+  )
+  .rpc_server(&rpc_server)
+  .expected_exit_code(2)
+  .stderr_regex(".*fee rate must be at most 1000 sat/vB.*")
+  .run();
+}
+
+#[test]
+fn send_with_fee_priority_uses_node_fee_estimate() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  rpc_server.set_fee_estimate(Some(bitcoin::Amount::from_sat(5_000)));
+
+  CommandBuilder::new(format!(
+    "--chain signet wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw --fee-priority high"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+}
+
+#[test]
+fn inscribe_with_conf_target_falls_back_without_node_fee_estimate() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt --conf-target 6")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(
+      "Node has no fee estimate for confirmation within 6 blocks, falling back to 1 sat/vB\n\
+      Paying fee of [0-9]+ sats at 1 sat/vB\n",
+    )
+    .run();
+}
+
+#[test]
+fn fee_rate_conflicts_with_fee_estimation() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new(
+    "--chain regtest wallet inscribe --file hello.txt --fee-rate 2 --fee-priority low",
+  )
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(2)
+  .stderr_regex("error: The argument '--fee-rate <FEE_RATE>' cannot be used with .*")
+  .run();
+}
+
+#[test]
+fn inscribe_with_postage() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt --postage 546sat")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  assert_eq!(rpc_server.mempool()[1].output[0].value, 546);
+}
+
+#[test]
+fn inscribe_sat_by_number() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--chain regtest --index-sats wallet inscribe --sat 5000001000 --file hello.txt",
+  )
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  rpc_server.mine_blocks(1);
+
+  TestServer::spawn_with_args(&rpc_server, &["--index-sats"]).assert_response_regex(
+    "/sat/5000001000",
+    ".*<dt>inscription</dt>\n  <dd>.*<pre class=inscription>HELLOWORLD</pre>.*</dd>.*",
+  );
+}
+
+#[test]
+fn inscribe_sat_by_rarity() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--chain regtest --index-sats wallet inscribe --rarity epic --file hello.txt",
+  )
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr("error: wallet contains no uninscribed epic sats\n")
+  .run();
+
+  CommandBuilder::new(
+    "--chain regtest --index-sats wallet inscribe --rarity uncommon --file hello.txt",
+  )
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  rpc_server.mine_blocks(1);
+
+  TestServer::spawn_with_args(&rpc_server, &["--index-sats"]).assert_response_regex(
+    "/sat/5000000000",
+    ".*<dt>inscription</dt>\n  <dd>.*<pre class=inscription>HELLOWORLD</pre>.*</dd>.*",
+  );
+}
+
+#[test]
+fn inscribe_sat_requires_sat_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --sat 5000000000 --file hello.txt")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: selecting sats requires index created with `--index-sats` flag\n")
     .run();
 }
+
+#[test]
+fn inscribe_to_destination() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let destination = "bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x"
+    .parse::<Address>()
+    .unwrap();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet inscribe --file hello.txt --destination {destination}"
+  ))
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  assert_eq!(
+    rpc_server.mempool()[1].output[0].script_pubkey,
+    destination.script_pubkey()
+  );
+}
+
+#[test]
+fn inscribe_destination_must_be_valid_for_network() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--chain regtest wallet inscribe --file hello.txt --destination tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+  )
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: Address `tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw` is not valid for regtest\n",
+  )
+  .run();
+
+  assert!(rpc_server.mempool().is_empty());
+}
+
+#[test]
+fn inscribe_batch() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new("--chain regtest wallet inscribe --batch batch.txt")
+    .write("batch.txt", "# greetings\nhello.txt\ngoodbye.txt\n")
+    .write("hello.txt", "HELLO")
+    .write("goodbye.txt", "GOODBYE")
+    .rpc_server(&rpc_server)
+    .stdout_regex(
+      "commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\thello.txt\nreveal\t[[:xdigit:]]{64}\tgoodbye.txt\n",
+    )
+    .stderr_regex(FEE_REPORT).run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 3);
+  assert_eq!(mempool[0].output.len(), 3);
+
+  rpc_server.mine_blocks(1);
+
+  let reveal_txids = stdout
+    .lines()
+    .skip(1)
+    .map(|line| line.split('\t').nth(1).unwrap().to_string())
+    .collect::<Vec<String>>();
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  ord_server.assert_response_regex(&format!("/inscription/{}", reveal_txids[0]), ".*HELLO.*");
+
+  ord_server.assert_response_regex(&format!("/inscription/{}", reveal_txids[1]), ".*GOODBYE.*");
+}
+
+#[test]
+fn inscribe_batch_defers_reveals_beyond_mempool_chain_limit() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let mut command =
+    CommandBuilder::new("--chain regtest wallet inscribe --batch batch").temp_dir(tempdir.clone());
+
+  fs::create_dir(tempdir.path().join("batch")).unwrap();
+
+  for i in 0..26 {
+    command = command.write(format!("batch/{i:02}.txt"), format!("{i}"));
+  }
+
+  command
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\n(reveal\t[[:xdigit:]]{64}\t.*\n){26}")
+    .stderr_regex(format!(
+      "{FEE_REPORT}2 reveal transactions were not broadcast, since at most 24 may spend commit transaction [[:xdigit:]]{{64}} before it confirms. .*",
+    ))
+    .run();
+
+  assert_eq!(rpc_server.mempool().len(), 25);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\n(reveal\t[[:xdigit:]]{64}\n){24}")
+    .stderr_regex("2 reveal transactions were not broadcast.*")
+    .run();
+
+  assert_eq!(rpc_server.mempool().len(), 25);
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\n(reveal\t[[:xdigit:]]{64}\n){26}")
+    .run();
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: journal contains no inscriptions to resume\n")
+    .run();
+}
+
+#[test]
+fn inscribe_batch_conflicts_with_file() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new("--chain regtest wallet inscribe --batch batch.txt --file hello.txt")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(2)
+    .stderr_regex("error: The argument '--batch <BATCH>' cannot be used with '--file <FILE>'.*")
+    .run();
+}
+
+#[test]
+fn inscribe_resume_broadcasts_journaled_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  rpc_server.set_broadcast_limit(Some(1));
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex(format!(
+      "{FEE_REPORT}error: Failed to send reveal transaction, retry with `ord wallet inscribe --resume`.*",
+    ))
+    .run();
+
+  assert_eq!(rpc_server.mempool().len(), 1);
+
+  rpc_server.set_broadcast_limit(None);
+
+  let stdout = CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[1].txid(), reveal_txid_from_inscribe_stdout(&stdout));
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: journal contains no inscriptions to resume\n")
+    .run();
+}
+
+#[test]
+fn inscribe_removes_journal_entry_if_commit_fails_to_broadcast() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  rpc_server.set_broadcast_limit(Some(0));
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex(format!(
+      "{FEE_REPORT}error: Failed to send commit transaction.*"
+    ))
+    .run();
+
+  rpc_server.set_broadcast_limit(None);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: journal contains no inscriptions to resume\n")
+    .run();
+}
+
+#[test]
+fn inscribe_resume_continues_after_failed_entry() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  for file in ["hello.txt", "goodbye.txt"] {
+    rpc_server.set_broadcast_limit(Some(1));
+
+    CommandBuilder::new(format!("--chain regtest wallet inscribe --file {file}"))
+      .temp_dir(tempdir.clone())
+      .write(file, file)
+      .rpc_server(&rpc_server)
+      .expected_exit_code(1)
+      .stderr_regex(format!(
+        "{FEE_REPORT}error: Failed to send reveal transaction.*"
+      ))
+      .run();
+
+    rpc_server.mine_blocks(1);
+  }
+
+  rpc_server.set_broadcast_limit(Some(1));
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\n(reveal\t[[:xdigit:]]{64}\n)?commit\t[[:xdigit:]]{64}\n(reveal\t[[:xdigit:]]{64}\n)?")
+    .stderr_regex(
+      "error: failed to resume commit transaction [[:xdigit:]]{64}: Failed to send reveal transaction.*\nerror: failed to resume 1 of 2 journal entries\n",
+    )
+    .run();
+
+  assert_eq!(rpc_server.mempool().len(), 1);
+
+  rpc_server.set_broadcast_limit(None);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .run();
+
+  assert_eq!(rpc_server.mempool().len(), 2);
+}
+
+#[test]
+fn inscribe_resume_with_fee_rate_re_signs_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  rpc_server.set_broadcast_limit(Some(1));
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex(format!(
+      "{FEE_REPORT}error: Failed to send reveal transaction.*"
+    ))
+    .run();
+
+  rpc_server.set_broadcast_limit(None);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume --fee-rate 2")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+
+  let reveal_fee = transaction_fee(&rpc_server, &mempool[1]);
+  assert_eq!(reveal_fee, 2 * u64::try_from(mempool[1].vsize()).unwrap());
+  assert!(mempool[1].output[0].value < 10_000);
+}
+
+#[test]
+fn recover_sweeps_commit_output() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  rpc_server.set_broadcast_limit(Some(1));
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .stderr_regex(format!(
+      "{FEE_REPORT}error: Failed to send reveal transaction.*"
+    ))
+    .run();
+
+  rpc_server.set_broadcast_limit(None);
+
+  let commit_txid = rpc_server.mempool()[0].txid();
+
+  CommandBuilder::new(format!("--chain regtest wallet recover {commit_txid}"))
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .stdout_regex("[[:xdigit:]]{64}\n")
+    .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[1].input.len(), 1);
+  assert_eq!(mempool[1].input[0].previous_output.txid, commit_txid);
+  assert_eq!(mempool[1].input[0].witness.len(), 1);
+
+  CommandBuilder::new(format!("--chain regtest wallet recover {commit_txid}"))
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: journal contains no inscriptions with commit transaction {commit_txid}\n"
+    ))
+    .run();
+}
+
+#[test]
+fn recover_removes_entry_of_confirmed_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  let commit_txid = rpc_server.mempool()[0].txid();
+
+  CommandBuilder::new(format!("--chain regtest wallet recover {commit_txid}"))
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: outputs of commit transaction {commit_txid} have already been spent\n"
+    ))
+    .run();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!("--chain regtest wallet recover {commit_txid}"))
+    .temp_dir(tempdir.clone())
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: outputs of commit transaction {commit_txid} have already been spent by confirmed reveal transactions, removed it from journal\n"
+    ))
+    .run();
+
+  CommandBuilder::new(format!("--chain regtest wallet recover {commit_txid}"))
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: journal contains no inscriptions with commit transaction {commit_txid}\n"
+    ))
+    .run();
+}
+
+fn transaction_fee(rpc_server: &test_bitcoincore_rpc::Handle, tx: &bitcoin::Transaction) -> u64 {
+  let input_value = tx
+    .input
+    .iter()
+    .map(|tx_in| {
+      rpc_server.tx_by_id(tx_in.previous_output.txid).output
+        [usize::try_from(tx_in.previous_output.vout).unwrap()]
+      .value
+    })
+    .sum::<u64>();
+
+  input_value - tx.output.iter().map(|tx_out| tx_out.value).sum::<u64>()
+}
+
+#[test]
+fn bump_lowers_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let send_txid = CommandBuilder::new(format!(
+    "--chain signet wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let original = rpc_server.mempool()[0].clone();
+
+  CommandBuilder::new(format!(
+    "--chain signet wallet bump {} --fee-rate 5",
+    send_txid.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+
+  let replacement = &mempool[0];
+  assert_eq!(replacement.input, original.input);
+  assert_eq!(replacement.output[0], original.output[0]);
+  assert!(replacement.output[1].value < original.output[1].value);
+  assert_eq!(
+    transaction_fee(&rpc_server, replacement),
+    5 * u64::try_from(replacement.vsize()).unwrap()
+  );
+}
+
+#[test]
+fn bump_adds_cardinal_input_if_there_is_no_change() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks_with_subsidy(1, 10_000)[0].txdata[0].txid();
+  rpc_server.mine_blocks_with_subsidy(1, 10_000);
+
+  let send_txid = CommandBuilder::new(format!(
+    "--chain signet wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let original = rpc_server.mempool()[0].clone();
+  assert_eq!(original.output.len(), 1);
+
+  CommandBuilder::new(format!(
+    "--chain signet wallet bump {} --fee-rate 5",
+    send_txid.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+
+  let replacement = &mempool[0];
+  assert_eq!(replacement.input.len(), 2);
+  assert_eq!(replacement.input[0], original.input[0]);
+  assert_eq!(replacement.output.len(), 2);
+  assert_eq!(replacement.output[0], original.output[0]);
+  assert!(transaction_fee(&rpc_server, replacement) > transaction_fee(&rpc_server, &original));
+}
+
+#[test]
+fn bump_does_not_add_input_containing_protected_sats() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks_with_subsidy(1, 10_000)[0].txdata[0].txid();
+  rpc_server.mine_blocks_with_subsidy(1, 10_000);
+
+  let send_txid = CommandBuilder::new(format!(
+    "--chain signet --index-sats wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  CommandBuilder::new(format!(
+    "--chain signet --index-sats wallet bump {} --fee-rate 5",
+    send_txid.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: wallet does not contain a cardinal UTXO large enough to pay additional fee\n",
+  )
+  .run();
+
+  CommandBuilder::new(format!(
+    "--chain signet --index-sats wallet bump {} --fee-rate 5 --allow-rare",
+    send_txid.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+
+  assert_eq!(rpc_server.mempool()[0].input.len(), 2);
+}
+
+#[test]
+fn bump_re_signs_reveal_with_journaled_key() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let stdout = CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  let original = rpc_server.mempool()[1].clone();
+  assert_eq!(original.txid(), reveal_txid_from_inscribe_stdout(&stdout));
+
+  let replacement_txid = CommandBuilder::new(format!(
+    "--chain regtest wallet bump {} --fee-rate 5",
+    original.txid()
+  ))
+  .temp_dir(tempdir)
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+
+  let replacement = &mempool[1];
+  assert_eq!(replacement.txid().to_string(), replacement_txid.trim());
+  assert_eq!(
+    replacement.input[0].previous_output,
+    original.input[0].previous_output
+  );
+  assert_eq!(
+    replacement.input[0].witness.second_to_last(),
+    original.input[0].witness.second_to_last()
+  );
+  assert!(replacement.output[0].value < original.output[0].value);
+  assert_eq!(
+    transaction_fee(&rpc_server, replacement),
+    5 * u64::try_from(replacement.vsize()).unwrap()
+  );
+}
+
+#[test]
+fn bump_commit_re_signs_reveal_with_journaled_key() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  let original_commit = rpc_server.mempool()[0].clone();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet bump {} --fee-rate 5",
+    original_commit.txid()
+  ))
+  .temp_dir(tempdir.clone())
+  .rpc_server(&rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+
+  let (commit, reveal) = (&mempool[0], &mempool[1]);
+  assert_eq!(commit.input, original_commit.input);
+  assert_eq!(commit.output[0], original_commit.output[0]);
+  assert_eq!(
+    transaction_fee(&rpc_server, commit),
+    5 * u64::try_from(commit.vsize()).unwrap()
+  );
+  assert_eq!(reveal.input[0].previous_output.txid, commit.txid());
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscribe --resume")
+    .temp_dir(tempdir)
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: journal contains no inscriptions to resume\n")
+    .run();
+}
+
+#[test]
+fn bump_refuses_reveal_without_journaled_key() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let stdout = CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .temp_dir(tempdir.clone())
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  let reveal_txid = reveal_txid_from_inscribe_stdout(&stdout);
+
+  fs::remove_dir_all(tempdir.path().join("regtest").join("journal")).unwrap();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet bump {reveal_txid} --fee-rate 5"
+  ))
+  .temp_dir(tempdir)
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: cannot bump reveal transaction {reveal_txid}, since the journal does not contain its key, use `ord wallet cpfp` instead\n"
+  ))
+  .run();
+}
+
+#[test]
+fn bump_refuses_confirmed_transactions() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let send_txid = CommandBuilder::new(format!(
+    "--chain signet wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  rpc_server.mine_blocks(1);
+
+  let send_txid = send_txid.trim();
+
+  CommandBuilder::new(format!(
+    "--chain signet wallet bump {send_txid} --fee-rate 5"
+  ))
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: transaction {send_txid} is already confirmed\n"
+  ))
+  .run();
+}
+
+fn package_fee_rate(rpc_server: &test_bitcoincore_rpc::Handle) -> f64 {
+  let mempool = rpc_server.mempool();
+
+  let fee = mempool
+    .iter()
+    .map(|tx| transaction_fee(rpc_server, tx))
+    .sum::<u64>();
+
+  let vsize = mempool.iter().map(|tx| tx.vsize()).sum::<usize>();
+
+  fee as f64 / vsize as f64
+}
+
+#[test]
+fn cpfp_spends_change_of_send() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let send_txid = CommandBuilder::new(format!(
+    "--chain signet wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let send_txid = send_txid.trim();
+
+  let child_txid = CommandBuilder::new(format!(
+    "--chain signet wallet cpfp {send_txid} --fee-rate 5"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+
+  let child = &mempool[1];
+  assert_eq!(child.txid().to_string(), child_txid.trim());
+  assert_eq!(child.input.len(), 1);
+  assert_eq!(
+    child.input[0].previous_output,
+    OutPoint::new(send_txid.parse().unwrap(), 1)
+  );
+  assert_eq!(child.output.len(), 1);
+
+  assert!(package_fee_rate(&rpc_server) >= 5.0);
+}
+
+#[test]
+fn cpfp_spends_inscription_of_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  let reveal_txid = reveal_txid_from_inscribe_stdout(&stdout);
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet cpfp {reveal_txid} --fee-rate 5"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex("Paying fee of [0-9]+ sats at 5 sat/vB\n")
+  .run();
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 3);
+
+  let child = &mempool[2];
+  assert_eq!(child.input.len(), 1);
+  assert_eq!(
+    child.input[0].previous_output,
+    OutPoint::new(reveal_txid, 0)
+  );
+  assert_eq!(child.output.len(), 1);
+
+  assert!(package_fee_rate(&rpc_server) >= 5.0);
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscriptions")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{reveal_txid}\t{}:0:0\n", child.txid()))
+    .run();
+}
+
+#[test]
+fn cpfp_refuses_confirmed_transactions() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!("--chain signet wallet cpfp {txid} --fee-rate 5"))
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!("error: transaction {txid} is already confirmed\n"))
+    .run();
+}
+
+fn decode_psbt(psbt: &str) -> bitcoin::psbt::Psbt {
+  bitcoin::consensus::deserialize(&base64::decode(psbt.trim()).unwrap()).unwrap()
+}
+
+fn sign_psbt(psbt: &str) -> String {
+  let mut psbt = decode_psbt(psbt);
+
+  for input in &mut psbt.inputs {
+    input.final_script_witness = Some(bitcoin::Witness::from_vec(vec![vec![0; 64]]));
+  }
+
+  base64::encode(bitcoin::consensus::serialize(&psbt))
+}
+
+#[test]
+fn send_psbt() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let psbt = CommandBuilder::new(format!(
+    "--chain signet wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw --psbt"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:alnum:]+/=]+\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  assert!(rpc_server.mempool().is_empty());
+
+  let unsigned_tx = decode_psbt(&psbt).unsigned_tx;
+  assert_eq!(unsigned_tx.input[0].previous_output, OutPoint::new(txid, 0));
+  assert!(decode_psbt(&psbt).inputs[0].non_witness_utxo.is_some());
+
+  CommandBuilder::new(format!("--chain signet wallet broadcast {psbt}"))
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: PSBT is not fully signed\n")
+    .run();
+
+  CommandBuilder::new(format!(
+    "--chain signet wallet broadcast {}",
+    sign_psbt(&psbt)
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stdout(format!("{}\n", unsigned_tx.txid()))
+  .run();
+
+  assert_eq!(rpc_server.mempool().len(), 1);
+}
+
+#[test]
+fn broadcast_sign() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let inscription_id = create_inscription(&rpc_server, "foo.txt");
+
+  let psbt = CommandBuilder::new(format!(
+    "--chain regtest wallet send {inscription_id} bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x --psbt"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:alnum:]+/=]+\n")
+  .stderr_regex(FEE_REPORT).run();
+
+  let txid = decode_psbt(&psbt).unsigned_tx.txid();
+
+  CommandBuilder::new(format!("--chain regtest wallet broadcast {psbt}"))
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: PSBT is not fully signed\n")
+    .run();
+
+  CommandBuilder::new(format!("--chain regtest wallet broadcast --sign {psbt}"))
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{txid}\n"))
+    .run();
+
+  assert_eq!(rpc_server.mempool().len(), 1);
+}
+
+#[test]
+fn inscribe_psbt() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new("--chain regtest wallet inscribe --file hello.txt --psbt")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:alnum:]+/=]+\nreveal\t[[:alnum:]+/=]+\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  assert!(rpc_server.mempool().is_empty());
+
+  let psbts = stdout
+    .lines()
+    .map(|line| line.split('\t').nth(1).unwrap())
+    .collect::<Vec<&str>>();
+
+  let commit_txid = decode_psbt(psbts[0]).unsigned_tx.txid();
+  let reveal_txid = decode_psbt(psbts[1]).unsigned_tx.txid();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet broadcast {}",
+    sign_psbt(psbts[0])
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stdout(format!("{commit_txid}\n"))
+  .run();
+
+  CommandBuilder::new(format!("--chain regtest wallet broadcast {}", psbts[1]))
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{reveal_txid}\n"))
+    .run();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest wallet inscriptions")
+    .rpc_server(&rpc_server)
+    .expected_stdout(format!("{reveal_txid}\t{reveal_txid}:0:0\n"))
+    .run();
+}
+
+#[test]
+fn send_dry_run() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let stdout = CommandBuilder::new(format!(
+    "--index-sats --chain signet wallet send {txid}:0:0 tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw --dry-run"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(r"\{.*\}\n")
+  .stderr_regex(FEE_REPORT).run();
+
+  assert!(rpc_server.mempool().is_empty());
+
+  let preview = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+
+  let send_txid = preview["txid"].as_str().unwrap();
+
+  assert_eq!(preview["inputs"][0]["outpoint"], format!("{txid}:0"));
+  assert_eq!(
+    preview["outputs"][0]["address"],
+    "tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw"
+  );
+  assert_eq!(preview["outputs"][0]["value"], 10_000);
+  assert_eq!(preview["inscriptions"], serde_json::json!([]));
+  assert_eq!(
+    preview["rare_sats"],
+    serde_json::json!([{
+      "sat": 50 * COIN_VALUE,
+      "rarity": "uncommon",
+      "input": format!("{txid}:0:0"),
+      "output": format!("{send_txid}:0:0"),
+    }])
+  );
+}
+
+#[test]
+fn inscribe_dry_run() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let stdout = CommandBuilder::new(format!(
+    "--index-sats --chain regtest wallet inscribe --satpoint {txid}:0:0 --file hello.txt --dry-run"
+  ))
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .stdout_regex(r"\{.*\}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  assert!(rpc_server.mempool().is_empty());
+
+  let dry_run = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+
+  let commit_txid = dry_run["commit"]["txid"].as_str().unwrap();
+  let reveal_txid = dry_run["reveal"]["txid"].as_str().unwrap();
+
+  assert_eq!(
+    dry_run["reveal"]["inscriptions"],
+    serde_json::json!([{
+      "inscription_id": reveal_txid,
+      "input": null,
+      "output": format!("{reveal_txid}:0:0"),
+    }])
+  );
+
+  assert_eq!(
+    dry_run["commit"]["rare_sats"][0]["output"],
+    format!("{commit_txid}:0:0")
+  );
+
+  assert_eq!(
+    dry_run["reveal"]["rare_sats"],
+    serde_json::json!([{
+      "sat": 50 * COIN_VALUE,
+      "rarity": "uncommon",
+      "input": format!("{commit_txid}:0:0"),
+      "output": format!("{reveal_txid}:0:0"),
+    }])
+  );
+}
+
+#[test]
+fn create_with_wallet_name() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new("--chain regtest --wallet foo wallet create")
+    .rpc_server(&rpc_server)
+    .stdout_regex("([a-z]+ ){11}[a-z]+\n")
+    .run();
+
+  assert!(rpc_server.wallets().contains("foo"));
+  assert!(!rpc_server.wallets().contains("ord"));
+}
+
+#[test]
+fn wallet_name_is_percent_encoded_in_rpc_url() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new([
+    "--chain",
+    "regtest",
+    "--wallet",
+    "foo/bar?#",
+    "wallet",
+    "create",
+  ])
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .run();
+
+  assert!(rpc_server.wallets().contains("foo/bar?#"));
+
+  CommandBuilder::new([
+    "--chain",
+    "regtest",
+    "--wallet",
+    "foo/bar?#",
+    "wallet",
+    "receive",
+  ])
+  .rpc_server(&rpc_server)
+  .stdout_regex("bcrt1.*\n")
+  .run();
+}
+
+#[test]
+fn wallet_commands_use_selected_wallet() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  CommandBuilder::new("--chain regtest --wallet foo wallet create")
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*")
+    .run();
+
+  CommandBuilder::new("--chain regtest --wallet foo wallet receive")
+    .rpc_server(&rpc_server)
+    .stdout_regex("bcrt1.*\n")
+    .run();
+
+  CommandBuilder::new("--chain regtest --wallet bar wallet receive")
+    .rpc_server(&rpc_server)
+    .stderr_regex(".*Requested wallet does not exist or is not loaded: bar.*")
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn send_refuses_to_spend_rare_sats() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(2)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-sats wallet send {txid}:0:4999999950 bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x"
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stderr(
+    "error: wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.\n",
+  )
+  .expected_exit_code(1)
+  .run();
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-sats wallet send {txid}:0:4999999950 bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x --allow-rare"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT).run();
+}
+
+#[test]
+fn send_refuses_to_spend_protected_sats() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-sats wallet send {txid}:0:1 bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x --protect-rarity rare --protected-sats protected.tsv"
+  ))
+  .write("protected.tsv", format!("{}\tmy favorite sat\n", 50 * COIN_VALUE + 100))
+  .rpc_server(&rpc_server)
+  .expected_stderr(format!(
+    "error: cannot send {txid}:0:1 without also spending protected sat at {txid}:0:100\n"
+  ))
+  .expected_exit_code(1)
+  .run();
+}
+
+#[test]
+fn inscribe_does_not_pick_rare_sats() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--chain regtest --index-sats wallet inscribe --file hello.txt")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: wallet contains no cardinal utxos\n")
+    .expected_exit_code(1)
+    .run();
+
+  CommandBuilder::new(
+    "--chain regtest --index-sats wallet inscribe --file hello.txt --protect-rarity rare",
+  )
+  .write("hello.txt", "HELLOWORLD")
+  .rpc_server(&rpc_server)
+  .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+}
+
+#[test]
+fn protecting_sats_requires_sat_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet send {txid}:0:1 bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x --protect-rarity rare"
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stderr("error: protecting sats requires index created with `--index-sats` flag\n")
+  .expected_exit_code(1)
+  .run();
+}
+
+#[test]
+fn send_batch() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(2);
+
+  let mut inscriptions = Vec::new();
+
+  for _ in 0..2 {
+    let stdout = CommandBuilder::new("--chain signet wallet inscribe --file hello.txt")
+      .write("hello.txt", "HELLOWORLD")
+      .rpc_server(&rpc_server)
+      .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+      .stderr_regex(FEE_REPORT)
+      .run();
+
+    inscriptions.push(reveal_txid_from_inscribe_stdout(&stdout));
+
+    rpc_server.mine_blocks(1);
+  }
+
+  let txid = CommandBuilder::new("--chain signet wallet send-batch batch.tsv")
+    .write(
+      "batch.tsv",
+      format!(
+        "# airdrop\n{}\ttb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw\n{}\ttb1q5r9tdfu4tn4xrul5u68fqjllr2350t9gqh39wz\n",
+        inscriptions[0], inscriptions[1],
+      ),
+    )
+    .rpc_server(&rpc_server)
+    .stdout_regex("[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT).run();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(format!("{}\n", tx.txid()), txid);
+  assert_eq!(tx.input.len(), 2);
+
+  let addresses = tx
+    .output
+    .iter()
+    .map(|tx_out| {
+      Address::from_script(&tx_out.script_pubkey, Network::Signet)
+        .unwrap()
+        .to_string()
+    })
+    .collect::<Vec<String>>();
+
+  assert!(addresses.contains(&"tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw".to_string()));
+  assert!(addresses.contains(&"tb1q5r9tdfu4tn4xrul5u68fqjllr2350t9gqh39wz".to_string()));
+
+  rpc_server.mine_blocks(1);
+
+  let ord_server = TestServer::spawn_with_args(&rpc_server, &[]);
+
+  for inscription in inscriptions {
+    ord_server.assert_response_regex(
+      &format!("/inscription/{inscription}"),
+      &format!(
+        ".*<h1>Inscription {inscription}</h1>.*<dt>location</dt>.*<dd class=monospace>{}:[[:digit:]]+:0</dd>.*",
+        txid.trim()
+      ),
+    );
+  }
+}
+
+#[test]
+fn send_batch_rejects_malformed_lines() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+
+  CommandBuilder::new("--chain signet wallet send-batch batch.tsv")
+    .write("batch.tsv", "foo\n")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: expected inscription ID or satpoint and address on line 1\n")
+    .expected_exit_code(1)
+    .run();
+}
+
+#[test]
+fn send_amount_does_not_spend_inscriptions() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new("--chain signet wallet inscribe --file hello.txt")
+    .write("hello.txt", "HELLOWORLD")
+    .rpc_server(&rpc_server)
+    .stdout_regex("commit\t[[:xdigit:]]{64}\nreveal\t[[:xdigit:]]{64}\n")
+    .stderr_regex(FEE_REPORT)
+    .run();
+
+  let reveal_txid = reveal_txid_from_inscribe_stdout(&stdout);
+
+  rpc_server.mine_blocks(1);
+
+  let txid = CommandBuilder::new(
+    "--chain signet wallet send 5000sat tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw",
+  )
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(format!("{}\n", tx.txid()), txid);
+
+  assert!(tx
+    .input
+    .iter()
+    .all(|tx_in| tx_in.previous_output.txid != reveal_txid));
+
+  assert_eq!(tx.output[0].value, 5000);
+  assert_eq!(
+    Address::from_script(&tx.output[0].script_pubkey, Network::Signet)
+      .unwrap()
+      .to_string(),
+    "tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw"
+  );
+  assert_eq!(tx.output.len(), 2);
+}
+
+#[test]
+fn send_amount_does_not_take_postage() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Signet, "ord");
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(
+    "--chain signet wallet send 5000sat tb1qx4gf3ya0cxfcwydpq8vr2lhrysneuj5d7lqatw --postage 1000sat",
+  )
+  .rpc_server(&rpc_server)
+  .expected_stderr("error: --postage may only be used when sending a sat or inscription\n")
+  .expected_exit_code(1)
+  .run();
+}
+
+#[test]
+fn extract() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let stdout = CommandBuilder::new(format!(
+    "--chain regtest --index-sats wallet extract {}",
+    50 * COIN_VALUE + 1_000_000
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(format!("{}\n", tx.txid()), stdout);
+  assert_eq!(tx.input.len(), 1);
+  assert_eq!(tx.input[0].previous_output, OutPoint::new(txid, 0));
+  assert_eq!(tx.output.len(), 3);
+  assert_eq!(tx.output[0].value, 1_000_000);
+  assert_eq!(tx.output[1].value, 10_000);
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-sats find {}",
+    50 * COIN_VALUE + 1_000_000
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stdout(format!("{}:1:0\n", tx.txid()))
+  .run();
+}
+
+#[test]
+fn extract_returns_protected_sats_after_target_to_change_output() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new(format!(
+    "--chain regtest --index-sats wallet extract {} --protected-sats protected.tsv",
+    50 * COIN_VALUE + 1_000_000
+  ))
+  .write(
+    "protected.tsv",
+    format!("{}\tmy favorite sat\n", 50 * COIN_VALUE + 1_005_000),
+  )
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(format!("{}\n", tx.txid()), stdout);
+  assert_eq!(tx.output.len(), 3);
+  assert_eq!(tx.output[0].value, 1_000_000);
+  assert_eq!(tx.output[1].value, 5_000);
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-sats find {}",
+    50 * COIN_VALUE + 1_005_000
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stdout(format!("{}:2:0\n", tx.txid()))
+  .run();
+}
+
+#[test]
+fn extract_refuses_sat_already_at_start_of_postage_sized_output() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+  let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let stdout = CommandBuilder::new(format!(
+    "--chain regtest --index-sats wallet extract {txid}:0:1000000"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--chain regtest --index-sats wallet extract {}:1:0",
+    stdout.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stderr(format!(
+    "error: sat at {}:1:0 is already at the start of an output of at most 0.0001 BTC\n",
+    stdout.trim()
+  ))
+  .expected_exit_code(1)
+  .run();
+}
+
+#[test]
+fn offer_create_and_accept() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let inscription_id = create_inscription(&rpc_server, "foo.txt");
+
+  rpc_server.mine_blocks(3);
+
+  let psbt = CommandBuilder::new(format!(
+    "--chain regtest wallet offer create {inscription_id} --price 1btc"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .run();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet offer accept {} --inscription {inscription_id} --price 2btc",
+    psbt.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr("error: offer price of 1 BTC does not match expected price of 2 BTC\n")
+  .run();
+
+  let stdout = CommandBuilder::new(format!(
+    "--chain regtest wallet offer accept {} --inscription {inscription_id} --price 1btc",
+    psbt.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  let tx = rpc_server.mempool()[0].clone();
+  assert_eq!(format!("{}\n", tx.txid()), stdout);
+
+  assert_eq!(tx.input[2].previous_output.txid, inscription_id);
+  assert_eq!(tx.output[1].value, 10_000);
+  assert_eq!(tx.output[2].value, COIN_VALUE + 10_000);
+
+  rpc_server.mine_blocks(1);
+
+  TestServer::spawn_with_args(&rpc_server, &[]).assert_response_regex(
+    &format!("/inscription/{inscription_id}"),
+    &format!(
+      ".*<dt>location</dt>.*<dd class=monospace>{}:[[:digit:]]+:0</dd>.*",
+      tx.txid()
+    ),
+  );
+}
+
+#[test]
+fn offer_accept_checks_inscription() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let inscription_id = create_inscription(&rpc_server, "foo.txt");
+  let other_inscription_id = create_inscription(&rpc_server, "bar.txt");
+
+  let psbt = CommandBuilder::new(format!(
+    "--chain regtest wallet offer create {inscription_id} --price 1btc"
+  ))
+  .rpc_server(&rpc_server)
+  .stdout_regex(".*")
+  .run();
+
+  CommandBuilder::new(format!(
+    "--chain regtest wallet offer accept {} --inscription {other_inscription_id} --price 1btc",
+    psbt.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: offer does not sell inscription {other_inscription_id}\n"
+  ))
+  .run();
+}
+
+#[test]
+fn sign_and_verify() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let inscription_id = create_inscription(&rpc_server, "foo.txt");
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let signature = CommandBuilder::new(format!(
+    "--chain regtest wallet sign --inscription {inscription_id} --message hello"
+  ))
+  .rpc_server(&rpc_server)
+  .temp_dir(tempdir.clone())
+  .stdout_regex(".*")
+  .run();
+
+  CommandBuilder::new(format!(
+    "--chain regtest verify --inscription {inscription_id} --message hello --signature {}",
+    signature.trim()
+  ))
+  .temp_dir(tempdir.clone())
+  .run();
+
+  CommandBuilder::new(format!(
+    "--chain regtest verify --inscription {inscription_id} --message goodbye --signature {}",
+    signature.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .temp_dir(tempdir.clone())
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: signature does not prove ownership of inscription {inscription_id} in output {inscription_id}:0\nbecause: signature verification failed\n"
+  ))
+  .run();
+}
+
+#[test]
+fn verify_checks_current_satpoint() {
+  let rpc_server = test_bitcoincore_rpc::spawn_with(Network::Regtest, "ord");
+
+  let inscription_id = create_inscription(&rpc_server, "foo.txt");
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  let signature = CommandBuilder::new(format!(
+    "--chain regtest wallet sign --inscription {inscription_id} --message hello"
+  ))
+  .rpc_server(&rpc_server)
+  .temp_dir(tempdir.clone())
+  .stdout_regex(".*")
+  .run();
+
+  let send_txid = CommandBuilder::new(format!(
+    "--chain regtest wallet send {inscription_id} bcrt1q6rhpng9evdsfnn833a4f4vej0asu6dk5srld6x"
+  ))
+  .rpc_server(&rpc_server)
+  .temp_dir(tempdir.clone())
+  .stdout_regex("[[:xdigit:]]{64}\n")
+  .stderr_regex(FEE_REPORT)
+  .run();
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!(
+    "--chain regtest verify --inscription {inscription_id} --message hello --signature {}",
+    signature.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .temp_dir(tempdir.clone())
+  .run();
+
+  CommandBuilder::new("--chain regtest index")
+    .rpc_server(&rpc_server)
+    .temp_dir(tempdir.clone())
+    .run();
+
+  CommandBuilder::new(format!(
+    "--chain regtest verify --inscription {inscription_id} --message hello --signature {}",
+    signature.trim()
+  ))
+  .rpc_server(&rpc_server)
+  .temp_dir(tempdir.clone())
+  .expected_exit_code(1)
+  .expected_stderr(format!(
+    "error: signature does not prove ownership of inscription {inscription_id} in output {}:0\nbecause: P2WPKH signature must have two witness elements\n",
+    send_txid.trim()
+  ))
+  .run();
+}